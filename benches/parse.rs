@@ -0,0 +1,57 @@
+//! Throughput of the input parser for large inputs.
+//!
+//! The throughput has to be the same for all sizes, the parsing time grows linearly with the
+//! input.
+
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use crossterm::event::InputParser;
+
+const SIZES: [usize; 3] = [64 * 1024, 1024 * 1024, 4 * 1024 * 1024];
+
+// The event sources read at most 1_024 bytes at once.
+const CHUNK_SIZE: usize = 1_024;
+
+fn text(len: usize) -> Vec<u8> {
+    b"The quick brown fox jumps over the lazy dog.\r\n"
+        .iter()
+        .copied()
+        .cycle()
+        .take(len)
+        .collect()
+}
+
+/// Feeds the input in chunks like the event sources do, and returns the number of events.
+fn parse(input: &[u8]) -> usize {
+    let mut parser = InputParser::new();
+    let mut chunks = input.chunks(CHUNK_SIZE).peekable();
+    while let Some(chunk) = chunks.next() {
+        parser.advance(chunk, chunks.peek().is_some());
+    }
+    parser.count()
+}
+
+fn bench_input(c: &mut Criterion, name: &str, input: impl Fn(usize) -> Vec<u8>) {
+    let mut group = c.benchmark_group(name);
+    group.sample_size(10);
+    for size in SIZES {
+        let input = input(size);
+        group.throughput(Throughput::Bytes(input.len() as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(size), &input, |b, input| {
+            b.iter(|| parse(input))
+        });
+    }
+    group.finish();
+}
+
+fn bench_parse(c: &mut Criterion) {
+    bench_input(c, "bracketed_paste", |size| {
+        [b"\x1B[200~".as_slice(), &text(size), b"\x1B[201~"].concat()
+    });
+    bench_input(c, "operating_system_command", |size| {
+        [b"\x1B]52;c;".as_slice(), &vec![b'A'; size], b"\x1B\\"].concat()
+    });
+    bench_input(c, "key_presses", text);
+}
+
+criterion_group!(benches, bench_parse);
+criterion_main!(benches);
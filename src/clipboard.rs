@@ -5,11 +5,21 @@
 //! ## Implemented operations:
 //!
 //! - Copy: [`CopyToClipboard`](struct.CopyToClipboard.html)
+//! - Paste: [`read_clipboard`](fn.read_clipboard.html) (requires
+//!   [`feature = "events"`](../index.html#optional-features))
 use base64::prelude::{BASE64_STANDARD, Engine};
 
 use std::fmt;
+#[cfg(feature = "events")]
+use std::io;
 use std::str::FromStr;
+#[cfg(feature = "events")]
+use std::time::Duration;
 
+#[cfg(feature = "events")]
+use crate::event::{filter::ClipboardContentFilter, internal::InternalEvent};
+#[cfg(feature = "events")]
+use crate::terminal::query;
 use crate::{Command, osc};
 
 /// Different clipboard types
@@ -206,6 +216,55 @@ impl<T: AsRef<[u8]>> CopyToClipboard<T> {
     }
 }
 
+/// Reads the content of a clipboard from the terminal host.
+///
+/// This uses OSC control sequence `Pr = 5 2` with the data `?` (See
+/// [XTerm Control Sequences](https://invisible-island.net/xterm/ctlseqs/ctlseqs.html#h3-Operating-System-Commands) ),
+/// the terminal answers with the base64 encoded clipboard content.
+///
+/// Most terminal emulators don't allow reading the clipboard by default, or ask the user for
+/// permission first. Those which refuse usually don't answer at all, so an error of the kind
+/// [`io::ErrorKind::TimedOut`] is returned if no content arrived within the timeout. Choose a
+/// timeout leaving the user time to answer a permission prompt.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::{str::FromStr, time::Duration};
+/// use crossterm::clipboard::{ClipboardSelection, read_clipboard};
+///
+/// let selection = ClipboardSelection::from_str("c").unwrap();
+/// match read_clipboard(&selection, Duration::from_secs(5)) {
+///     Ok(text) => println!("Pasting {text:?}"),
+///     Err(error) => eprintln!("The clipboard can't be read: {error}"),
+/// }
+/// ```
+///
+/// # Notes
+///
+/// * Raw mode is enabled while the clipboard is read, if it isn't enabled already.
+/// * Only the first clipboard of the selection is read by most terminal emulators.
+/// * An error of the kind [`io::ErrorKind::InvalidData`] is returned if the content isn't UTF-8.
+/// * Reading the clipboard is only supported on UNIX systems, on Windows an error is returned.
+/// * On UNIX systems, this function will block and possibly time out while
+///   [`crossterm::event::read`](crate::event::read) or
+///   [`crossterm::event::poll`](crate::event::poll) are being called.
+#[cfg(feature = "events")]
+pub fn read_clipboard(selection: &ClipboardSelection, timeout: Duration) -> io::Result<String> {
+    let request = format!(
+        osc!("52;{selection};?"),
+        selection = selection.to_osc52_pc()
+    );
+    match query::execute(request.as_bytes(), &ClipboardContentFilter, timeout, false)? {
+        Some(InternalEvent::ClipboardContent(content)) => String::from_utf8(content)
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error)),
+        _ => Err(io::Error::new(
+            io::ErrorKind::TimedOut,
+            "The clipboard could not be read within the timeout",
+        )),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
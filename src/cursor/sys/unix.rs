@@ -1,14 +1,11 @@
 use std::{
-    io::{self, Error, Write},
+    io::{self, Error},
     time::Duration,
 };
 
 use crate::{
-    event::{
-        filter::CursorPositionFilter,
-        internal::{self, InternalEvent},
-    },
-    terminal::{disable_raw_mode, enable_raw_mode, sys::is_raw_mode_enabled},
+    event::{filter::CursorPositionFilter, internal::InternalEvent},
+    terminal::query::{self, DefaultTerminal, Terminal},
 };
 
 /// Returns the cursor position (column, row).
@@ -18,48 +15,22 @@ use crate::{
 /// On unix systems, this function will block and possibly time out while
 /// [`crossterm::event::read`](crate::event::read) or [`crossterm::event::poll`](crate::event::poll) are being called.
 pub fn position() -> io::Result<(u16, u16)> {
-    if is_raw_mode_enabled() {
-        read_position_raw()
-    } else {
-        read_position()
-    }
-}
-
-fn read_position() -> io::Result<(u16, u16)> {
-    enable_raw_mode()?;
-    let pos = read_position_raw();
-    disable_raw_mode()?;
-    pos
+    position_of(&DefaultTerminal)
 }
 
-fn read_position_raw() -> io::Result<(u16, u16)> {
-    // Discard any buffered cursor-position replies from earlier `ESC[6n` requests so the
-    // position returned below corresponds to the fresh request we are about to send.
-    // Poll with a zero timeout to drain only already-available events without blocking.
-    while let Ok(true) = internal::poll(Some(Duration::ZERO), &CursorPositionFilter) {
-        let _ = internal::read(&CursorPositionFilter);
-    }
-
+/// Returns the cursor position (column, row) of the given terminal.
+pub(crate) fn position_of(terminal: &impl Terminal) -> io::Result<(u16, u16)> {
     // Use `ESC [ 6 n` to and retrieve the cursor position.
-    let mut stdout = io::stdout();
-    stdout.write_all(b"\x1B[6n")?;
-    stdout.flush()?;
-
-    loop {
-        match internal::poll(Some(Duration::from_millis(2000)), &CursorPositionFilter) {
-            Ok(true) => {
-                if let Ok(InternalEvent::CursorPosition(x, y)) =
-                    internal::read(&CursorPositionFilter)
-                {
-                    return Ok((x, y));
-                }
-            }
-            Ok(false) => {
-                return Err(Error::other(
-                    "The cursor position could not be read within a normal duration",
-                ));
-            }
-            Err(_) => {}
-        }
+    match query::execute_on(
+        terminal,
+        b"\x1B[6n",
+        &CursorPositionFilter,
+        Duration::from_millis(2000),
+        false,
+    )? {
+        Some(InternalEvent::CursorPosition(x, y)) => Ok((x, y)),
+        _ => Err(Error::other(
+            "The cursor position could not be read within a normal duration",
+        )),
     }
 }
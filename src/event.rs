@@ -23,12 +23,18 @@
 //! [`EnableMouseCapture`](struct.EnableMouseCapture.html) / [`EnableFocusChange`](struct.EnableFocusChange.html) command.
 //! See [Command API](../index.html#command-api) for more information.
 //!
+//! ## Input From Other Sources
+//!
+//! The [`InputParser`](struct.InputParser.html) decodes terminal input that doesn't come from the
+//! local terminal, e.g. the input of a remote client of an SSH server. Unlike the functions above,
+//! it doesn't require the `events` feature.
+//!
 //! ## Examples
 //!
 //! Blocking read:
 //!
 //! ```no_run
-//! #![cfg(feature = "bracketed-paste")]
+//! # #[cfg(all(feature = "events", feature = "bracketed-paste"))]
 //! use crossterm::{
 //!     event::{
 //!         read, DisableBracketedPaste, DisableFocusChange, DisableMouseCapture, EnableBracketedPaste,
@@ -37,6 +43,7 @@
 //!     execute,
 //! };
 //!
+//! # #[cfg(all(feature = "events", feature = "bracketed-paste"))]
 //! fn print_events() -> std::io::Result<()> {
 //!     execute!(
 //!          std::io::stdout(),
@@ -54,6 +61,7 @@
 //!             #[cfg(feature = "bracketed-paste")]
 //!             Event::Paste(data) => println!("{:?}", data),
 //!             Event::Resize(width, height) => println!("New size {}x{}", width, height),
+//!             _ => {}
 //!         }
 //!     }
 //!     execute!(
@@ -69,9 +77,9 @@
 //! Non-blocking read:
 //!
 //! ```no_run
-//! #![cfg(feature = "bracketed-paste")]
 //! use std::{time::Duration, io};
 //!
+//! # #[cfg(all(feature = "events", feature = "bracketed-paste"))]
 //! use crossterm::{
 //!     event::{
 //!         poll, read, DisableBracketedPaste, DisableFocusChange, DisableMouseCapture,
@@ -80,6 +88,7 @@
 //!     execute,
 //! };
 //!
+//! # #[cfg(all(feature = "events", feature = "bracketed-paste"))]
 //! fn print_events() -> io::Result<()> {
 //!     execute!(
 //!          std::io::stdout(),
@@ -100,6 +109,7 @@
 //!                 #[cfg(feature = "bracketed-paste")]
 //!                 Event::Paste(data) => println!("Pasted {:?}", data),
 //!                 Event::Resize(width, height) => println!("New size {}x{}", width, height),
+//!                 _ => {}
 //!             }
 //!         } else {
 //!             // Timeout expired and no `Event` is available
@@ -118,25 +128,38 @@
 //! Check the [examples](https://github.com/crossterm-rs/crossterm/tree/master/examples) folder for more of
 //! them (`event-*`).
 
+mod click;
+pub(crate) mod encode;
+#[cfg(feature = "events")]
 pub(crate) mod filter;
 pub(crate) mod internal;
+pub(crate) mod parse;
+#[cfg(feature = "events")]
 pub(crate) mod read;
+#[cfg(feature = "events")]
 pub(crate) mod source;
 #[cfg(feature = "event-stream")]
 pub(crate) mod stream;
+#[cfg(feature = "events")]
 pub(crate) mod sys;
+#[cfg(feature = "events")]
 pub(crate) mod timeout;
 
+pub use click::ClickTracker;
 #[cfg(feature = "derive-more")]
 use derive_more::derive::IsVariant;
+pub use encode::{KeyEncoding, MouseEncoding, encode_paste};
+pub use parse::InputParser;
+#[cfg(feature = "events")]
+pub use source::{EventSource, fake::FakeTerminal};
 #[cfg(feature = "event-stream")]
 pub use stream::EventStream;
 
-use crate::{
-    Command, csi,
-    event::{filter::EventFilter, internal::InternalEvent},
-};
+#[cfg(feature = "events")]
+use crate::event::{filter::EventFilter, source::CustomEventSource};
+use crate::{Command, csi};
 use std::fmt::{self, Display};
+#[cfg(feature = "events")]
 use std::time::Duration;
 
 use bitflags::bitflags;
@@ -181,6 +204,7 @@ use std::hash::{Hash, Hasher};
 ///     poll(Duration::from_millis(100))
 /// }
 /// ```
+#[cfg(feature = "events")]
 pub fn poll(timeout: Duration) -> std::io::Result<bool> {
     internal::poll(Some(timeout), &EventFilter)
 }
@@ -226,11 +250,11 @@ pub fn poll(timeout: Duration) -> std::io::Result<bool> {
 ///     }
 /// }
 /// ```
+#[cfg(feature = "events")]
 pub fn read() -> std::io::Result<Event> {
-    match internal::read(&EventFilter)? {
-        InternalEvent::Event(event) => Ok(event),
-        #[cfg(unix)]
-        _ => unreachable!(),
+    match internal::read(&EventFilter)?.into_event() {
+        Some(event) => Ok(event),
+        None => unreachable!(),
     }
 }
 
@@ -255,21 +279,175 @@ pub fn read() -> std::io::Result<Event> {
 ///     }
 /// }
 /// ```
+#[cfg(feature = "events")]
 pub fn try_read() -> Option<Event> {
     match internal::try_read(&EventFilter) {
-        Some(InternalEvent::Event(event)) => Some(event),
+        Some(event) => match event.into_event() {
+            Some(event) => Some(event),
+            None => unreachable!(),
+        },
         None => None,
-        #[cfg(unix)]
-        _ => unreachable!(),
     }
 }
 
+/// Replaces the terminal with the given [`EventSource`] as the input of [`read`], [`poll`] and
+/// [`try_read`].
+///
+/// This is mainly useful for testing input handling without a terminal. Events which were read
+/// from the previous source, but not consumed yet, are discarded. Use [`reset_event_source`] to
+/// read from the terminal again.
+///
+/// *Note*: the source is replaced as soon as no other thread is reading events, so this function
+/// blocks while another thread waits in [`read`] or [`poll`].
+///
+/// # Examples
+///
+/// ```
+/// use std::{io, time::Duration};
+///
+/// use crossterm::event::{self, Event, EventSource, KeyCode};
+///
+/// struct Keys(Vec<KeyCode>);
+///
+/// impl EventSource for Keys {
+///     fn try_read(&mut self, _timeout: Option<Duration>) -> io::Result<Option<Event>> {
+///         Ok(self.0.pop().map(|code| Event::Key(code.into())))
+///     }
+/// }
+///
+/// event::set_event_source(Keys(vec![KeyCode::Enter]));
+/// assert_eq!(event::read()?, Event::Key(KeyCode::Enter.into()));
+/// assert!(!event::poll(Duration::ZERO)?);
+///
+/// event::reset_event_source();
+/// # io::Result::Ok(())
+/// ```
+#[cfg(feature = "events")]
+pub fn set_event_source<S: EventSource + 'static>(source: S) {
+    internal::replace_event_source(Some(Box::new(CustomEventSource(Box::new(source)))));
+}
+
+/// Restores the terminal as the input of [`read`], [`poll`] and [`try_read`] after
+/// [`set_event_source`] or [`FakeTerminal::install`].
+///
+/// Events which were read from the previous source, but not consumed yet, are discarded.
+#[cfg(feature = "events")]
+pub fn reset_event_source() {
+    internal::replace_event_source(None);
+}
+
+/// Sets whether [`read`], [`poll`] and [`try_read`] report input which can't be decoded as
+/// [`Event::Unknown`] instead of dropping it, which is the default.
+///
+/// This includes malformed or unsupported escape sequences, invalid UTF-8 and control sequences
+/// which are neither a known event nor the response to a query. Use it to implement custom key
+/// mappings or to find out which sequences a terminal sends.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::io;
+/// use crossterm::event::{self, Event};
+///
+/// fn main() -> io::Result<()> {
+///     event::set_report_unknown(true);
+///     if let Event::Unknown(bytes) = event::read()? {
+///         println!("Unsupported sequence: {}", bytes.escape_ascii());
+///     }
+///     Ok(())
+/// }
+/// ```
+///
+/// # Notes
+///
+/// * Only the terminal input on UNIX systems contains undecodable sequences, the events of
+///   Windows consoles and custom [`EventSource`]s are always known.
+/// * A late response to a query that already timed out is reported as unknown too.
+#[cfg(feature = "events")]
+pub fn set_report_unknown(enabled: bool) {
+    parse::set_report_unknown(enabled);
+}
+
+/// Sets whether [`read`], [`poll`] and [`try_read`] decode mouse positions as pixels, because the
+/// terminal was switched to pixel reporting with [`EnableMousePixelReporting`] or
+/// [`MouseCaptureEncoding::SgrPixels`]. Disabled by default.
+///
+/// The `column` and `row` of the [`MouseEvent`]s are derived from the pixels and the cell size of
+/// the terminal.
+#[cfg(feature = "events")]
+pub fn set_mouse_pixel_reporting(enabled: bool) {
+    parse::set_mouse_pixel_reporting(enabled);
+}
+
+/// Sets how long [`read`], [`poll`] and [`try_read`] wait for the rest of an escape sequence
+/// before a lone `ESC` is reported as [`KeyCode::Esc`], like vim's `ttimeoutlen`.
+///
+/// Keys like Alt+key or the arrow keys are sent as escape sequences starting with `ESC`. By
+/// default (a timeout of zero), an `ESC` which is the last byte read from the terminal is the Esc
+/// key. Over slow connections, e.g. SSH, a sequence can be split across reads though, which turns
+/// it into the Esc key followed by the rest of the sequence. A timeout of e.g. 50 milliseconds
+/// keeps such sequences intact, at the cost of delaying the Esc key by that time.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::time::Duration;
+/// use crossterm::event;
+///
+/// event::set_escape_timeout(Duration::from_millis(50));
+/// ```
+///
+/// # Notes
+///
+/// * The timeout only applies to the terminal input on UNIX systems, the Windows console reports
+///   keys instead of escape sequences.
+#[cfg(feature = "events")]
+pub fn set_escape_timeout(timeout: Duration) {
+    #[cfg(unix)]
+    source::unix::set_escape_timeout(timeout);
+    #[cfg(windows)]
+    let _ = timeout;
+}
+
+/// Sets how [`read`], [`poll`] and [`try_read`] report bracketed pastes, see [`PasteMode`].
+///
+/// By default, a paste is reported as a whole once it ended, which keeps all of it in memory
+/// and delays the event until the end arrived. [`PasteMode::Chunked`] reports the text as it's
+/// read instead, and can limit how much of a paste is kept.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::io;
+/// use crossterm::event::{self, Event, PasteMode};
+///
+/// fn main() -> io::Result<()> {
+///     event::set_paste_mode(PasteMode::Chunked {
+///         max_size: Some(1024 * 1024),
+///     });
+///
+///     let mut text = String::new();
+///     loop {
+///         match event::read()? {
+///             Event::PasteStart => text.clear(),
+///             Event::PasteData(data) => text.push_str(&data),
+///             Event::PasteEnd { truncated } => {
+///                 println!("Pasted {} bytes, truncated: {truncated}", text.len());
+///             }
+///             _ => {}
+///         }
+///     }
+/// }
+/// ```
+#[cfg(all(feature = "events", feature = "bracketed-paste"))]
+pub fn set_paste_mode(mode: PasteMode) {
+    parse::set_paste_mode(mode);
+}
+
 bitflags! {
     /// Represents special flags that tell compatible terminals to add extra information to keyboard events.
     ///
     /// See <https://sw.kovidgoyal.net/kitty/keyboard-protocol/#progressive-enhancement> for more information.
-    ///
-    /// Alternate keys and Unicode codepoints are not yet supported by crossterm.
     #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(transparent))]
     #[derive(Debug, PartialOrd, PartialEq, Eq, Clone, Copy, Hash)]
     pub struct KeyboardEnhancementFlags: u8 {
@@ -280,22 +458,24 @@ bitflags! {
         /// [`KeyEventKind::Release`] when keys are autorepeated or released.
         const REPORT_EVENT_TYPES = 0b0000_0010;
         /// Send [alternate keycodes](https://sw.kovidgoyal.net/kitty/keyboard-protocol/#key-codes)
-        /// in addition to the base keycode. The alternate keycode overrides the base keycode in
-        /// resulting `KeyEvent`s.
+        /// in addition to the base keycode. The shifted keycode overrides the base keycode in
+        /// resulting `KeyEvent`s, all of them are available in [`KeyEvent::alternate_keys`].
         const REPORT_ALTERNATE_KEYS = 0b0000_0100;
         /// Represent all keyboard events as CSI-u sequences. This is required to get repeat/release
         /// events for plain-text keys.
         const REPORT_ALL_KEYS_AS_ESCAPE_CODES = 0b0000_1000;
-        // Send the Unicode codepoint as well as the keycode.
-        //
-        // *Note*: this is not yet supported by crossterm.
-        // const REPORT_ASSOCIATED_TEXT = 0b0001_0000;
+        /// Send the text produced by a key as well as the keycode, see [`KeyEvent::text`]. This has
+        /// no effect unless [`REPORT_ALL_KEYS_AS_ESCAPE_CODES`](Self::REPORT_ALL_KEYS_AS_ESCAPE_CODES)
+        /// is enabled too.
+        const REPORT_ASSOCIATED_TEXT = 0b0001_0000;
     }
 }
 
 /// A command that enables mouse event capturing.
 ///
 /// Mouse events can be captured with [read](./fn.read.html)/[poll](./fn.poll.html).
+///
+/// All mouse motion is reported, use [`EnableMouseCaptureWith`] to choose the reported events.
 #[cfg(feature = "events")]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct EnableMouseCapture;
@@ -331,9 +511,11 @@ impl Command for EnableMouseCapture {
 /// A command that disables mouse event capturing.
 ///
 /// Mouse events can be captured with [read](./fn.read.html)/[poll](./fn.poll.html).
+#[cfg(feature = "events")]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct DisableMouseCapture;
 
+#[cfg(feature = "events")]
 impl Command for DisableMouseCapture {
     fn write_ansi(&self, f: &mut impl fmt::Write) -> fmt::Result {
         f.write_str(concat!(
@@ -357,6 +539,286 @@ impl Command for DisableMouseCapture {
     }
 }
 
+/// Which mouse events the terminal reports, see [`MouseCapture`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MouseTracking {
+    /// Report button presses only (mode 9).
+    X10,
+    /// Report button presses and releases (mode 1000).
+    Normal,
+    /// Report button presses, releases and motion while a button is pressed (mode 1002).
+    ButtonEvent,
+    /// Report button presses, releases and all motion (mode 1003).
+    AnyEvent,
+}
+
+/// How the terminal encodes mouse events, see [`MouseCapture`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MouseCaptureEncoding {
+    /// The default encoding, which is limited to 223 columns and rows.
+    X10,
+    /// The urxvt encoding (mode 1015).
+    Urxvt,
+    /// The SGR encoding (mode 1006), which also reports which button was released.
+    Sgr,
+    /// The SGR encoding with positions in pixels (mode 1016), see [`EnableMousePixelReporting`]
+    /// and [`set_mouse_pixel_reporting`].
+    SgrPixels,
+}
+
+/// The mouse capture enabled by [`EnableMouseCaptureWith`] and disabled by
+/// [`DisableMouseCaptureWith`].
+///
+/// The default reports button presses and releases with the SGR encoding.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::io;
+/// # #[cfg(feature = "events")]
+/// use crossterm::{
+///     event::{DisableMouseCaptureWith, EnableMouseCaptureWith, MouseCapture, MouseTracking},
+///     execute,
+/// };
+///
+/// # #[cfg(feature = "events")]
+/// fn main() -> io::Result<()> {
+///     // Report dragging, but not every mouse movement.
+///     let capture = MouseCapture::new().tracking(MouseTracking::ButtonEvent);
+///
+///     execute!(io::stdout(), EnableMouseCaptureWith(capture))?;
+///     // ...
+///     execute!(io::stdout(), DisableMouseCaptureWith(capture))
+/// }
+/// # #[cfg(not(feature = "events"))]
+/// # fn main() {}
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MouseCapture {
+    tracking: MouseTracking,
+    encoding: MouseCaptureEncoding,
+    alternate_scroll: bool,
+}
+
+impl Default for MouseCapture {
+    fn default() -> Self {
+        MouseCapture {
+            tracking: MouseTracking::Normal,
+            encoding: MouseCaptureEncoding::Sgr,
+            alternate_scroll: false,
+        }
+    }
+}
+
+impl MouseCapture {
+    /// Creates the default mouse capture.
+    pub fn new() -> MouseCapture {
+        MouseCapture::default()
+    }
+
+    /// Sets which mouse events are reported.
+    pub fn tracking(mut self, tracking: MouseTracking) -> MouseCapture {
+        self.tracking = tracking;
+        self
+    }
+
+    /// Sets how mouse events are encoded.
+    pub fn encoding(mut self, encoding: MouseCaptureEncoding) -> MouseCapture {
+        self.encoding = encoding;
+        self
+    }
+
+    /// Sets whether the mouse wheel sends arrow keys while the alternate screen is active and the
+    /// mouse isn't captured (mode 1007).
+    pub fn alternate_scroll(mut self, alternate_scroll: bool) -> MouseCapture {
+        self.alternate_scroll = alternate_scroll;
+        self
+    }
+
+    /// Returns the private modes to set, in order.
+    #[cfg(feature = "events")]
+    fn modes(&self) -> impl DoubleEndedIterator<Item = u16> {
+        // Lower tracking levels are set too, for terminals which don't know the higher ones.
+        let tracking: &[u16] = match self.tracking {
+            MouseTracking::X10 => &[9],
+            MouseTracking::Normal => &[1000],
+            MouseTracking::ButtonEvent => &[1000, 1002],
+            MouseTracking::AnyEvent => &[1000, 1002, 1003],
+        };
+        let encoding = match self.encoding {
+            MouseCaptureEncoding::X10 => None,
+            MouseCaptureEncoding::Urxvt => Some(1015),
+            MouseCaptureEncoding::Sgr => Some(1006),
+            MouseCaptureEncoding::SgrPixels => Some(1016),
+        };
+        let alternate_scroll = self.alternate_scroll.then_some(1007);
+
+        tracking
+            .iter()
+            .copied()
+            .chain(encoding)
+            .chain(alternate_scroll)
+    }
+}
+
+/// A command that enables mouse event capturing with the given tracking mode and encoding.
+///
+/// Mouse events can be captured with [read](./fn.read.html)/[poll](./fn.poll.html). Unlike
+/// [`EnableMouseCapture`], which reports all mouse motion, the reported events can be limited.
+///
+/// # Notes
+///
+/// * On Windows, all mouse events are reported regardless of the mouse capture.
+/// * Commands must be executed/queued for execution otherwise they do nothing.
+#[cfg(feature = "events")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EnableMouseCaptureWith(pub MouseCapture);
+
+#[cfg(feature = "events")]
+impl Command for EnableMouseCaptureWith {
+    fn write_ansi(&self, f: &mut impl fmt::Write) -> fmt::Result {
+        for mode in self.0.modes() {
+            write!(f, csi!("?{}h"), mode)?;
+        }
+        Ok(())
+    }
+
+    #[cfg(windows)]
+    fn execute_winapi(&self) -> std::io::Result<()> {
+        sys::windows::enable_mouse_capture()
+    }
+
+    #[cfg(windows)]
+    fn is_ansi_code_supported(&self) -> bool {
+        false
+    }
+}
+
+/// A command that disables the mouse event capturing enabled by [`EnableMouseCaptureWith`].
+///
+/// The same [`MouseCapture`] has to be passed, so exactly the enabled modes are reset.
+///
+/// # Notes
+///
+/// Commands must be executed/queued for execution otherwise they do nothing.
+#[cfg(feature = "events")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DisableMouseCaptureWith(pub MouseCapture);
+
+#[cfg(feature = "events")]
+impl Command for DisableMouseCaptureWith {
+    fn write_ansi(&self, f: &mut impl fmt::Write) -> fmt::Result {
+        // The modes are reset in reverse order.
+        for mode in self.0.modes().rev() {
+            write!(f, csi!("?{}l"), mode)?;
+        }
+        Ok(())
+    }
+
+    #[cfg(windows)]
+    fn execute_winapi(&self) -> std::io::Result<()> {
+        sys::windows::disable_mouse_capture()
+    }
+
+    #[cfg(windows)]
+    fn is_ansi_code_supported(&self) -> bool {
+        false
+    }
+}
+
+/// A command that makes the terminal report mouse positions in pixels instead of cells (SGR-Pixels
+/// mode).
+///
+/// Mouse events have to be enabled with [`EnableMouseCapture`] as well. The positions are
+/// reported in [`MouseEvent::pixel`], the `column` and `row` are derived from them and the cell
+/// size of the terminal.
+///
+/// The event reader has to be told to decode the positions as pixels with
+/// [`set_mouse_pixel_reporting`].
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::io;
+/// # #[cfg(feature = "events")]
+/// use crossterm::{
+///     event::{self, EnableMouseCapture, EnableMousePixelReporting},
+///     execute,
+/// };
+///
+/// # #[cfg(feature = "events")]
+/// fn main() -> io::Result<()> {
+///     execute!(io::stdout(), EnableMouseCapture, EnableMousePixelReporting)?;
+///     event::set_mouse_pixel_reporting(true);
+///     Ok(())
+/// }
+/// # #[cfg(not(feature = "events"))]
+/// # fn main() {}
+/// ```
+///
+/// # Notes
+///
+/// * Pixel mouse reporting is supported by e.g. xterm, kitty, foot, WezTerm and Contour. Other
+///   terminals keep reporting cells, which are then misread as pixels.
+/// * This command is not supported on Windows.
+/// * Commands must be executed/queued for execution otherwise they do nothing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EnableMousePixelReporting;
+
+impl Command for EnableMousePixelReporting {
+    fn write_ansi(&self, f: &mut impl fmt::Write) -> fmt::Result {
+        f.write_str(csi!("?1016h"))
+    }
+
+    #[cfg(windows)]
+    fn execute_winapi(&self) -> std::io::Result<()> {
+        use std::io;
+
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "Pixel mouse reporting not implemented for the legacy Windows API.",
+        ))
+    }
+
+    #[cfg(windows)]
+    fn is_ansi_code_supported(&self) -> bool {
+        false
+    }
+}
+
+/// A command that makes the terminal report mouse positions in cells again, see
+/// [`EnableMousePixelReporting`].
+///
+/// The event reader has to be told with [`set_mouse_pixel_reporting`] as well.
+///
+/// # Notes
+///
+/// * This command is not supported on Windows.
+/// * Commands must be executed/queued for execution otherwise they do nothing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DisableMousePixelReporting;
+
+impl Command for DisableMousePixelReporting {
+    fn write_ansi(&self, f: &mut impl fmt::Write) -> fmt::Result {
+        f.write_str(csi!("?1016l"))
+    }
+
+    #[cfg(windows)]
+    fn execute_winapi(&self) -> std::io::Result<()> {
+        use std::io;
+
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "Pixel mouse reporting not implemented for the legacy Windows API.",
+        ))
+    }
+
+    #[cfg(windows)]
+    fn is_ansi_code_supported(&self) -> bool {
+        false
+    }
+}
+
 /// A command that enables focus event emission.
 ///
 /// It should be paired with [`DisableFocusChange`] at the end of execution.
@@ -435,6 +897,22 @@ impl Command for DisableBracketedPaste {
     }
 }
 
+/// How a bracketed paste is reported, see [`set_paste_mode`] and [`InputParser::set_paste_mode`].
+#[cfg(feature = "bracketed-paste")]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PasteMode {
+    /// The paste is reported as a whole by [`Event::Paste`] once it ended.
+    #[default]
+    Whole,
+    /// The paste is reported while it arrives, by [`Event::PasteStart`], an [`Event::PasteData`]
+    /// for every chunk of text that was read and [`Event::PasteEnd`].
+    Chunked {
+        /// The number of bytes after which the rest of the paste is discarded, `None` to report
+        /// all of it.
+        max_size: Option<usize>,
+    },
+}
+
 /// A command that enables the [kitty keyboard protocol](https://sw.kovidgoyal.net/kitty/keyboard-protocol/), which adds extra information to keyboard events and removes ambiguity for modifier keys.
 ///
 /// It should be paired with [`PopKeyboardEnhancementFlags`] at the end of execution.
@@ -525,10 +1003,87 @@ impl Command for PopKeyboardEnhancementFlags {
     }
 }
 
+/// A command that enables xterm's [modifyOtherKeys](https://invisible-island.net/xterm/modified-keys.html)
+/// mode at the given level, which reports modified keys like Ctrl+Enter, Ctrl+Shift+Tab or Ctrl+1
+/// that can't be told apart in the legacy encoding.
+///
+/// Level 1 only reports modified keys which are ambiguous otherwise, level 2 reports all modified
+/// keys, see the xterm documentation for details.
+///
+/// It should be paired with [`DisableModifyOtherKeys`] at the end of execution.
+///
+/// Terminals which support the [kitty keyboard protocol](PushKeyboardEnhancementFlags) usually
+/// prefer it; modifyOtherKeys is supported by e.g. xterm, GNOME Terminal (VTE) and tmux with
+/// `extended-keys` enabled.
+///
+/// Example usage:
+/// ```no_run
+/// use std::io::{Write, stdout};
+/// use crossterm::execute;
+/// use crossterm::event::{EnableModifyOtherKeys, DisableModifyOtherKeys};
+///
+/// let mut stdout = stdout();
+///
+/// execute!(stdout, EnableModifyOtherKeys(2));
+///
+/// // ...
+///
+/// execute!(stdout, DisableModifyOtherKeys);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EnableModifyOtherKeys(pub u8);
+
+impl Command for EnableModifyOtherKeys {
+    fn write_ansi(&self, f: &mut impl fmt::Write) -> fmt::Result {
+        write!(f, "{}{}m", csi!(">4;"), self.0)
+    }
+
+    #[cfg(windows)]
+    fn execute_winapi(&self) -> std::io::Result<()> {
+        use std::io;
+
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "modifyOtherKeys not implemented for the legacy Windows API.",
+        ))
+    }
+
+    #[cfg(windows)]
+    fn is_ansi_code_supported(&self) -> bool {
+        false
+    }
+}
+
+/// A command that disables xterm's modifyOtherKeys mode.
+///
+/// See [`EnableModifyOtherKeys`] for more information.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DisableModifyOtherKeys;
+
+impl Command for DisableModifyOtherKeys {
+    fn write_ansi(&self, f: &mut impl fmt::Write) -> fmt::Result {
+        f.write_str(csi!(">4;0m"))
+    }
+
+    #[cfg(windows)]
+    fn execute_winapi(&self) -> std::io::Result<()> {
+        use std::io;
+
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "modifyOtherKeys not implemented for the legacy Windows API.",
+        ))
+    }
+
+    #[cfg(windows)]
+    fn is_ansi_code_supported(&self) -> bool {
+        false
+    }
+}
+
 /// Represents an event.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[cfg_attr(feature = "derive-more", derive(IsVariant))]
-#[cfg_attr(not(feature = "bracketed-paste"), derive(Copy))]
 #[derive(Debug, PartialOrd, Ord, PartialEq, Eq, Clone, Hash)]
 pub enum Event {
     /// The terminal gained focus
@@ -543,9 +1098,29 @@ pub enum Event {
     /// enabled.
     #[cfg(feature = "bracketed-paste")]
     Paste(String),
+    /// The start of a paste in [`PasteMode::Chunked`].
+    #[cfg(feature = "bracketed-paste")]
+    PasteStart,
+    /// A chunk of the text pasted in [`PasteMode::Chunked`].
+    #[cfg(feature = "bracketed-paste")]
+    PasteData(String),
+    /// The end of a paste in [`PasteMode::Chunked`].
+    #[cfg(feature = "bracketed-paste")]
+    PasteEnd {
+        /// Whether the paste exceeded the maximum size and the rest was discarded.
+        truncated: bool,
+    },
     /// A resize event with new dimensions after resize (columns, rows).
     /// **Note** that resize events can occur in batches.
     Resize(u16, u16),
+    /// The program was continued after it was stopped, e.g. by
+    /// [`terminal::suspend`](crate::terminal::suspend). The terminal should be redrawn, it may
+    /// have been resized meanwhile. Only emitted on UNIX.
+    Resumed,
+    /// The raw bytes of input which couldn't be decoded, e.g. a key sequence of an exotic
+    /// terminal. Only emitted if enabled with [`set_report_unknown`] or
+    /// [`InputParser::set_report_unknown`].
+    Unknown(Vec<u8>),
 }
 
 impl Event {
@@ -562,6 +1137,7 @@ impl Event {
     /// ```no_run
     /// use crossterm::event;
     ///
+    /// # #[cfg(feature = "events")]
     /// while !event::read()?.is_key_press() {
     ///     // ...
     /// }
@@ -613,6 +1189,7 @@ impl Event {
     /// ```no_run
     /// use crossterm::event;
     ///
+    /// # #[cfg(feature = "events")]
     /// while let Some(key_event) = event::read()?.as_key_event() {
     ///     // ...
     /// }
@@ -640,6 +1217,7 @@ impl Event {
     /// ```no_run
     /// use crossterm::event;
     ///
+    /// # #[cfg(feature = "events")]
     /// while let Ok(event) = event::read() {
     ///     if let Some(key) = event.as_key_press_event() {
     ///         // ...
@@ -681,6 +1259,7 @@ impl Event {
     /// ```no_run
     /// use crossterm::event;
     ///
+    /// # #[cfg(feature = "events")]
     /// while let Some(mouse_event) = event::read()?.as_mouse_event() {
     ///     // ...
     /// }
@@ -703,6 +1282,7 @@ impl Event {
     /// ```no_run
     /// use crossterm::event;
     ///
+    /// # #[cfg(feature = "events")]
     /// while let Some(paste) = event::read()?.as_paste_event() {
     ///     // ...
     /// }
@@ -726,6 +1306,7 @@ impl Event {
     /// ```no_run
     /// use crossterm::event;
     ///
+    /// # #[cfg(feature = "events")]
     /// while let Some((columns, rows)) = event::read()?.as_resize_event() {
     ///     // ...
     /// }
@@ -766,6 +1347,27 @@ pub struct MouseEvent {
     pub row: u16,
     /// The key modifiers active when the event occurred.
     pub modifiers: KeyModifiers,
+    /// The position in pixels, if pixel mouse reporting is enabled with
+    /// [`EnableMousePixelReporting`].
+    pub pixel: Option<MousePixelPosition>,
+}
+
+/// The position of a mouse event in pixels, see [`EnableMousePixelReporting`].
+///
+/// The cell size is taken from the terminal when the event is read, it's `0` if the terminal
+/// doesn't report its size in pixels. In that case the `column` and `row` of the [`MouseEvent`]
+/// are `0` too.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, PartialOrd, Ord, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct MousePixelPosition {
+    /// The horizontal position in pixels, relative to the left edge of the terminal.
+    pub x: u16,
+    /// The vertical position in pixels, relative to the top edge of the terminal.
+    pub y: u16,
+    /// The width of a cell in pixels.
+    pub cell_width: u16,
+    /// The height of a cell in pixels.
+    pub cell_height: u16,
 }
 
 /// A mouse event kind.
@@ -825,6 +1427,12 @@ pub enum MouseButton {
     Right,
     /// Middle mouse button.
     Middle,
+    /// Back mouse button (button 8), usually on the side of the mouse.
+    Back,
+    /// Forward mouse button (button 9), usually on the side of the mouse.
+    Forward,
+    /// Another mouse button, numbered like X11 does, e.g. `10` or `11`.
+    Other(u8),
 }
 
 bitflags! {
@@ -948,6 +1556,24 @@ pub struct KeyEvent {
     /// Only set if [`KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES`] has been enabled with
     /// [`PushKeyboardEnhancementFlags`].
     pub state: KeyEventState,
+    /// The text produced by the key, taking the keyboard layout, dead keys and compose sequences
+    /// into account.
+    ///
+    /// Only set if [`KeyboardEnhancementFlags::REPORT_ASSOCIATED_TEXT`] and
+    /// [`KeyboardEnhancementFlags::REPORT_ALL_KEYS_AS_ESCAPE_CODES`] have been enabled with
+    /// [`PushKeyboardEnhancementFlags`] and the key produced text.
+    ///
+    /// **Note:** the text is not taken into account when comparing or hashing key events.
+    pub text: Option<KeyText>,
+    /// The other keys the terminal reported for this key, e.g. to match shortcuts independently
+    /// of the keyboard layout.
+    ///
+    /// Only set if [`KeyboardEnhancementFlags::REPORT_ALTERNATE_KEYS`] has been enabled with
+    /// [`PushKeyboardEnhancementFlags`] and the terminal reported a shifted or base layout key.
+    ///
+    /// **Note:** the alternate keys are not taken into account when comparing or hashing key
+    /// events.
+    pub alternate_keys: Option<AlternateKeys>,
 }
 
 impl KeyEvent {
@@ -957,6 +1583,8 @@ impl KeyEvent {
             modifiers,
             kind: KeyEventKind::Press,
             state: KeyEventState::empty(),
+            text: None,
+            alternate_keys: None,
         }
     }
 
@@ -970,6 +1598,8 @@ impl KeyEvent {
             modifiers,
             kind,
             state: KeyEventState::empty(),
+            text: None,
+            alternate_keys: None,
         }
     }
 
@@ -984,6 +1614,8 @@ impl KeyEvent {
             modifiers,
             kind,
             state,
+            text: None,
+            alternate_keys: None,
         }
     }
 
@@ -1027,6 +1659,8 @@ impl From<KeyCode> for KeyEvent {
             modifiers: KeyModifiers::empty(),
             kind: KeyEventKind::Press,
             state: KeyEventState::empty(),
+            text: None,
+            alternate_keys: None,
         }
     }
 }
@@ -1038,12 +1672,16 @@ impl PartialEq for KeyEvent {
             modifiers: lhs_modifiers,
             kind: lhs_kind,
             state: lhs_state,
+            text: _,
+            alternate_keys: _,
         } = self.normalize_case();
         let KeyEvent {
             code: rhs_code,
             modifiers: rhs_modifiers,
             kind: rhs_kind,
             state: rhs_state,
+            text: _,
+            alternate_keys: _,
         } = other.normalize_case();
         (lhs_code == rhs_code)
             && (lhs_modifiers == rhs_modifiers)
@@ -1061,6 +1699,8 @@ impl Hash for KeyEvent {
             modifiers,
             kind,
             state,
+            text: _,
+            alternate_keys: _,
         } = self.normalize_case();
         code.hash(hash_state);
         modifiers.hash(hash_state);
@@ -1069,6 +1709,128 @@ impl Hash for KeyEvent {
     }
 }
 
+/// The alternate keys reported for a key, see [`KeyEvent::alternate_keys`].
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, PartialOrd, Ord, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct AlternateKeys {
+    /// The key without Shift applied. This is the key code reported by the terminal, which
+    /// differs from [`KeyEvent::code`] when the shifted key replaced it.
+    pub unshifted: KeyCode,
+    /// The character produced by the key with Shift applied, according to the keyboard layout.
+    ///
+    /// Only reported when Shift is pressed. It then becomes [`KeyEvent::code`] and Shift is
+    /// removed from [`KeyEvent::modifiers`].
+    pub shifted: Option<char>,
+    /// The key at the same position in the standard PC-101 (US) layout, e.g. `KeyCode::Char('z')`
+    /// for the `w` key of an AZERTY keyboard. Terminals only report it when it differs from the
+    /// unshifted key.
+    pub base_layout: Option<KeyCode>,
+}
+
+/// The text produced by a key press, see [`KeyEvent::text`].
+///
+/// The text is stored inline so [`KeyEvent`] stays `Copy`; it can hold up to
+/// [`KeyText::MAX_LEN`] bytes of UTF-8.
+#[derive(Clone, Copy)]
+pub struct KeyText {
+    len: u8,
+    bytes: [u8; KeyText::MAX_LEN],
+}
+
+impl KeyText {
+    /// The maximum length of the text in bytes.
+    pub const MAX_LEN: usize = 31;
+
+    /// Creates a new `KeyText`, returning `None` if `text` is longer than [`KeyText::MAX_LEN`]
+    /// bytes.
+    pub fn new(text: &str) -> Option<KeyText> {
+        if text.len() > KeyText::MAX_LEN {
+            return None;
+        }
+        let mut bytes = [0; KeyText::MAX_LEN];
+        bytes[..text.len()].copy_from_slice(text.as_bytes());
+        Some(KeyText {
+            len: text.len() as u8,
+            bytes,
+        })
+    }
+
+    /// Returns the text as a string slice.
+    pub fn as_str(&self) -> &str {
+        // The bytes were copied from a `&str` in `KeyText::new`.
+        std::str::from_utf8(&self.bytes[..self.len as usize]).unwrap_or_default()
+    }
+}
+
+impl std::ops::Deref for KeyText {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl AsRef<str> for KeyText {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl Display for KeyText {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl fmt::Debug for KeyText {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl PartialEq for KeyText {
+    fn eq(&self, other: &KeyText) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl Eq for KeyText {}
+
+impl PartialOrd for KeyText {
+    fn partial_cmp(&self, other: &KeyText) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for KeyText {
+    fn cmp(&self, other: &KeyText) -> std::cmp::Ordering {
+        self.as_str().cmp(other.as_str())
+    }
+}
+
+impl Hash for KeyText {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state);
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for KeyText {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for KeyText {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        KeyText::new(&text).ok_or_else(|| {
+            serde::de::Error::invalid_length(text.len(), &"at most 31 bytes of text")
+        })
+    }
+}
+
 /// Represents a media key (as part of [`KeyCode::Media`]).
 #[derive(Debug, PartialOrd, Ord, PartialEq, Eq, Clone, Copy, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -1475,6 +2237,45 @@ mod tests {
     use MediaKeyCode::*;
     use ModifierKeyCode::*;
 
+    #[test]
+    #[cfg(feature = "events")]
+    fn test_mouse_capture_with() {
+        fn ansi(command: impl Command) -> String {
+            let mut buffer = String::new();
+            command.write_ansi(&mut buffer).unwrap();
+            buffer
+        }
+
+        let capture = MouseCapture::new();
+        assert_eq!(
+            ansi(EnableMouseCaptureWith(capture)),
+            "\x1B[?1000h\x1B[?1006h"
+        );
+        assert_eq!(
+            ansi(DisableMouseCaptureWith(capture)),
+            "\x1B[?1006l\x1B[?1000l"
+        );
+
+        let capture = MouseCapture::new()
+            .tracking(MouseTracking::AnyEvent)
+            .encoding(MouseCaptureEncoding::Urxvt)
+            .alternate_scroll(true);
+        assert_eq!(
+            ansi(EnableMouseCaptureWith(capture)),
+            "\x1B[?1000h\x1B[?1002h\x1B[?1003h\x1B[?1015h\x1B[?1007h"
+        );
+        assert_eq!(
+            ansi(DisableMouseCaptureWith(capture)),
+            "\x1B[?1007l\x1B[?1015l\x1B[?1003l\x1B[?1002l\x1B[?1000l"
+        );
+
+        let capture = MouseCapture::new()
+            .tracking(MouseTracking::X10)
+            .encoding(MouseCaptureEncoding::X10);
+        assert_eq!(ansi(EnableMouseCaptureWith(capture)), "\x1B[?9h");
+        assert_eq!(ansi(DisableMouseCaptureWith(capture)), "\x1B[?9l");
+    }
+
     #[test]
     fn test_equality() {
         let lowercase_d_with_shift = KeyEvent::new(KeyCode::Char('d'), KeyModifiers::SHIFT);
@@ -1630,6 +2431,7 @@ mod tests {
         column: 1,
         row: 1,
         modifiers: KeyModifiers::empty(),
+        pixel: None,
     };
 
     #[cfg(feature = "derive-more")]
@@ -0,0 +1,188 @@
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "events")]
+use std::io;
+
+use crate::event::{Event, MouseButton, MouseEventKind};
+
+/// Counts repeated clicks, to detect double and triple clicks.
+///
+/// A press of a mouse button continues the click sequence of the previous press if it's the same
+/// button, it happened within the [interval](ClickTracker::interval) and the mouse moved at most
+/// the [distance](ClickTracker::distance).
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::io;
+/// # #[cfg(feature = "events")]
+/// use crossterm::event::{ClickTracker, Event};
+///
+/// # #[cfg(feature = "events")]
+/// fn main() -> io::Result<()> {
+///     let mut tracker = ClickTracker::new();
+///     loop {
+///         match tracker.read()? {
+///             (Event::Mouse(event), Some(2)) => println!("Select the word at {event:?}"),
+///             (Event::Mouse(event), Some(3)) => println!("Select the line at {event:?}"),
+///             _ => {}
+///         }
+///     }
+/// }
+/// # #[cfg(not(feature = "events"))]
+/// # fn main() {}
+/// ```
+#[derive(Debug, Clone)]
+pub struct ClickTracker {
+    interval: Duration,
+    distance: u16,
+    last_click: Option<Click>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Click {
+    button: MouseButton,
+    column: u16,
+    row: u16,
+    time: Instant,
+    count: u32,
+}
+
+impl Default for ClickTracker {
+    fn default() -> Self {
+        ClickTracker {
+            interval: Duration::from_millis(500),
+            distance: 1,
+            last_click: None,
+        }
+    }
+}
+
+impl ClickTracker {
+    /// Creates a click tracker with an interval of 500 milliseconds and a distance of 1 cell.
+    pub fn new() -> ClickTracker {
+        ClickTracker::default()
+    }
+
+    /// Sets the maximum time between two presses of a click sequence.
+    pub fn interval(mut self, interval: Duration) -> ClickTracker {
+        self.interval = interval;
+        self
+    }
+
+    /// Sets how many cells the mouse may move between two presses of a click sequence,
+    /// horizontally and vertically.
+    pub fn distance(mut self, distance: u16) -> ClickTracker {
+        self.distance = distance;
+        self
+    }
+
+    /// Tracks the event and returns the click count if it's a press of a mouse button, e.g. `2`
+    /// for a double click.
+    ///
+    /// All events have to be passed, in the order they were read.
+    pub fn track(&mut self, event: &Event) -> Option<u32> {
+        self.track_at(event, Instant::now())
+    }
+
+    fn track_at(&mut self, event: &Event, time: Instant) -> Option<u32> {
+        let Event::Mouse(event) = event else {
+            return None;
+        };
+        let MouseEventKind::Down(button) = event.kind else {
+            return None;
+        };
+
+        let count = match self.last_click {
+            Some(last_click)
+                if last_click.button == button
+                    && time.saturating_duration_since(last_click.time) <= self.interval
+                    && last_click.column.abs_diff(event.column) <= self.distance
+                    && last_click.row.abs_diff(event.row) <= self.distance =>
+            {
+                last_click.count.saturating_add(1)
+            }
+            _ => 1,
+        };
+        self.last_click = Some(Click {
+            button,
+            column: event.column,
+            row: event.row,
+            time,
+            count,
+        });
+        Some(count)
+    }
+
+    /// Reads an [`Event`](enum.Event.html) like [`read`](fn.read.html) does, together with its
+    /// click count, see [`track`](ClickTracker::track).
+    #[cfg(feature = "events")]
+    pub fn read(&mut self) -> io::Result<(Event, Option<u32>)> {
+        let event = super::read()?;
+        let clicks = self.track(&event);
+        Ok((event, clicks))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::ClickTracker;
+    use crate::event::{Event, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+
+    fn mouse(kind: MouseEventKind, column: u16, row: u16) -> Event {
+        Event::Mouse(MouseEvent {
+            kind,
+            column,
+            row,
+            modifiers: KeyModifiers::NONE,
+            pixel: None,
+        })
+    }
+
+    #[test]
+    fn test_click_tracker_counts_clicks() {
+        let mut tracker = ClickTracker::new();
+        let start = Instant::now();
+        let at = |millis| start + Duration::from_millis(millis);
+        let down = mouse(MouseEventKind::Down(MouseButton::Left), 10, 5);
+        let up = mouse(MouseEventKind::Up(MouseButton::Left), 10, 5);
+
+        assert_eq!(tracker.track_at(&down, at(0)), Some(1));
+        assert_eq!(tracker.track_at(&up, at(50)), None);
+        assert_eq!(tracker.track_at(&down, at(200)), Some(2));
+        assert_eq!(tracker.track_at(&up, at(250)), None);
+        assert_eq!(tracker.track_at(&down, at(600)), Some(3));
+
+        // Too late.
+        assert_eq!(tracker.track_at(&down, at(1200)), Some(1));
+        // Too far away.
+        let moved = mouse(MouseEventKind::Down(MouseButton::Left), 12, 5);
+        assert_eq!(tracker.track_at(&moved, at(1300)), Some(1));
+        // Within the distance.
+        let nearby = mouse(MouseEventKind::Down(MouseButton::Left), 11, 6);
+        assert_eq!(tracker.track_at(&nearby, at(1400)), Some(2));
+        // Another button.
+        let right = mouse(MouseEventKind::Down(MouseButton::Right), 11, 6);
+        assert_eq!(tracker.track_at(&right, at(1500)), Some(1));
+
+        assert_eq!(tracker.track_at(&Event::FocusLost, at(1600)), None);
+    }
+
+    #[test]
+    fn test_click_tracker_thresholds() {
+        let mut tracker = ClickTracker::new()
+            .interval(Duration::from_millis(100))
+            .distance(0);
+        let start = Instant::now();
+        let at = |millis| start + Duration::from_millis(millis);
+
+        let down = mouse(MouseEventKind::Down(MouseButton::Left), 10, 5);
+        assert_eq!(tracker.track_at(&down, at(0)), Some(1));
+        assert_eq!(tracker.track_at(&down, at(200)), Some(1));
+
+        let nearby = mouse(MouseEventKind::Down(MouseButton::Left), 11, 5);
+        assert_eq!(tracker.track_at(&nearby, at(250)), Some(1));
+    }
+}
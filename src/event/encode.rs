@@ -0,0 +1,1092 @@
+//! Encoding of events into the byte sequences a terminal would send for them.
+//!
+//! This is the inverse of the input parser, e.g. for terminal multiplexers forwarding the input
+//! they read to the applications running inside of them.
+
+use crate::event::{
+    KeyCode, KeyEvent, KeyEventKind, KeyEventState, KeyModifiers, KeyboardEnhancementFlags,
+    MediaKeyCode, ModifierKeyCode, MouseButton, MouseEvent, MouseEventKind,
+};
+
+/// The encoding used by [`KeyEvent::encode`].
+///
+/// Events decoded from an encoded key event are equal to the original one, as long as the
+/// encoding is able to represent it. Encodings can't represent everything a `KeyEvent` is able to
+/// describe, e.g. the legacy encoding has no way to tell `Ctrl+i` from `Tab` and drops modifiers
+/// it can't express, just like the terminals using it.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, PartialOrd, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum KeyEncoding {
+    /// The encoding used by xterm and most other terminals by default.
+    ///
+    /// Release events can't be represented and repeat events are encoded as press events.
+    Legacy,
+    /// The xterm [modifyOtherKeys](https://invisible-island.net/xterm/modified-keys.html) mode
+    /// (level 2).
+    ///
+    /// Modified keys which have no legacy encoding are sent as `CSI 27 ; modifiers ; code ~`,
+    /// everything else is encoded like [`KeyEncoding::Legacy`].
+    ModifyOtherKeys,
+    /// The [kitty keyboard protocol](https://sw.kovidgoyal.net/kitty/keyboard-protocol/) with
+    /// the given progressive enhancements enabled.
+    ///
+    /// With [`KeyboardEnhancementFlags::REPORT_ALTERNATE_KEYS`], the
+    /// [alternate keys](KeyEvent::alternate_keys) of text keys are appended to the key code as
+    /// `code:shifted:base`. A shifted key implies the Shift modifier.
+    Kitty(KeyboardEnhancementFlags),
+}
+
+/// The encoding used by [`MouseEvent::encode`].
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, PartialOrd, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum MouseEncoding {
+    /// The original `CSI M Cb Cx Cy` encoding.
+    ///
+    /// Coordinates are limited to 222 and the released button isn't reported.
+    X10,
+    /// The SGR `CSI < Cb ; Cx ; Cy M` encoding (mode 1006).
+    Sgr,
+}
+
+impl KeyEvent {
+    /// Encodes the key event as the byte sequence a terminal would send for it.
+    ///
+    /// Returns `None` if the key can't be represented in the given encoding at all, e.g. a key
+    /// release in the legacy encoding.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crossterm::event::{
+    ///     KeyCode, KeyEncoding, KeyEvent, KeyModifiers, KeyboardEnhancementFlags,
+    /// };
+    ///
+    /// let event = KeyEvent::new(KeyCode::Up, KeyModifiers::CONTROL);
+    /// assert_eq!(event.encode(KeyEncoding::Legacy), Some(b"\x1B[1;5A".to_vec()));
+    ///
+    /// let event = KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE);
+    /// let encoding = KeyEncoding::Kitty(KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES);
+    /// assert_eq!(event.encode(encoding), Some(b"\x1B[27u".to_vec()));
+    /// ```
+    pub fn encode(&self, encoding: KeyEncoding) -> Option<Vec<u8>> {
+        let event = self.normalize_case();
+
+        match encoding {
+            KeyEncoding::Legacy => encode_legacy(&event),
+            KeyEncoding::ModifyOtherKeys => encode_modify_other_keys(&event),
+            KeyEncoding::Kitty(flags) => encode_kitty(&event, flags),
+        }
+        .map(String::into_bytes)
+    }
+}
+
+impl MouseEvent {
+    /// Encodes the mouse event as the byte sequence a terminal would send for it.
+    ///
+    /// Returns `None` if the button or position can't be represented in the given encoding. The
+    /// SGR encoding uses the [pixel position](MouseEvent::pixel) if it's set, like a terminal with
+    /// [pixel reporting](super::EnableMousePixelReporting) does.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crossterm::event::{
+    ///     KeyModifiers, MouseButton, MouseEncoding, MouseEvent, MouseEventKind,
+    /// };
+    ///
+    /// let event = MouseEvent {
+    ///     kind: MouseEventKind::Up(MouseButton::Right),
+    ///     column: 9,
+    ///     row: 19,
+    ///     modifiers: KeyModifiers::NONE,
+    ///     pixel: None,
+    /// };
+    /// assert_eq!(event.encode(MouseEncoding::Sgr), Some(b"\x1B[<2;10;20m".to_vec()));
+    /// ```
+    pub fn encode(&self, encoding: MouseEncoding) -> Option<Vec<u8>> {
+        let mut cb = match self.kind {
+            MouseEventKind::Up(_) if encoding == MouseEncoding::X10 => 3,
+            MouseEventKind::Down(button) | MouseEventKind::Up(button) => button_code(button)?,
+            MouseEventKind::Drag(button) => 32 + button_code(button)?,
+            MouseEventKind::Moved => 35,
+            MouseEventKind::ScrollUp => 64,
+            MouseEventKind::ScrollDown => 65,
+            MouseEventKind::ScrollLeft => 66,
+            MouseEventKind::ScrollRight => 67,
+        };
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            cb |= 4;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            cb |= 8;
+        }
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            cb |= 16;
+        }
+
+        match encoding {
+            MouseEncoding::X10 => {
+                // Coordinates are 1-based and offset by 32 to be printable.
+                let cx = u8::try_from(u32::from(self.column) + 33).ok()?;
+                let cy = u8::try_from(u32::from(self.row) + 33).ok()?;
+                Some(vec![b'\x1B', b'[', b'M', cb + 32, cx, cy])
+            }
+            MouseEncoding::Sgr => {
+                let action = match self.kind {
+                    MouseEventKind::Up(_) => 'm',
+                    _ => 'M',
+                };
+                let (x, y) = self
+                    .pixel
+                    .map_or((self.column, self.row), |pixel| (pixel.x, pixel.y));
+                Some(
+                    format!(
+                        "\x1B[<{};{};{}{}",
+                        cb,
+                        u32::from(x) + 1,
+                        u32::from(y) + 1,
+                        action
+                    )
+                    .into_bytes(),
+                )
+            }
+        }
+    }
+}
+
+/// Wraps the pasted text in the markers of bracketed paste, like a terminal does when text is
+/// pasted while [`EnableBracketedPaste`](super::EnableBracketedPaste) is active.
+///
+/// Any paste end marker in the text is removed, so that the text can't end the paste early and
+/// inject input.
+///
+/// # Examples
+///
+/// ```
+/// use crossterm::event::encode_paste;
+///
+/// assert_eq!(encode_paste("text"), "\x1B[200~text\x1B[201~");
+/// ```
+pub fn encode_paste(text: &str) -> String {
+    // ESC [ 2 0 1 ~
+    const END: &str = "\x1B[201~";
+
+    // Removing a marker can join the text around it into another one.
+    let mut text = text.to_string();
+    while text.contains(END) {
+        text = text.replace(END, "");
+    }
+    format!("\x1B[200~{text}{END}")
+}
+
+fn button_code(button: MouseButton) -> Option<u8> {
+    match button {
+        MouseButton::Left => Some(0),
+        MouseButton::Middle => Some(1),
+        MouseButton::Right => Some(2),
+        MouseButton::Back => Some(128),
+        MouseButton::Forward => Some(129),
+        MouseButton::Other(button @ 10..=11) => Some(128 + button - 8),
+        MouseButton::Other(_) => None,
+    }
+}
+
+fn modifier_bits(modifiers: KeyModifiers) -> u16 {
+    let mut bits = 0;
+    if modifiers.contains(KeyModifiers::SHIFT) {
+        bits |= 1;
+    }
+    if modifiers.contains(KeyModifiers::ALT) {
+        bits |= 2;
+    }
+    if modifiers.contains(KeyModifiers::CONTROL) {
+        bits |= 4;
+    }
+    if modifiers.contains(KeyModifiers::SUPER) {
+        bits |= 8;
+    }
+    if modifiers.contains(KeyModifiers::HYPER) {
+        bits |= 16;
+    }
+    if modifiers.contains(KeyModifiers::META) {
+        bits |= 32;
+    }
+    bits
+}
+
+fn state_bits(state: KeyEventState) -> u16 {
+    let mut bits = 0;
+    if state.contains(KeyEventState::CAPS_LOCK) {
+        bits |= 64;
+    }
+    if state.contains(KeyEventState::NUM_LOCK) {
+        bits |= 128;
+    }
+    bits
+}
+
+// The parameters of a CSI sequence describing the modifiers and the event kind. Empty if there
+// are neither modifiers nor a kind other than a press, the parameters can be omitted then.
+fn modifier_params(modifiers: u16, kind: u8) -> String {
+    match (modifiers, kind) {
+        (0, 1) => String::new(),
+        (_, 1) => format!("{}", modifiers + 1),
+        _ => format!("{}:{}", modifiers + 1, kind),
+    }
+}
+
+// The byte sent with Ctrl held, if any.
+fn control_byte(c: char) -> Option<u8> {
+    Some(match c {
+        'a'..='z' => c as u8 - b'a' + 1,
+        'A'..='Z' => c as u8 - b'A' + 1,
+        ' ' | '@' | '2' => 0x00,
+        '[' | '3' => 0x1B,
+        '\\' | '4' => 0x1C,
+        ']' | '5' => 0x1D,
+        '^' | '6' => 0x1E,
+        '_' | '7' | '/' => 0x1F,
+        '?' | '8' => 0x7F,
+        _ => return None,
+    })
+}
+
+// Encodes the keys with a legacy CSI or SS3 sequence, which are the same in all encodings.
+fn encode_functional(code: KeyCode, params: &str, ss3: bool) -> Option<String> {
+    let final_byte = match code {
+        KeyCode::Up => 'A',
+        KeyCode::Down => 'B',
+        KeyCode::Right => 'C',
+        KeyCode::Left => 'D',
+        KeyCode::End => 'F',
+        KeyCode::Home => 'H',
+        KeyCode::F(1) => 'P',
+        KeyCode::F(2) => 'Q',
+        KeyCode::F(3) if ss3 && params.is_empty() => 'R',
+        KeyCode::F(4) => 'S',
+        code => {
+            let number = match code {
+                KeyCode::Insert => 2,
+                KeyCode::Delete => 3,
+                KeyCode::PageUp => 5,
+                KeyCode::PageDown => 6,
+                // `CSI 1 ; modifiers R` is a cursor position report, kitty uses this instead.
+                KeyCode::F(3) => 13,
+                KeyCode::F(n @ 5) => n + 10,
+                KeyCode::F(n @ 6..=10) => n + 11,
+                KeyCode::F(n @ 11..=12) => n + 12,
+                _ => return None,
+            };
+            return Some(if params.is_empty() {
+                format!("\x1B[{number}~")
+            } else {
+                format!("\x1B[{number};{params}~")
+            });
+        }
+    };
+
+    Some(if !params.is_empty() {
+        format!("\x1B[1;{params}{final_byte}")
+    } else if ss3 && matches!(code, KeyCode::F(_)) {
+        format!("\x1BO{final_byte}")
+    } else {
+        format!("\x1B[{final_byte}")
+    })
+}
+
+fn encode_legacy(event: &KeyEvent) -> Option<String> {
+    if event.kind == KeyEventKind::Release {
+        return None;
+    }
+
+    let modifiers = event.modifiers;
+    let text = match event.code {
+        KeyCode::Char(c) => match control_byte(c) {
+            Some(byte) if modifiers.contains(KeyModifiers::CONTROL) => char::from(byte).to_string(),
+            _ => c.to_string(),
+        },
+        KeyCode::Enter => "\r".to_string(),
+        KeyCode::Tab => "\t".to_string(),
+        KeyCode::Backspace => "\x7F".to_string(),
+        KeyCode::Esc => "\x1B".to_string(),
+        KeyCode::Null => "\0".to_string(),
+        KeyCode::BackTab => return Some("\x1B[Z".to_string()),
+        code => {
+            let params = modifier_params(modifier_bits(modifiers), 1);
+            return encode_functional(code, &params, true);
+        }
+    };
+
+    Some(if modifiers.contains(KeyModifiers::ALT) {
+        format!("\x1B{text}")
+    } else {
+        text
+    })
+}
+
+fn encode_modify_other_keys(event: &KeyEvent) -> Option<String> {
+    if event.kind == KeyEventKind::Release {
+        return None;
+    }
+
+    let mut modifiers = event.modifiers;
+    let code = match event.code {
+        KeyCode::Char(c) if !modifiers.difference(KeyModifiers::SHIFT).is_empty() => u32::from(c),
+        KeyCode::Enter if !modifiers.is_empty() => 13,
+        KeyCode::Tab if !modifiers.is_empty() => 9,
+        KeyCode::BackTab if !modifiers.difference(KeyModifiers::SHIFT).is_empty() => {
+            modifiers |= KeyModifiers::SHIFT;
+            9
+        }
+        KeyCode::Backspace if !modifiers.is_empty() => 127,
+        KeyCode::Esc if !modifiers.is_empty() => 27,
+        _ => return encode_legacy(event),
+    };
+
+    Some(format!(
+        "\x1B[27;{};{}~",
+        modifier_bits(modifiers) + 1,
+        code
+    ))
+}
+
+fn encode_kitty(event: &KeyEvent, flags: KeyboardEnhancementFlags) -> Option<String> {
+    let all_keys = flags.contains(KeyboardEnhancementFlags::REPORT_ALL_KEYS_AS_ESCAPE_CODES);
+    if !all_keys && !flags.contains(KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES) {
+        return encode_legacy(event);
+    }
+
+    let report_event_types = flags.contains(KeyboardEnhancementFlags::REPORT_EVENT_TYPES);
+    let kind = match event.kind {
+        KeyEventKind::Release if !report_event_types => return None,
+        _ if !report_event_types => 1,
+        KeyEventKind::Press => 1,
+        KeyEventKind::Repeat => 2,
+        KeyEventKind::Release => 3,
+    };
+
+    // The alternate keys are reported for text keys, the shifted key implies Shift.
+    let alternates = event
+        .alternate_keys
+        .filter(|_| flags.contains(KeyboardEnhancementFlags::REPORT_ALTERNATE_KEYS))
+        .filter(|_| {
+            matches!(event.code, KeyCode::Char(_)) && !event.state.contains(KeyEventState::KEYPAD)
+        });
+    let alternate_codes = alternates.map_or_else(String::new, |keys| {
+        let shifted = keys.shifted.map(u32::from);
+        match (shifted, keys.base_layout.and_then(key_code_point)) {
+            (Some(shifted), Some(base)) => format!(":{shifted}:{base}"),
+            (Some(shifted), None) => format!(":{shifted}"),
+            (None, Some(base)) => format!("::{base}"),
+            (None, None) => String::new(),
+        }
+    });
+    let mut modifiers = event.modifiers;
+    if event.code == KeyCode::BackTab || alternates.is_some_and(|keys| keys.shifted.is_some()) {
+        modifiers |= KeyModifiers::SHIFT;
+    }
+    let params = modifier_params(modifier_bits(modifiers) | state_bits(event.state), kind);
+    // Associated text is only reported along with all keys, and never for releases.
+    let text = event
+        .text
+        .filter(|_| all_keys && flags.contains(KeyboardEnhancementFlags::REPORT_ASSOCIATED_TEXT))
+        .filter(|text| kind != 3 && !text.is_empty())
+        .map(|text| {
+            text.chars()
+                .map(|c| u32::from(c).to_string())
+                .collect::<Vec<_>>()
+                .join(":")
+        });
+    let csi_u = |code: u32| {
+        Some(match (&text, params.is_empty()) {
+            (Some(text), _) => format!("\x1B[{code}{alternate_codes};{params};{text}u"),
+            (None, true) => format!("\x1B[{code}{alternate_codes}u"),
+            (None, false) => format!("\x1B[{code}{alternate_codes};{params}u"),
+        })
+    };
+
+    if event.state.contains(KeyEventState::KEYPAD) {
+        if let Some(code) = keypad_code(event.code) {
+            return csi_u(code);
+        }
+    }
+
+    // Keys generating text are sent as text unless all keys are reported as escape codes. Their
+    // release isn't reported then.
+    let text = match event.code {
+        KeyCode::Char(c) if modifiers.difference(KeyModifiers::SHIFT).is_empty() => {
+            Some(c.to_string())
+        }
+        KeyCode::Enter if modifiers.is_empty() => Some("\r".to_string()),
+        KeyCode::Tab if modifiers.is_empty() => Some("\t".to_string()),
+        KeyCode::Backspace if modifiers.is_empty() => Some("\x7F".to_string()),
+        _ => None,
+    };
+    if let Some(text) = text {
+        if !all_keys {
+            return (event.kind != KeyEventKind::Release).then_some(text);
+        }
+    }
+
+    match event.code {
+        // The key code is the unshifted key, Shift is reported as a modifier.
+        KeyCode::Char(c) => match alternates {
+            Some(keys) => csi_u(key_code_point(keys.unshifted)?),
+            None => csi_u(u32::from(c.to_ascii_lowercase())),
+        },
+        KeyCode::Enter => csi_u(13),
+        KeyCode::Tab | KeyCode::BackTab => csi_u(9),
+        KeyCode::Backspace => csi_u(127),
+        KeyCode::Esc => csi_u(27),
+        KeyCode::Null => (event.kind != KeyEventKind::Release).then(|| "\0".to_string()),
+        // Lock and modifier keys are only reported when all keys are.
+        KeyCode::CapsLock | KeyCode::ScrollLock | KeyCode::NumLock | KeyCode::Modifier(_)
+            if !all_keys =>
+        {
+            None
+        }
+        code => match functional_code(code) {
+            Some(code) => csi_u(code),
+            None => encode_functional(code, &params, false),
+        },
+    }
+}
+
+// The kitty key code of a key reported as `CSI u`.
+fn key_code_point(code: KeyCode) -> Option<u32> {
+    match code {
+        KeyCode::Char(c) => Some(u32::from(c)),
+        KeyCode::Enter => Some(13),
+        KeyCode::Tab | KeyCode::BackTab => Some(9),
+        KeyCode::Backspace => Some(127),
+        KeyCode::Esc => Some(27),
+        code => functional_code(code),
+    }
+}
+
+// The kitty key codes of the keys on the keypad, see `translate_functional_key_code`.
+fn keypad_code(code: KeyCode) -> Option<u32> {
+    Some(match code {
+        KeyCode::Char(c @ '0'..='9') => 57399 + (c as u32 - '0' as u32),
+        KeyCode::Char('.') => 57409,
+        KeyCode::Char('/') => 57410,
+        KeyCode::Char('*') => 57411,
+        KeyCode::Char('-') => 57412,
+        KeyCode::Char('+') => 57413,
+        KeyCode::Enter => 57414,
+        KeyCode::Char('=') => 57415,
+        KeyCode::Char(',') => 57416,
+        KeyCode::Left => 57417,
+        KeyCode::Right => 57418,
+        KeyCode::Up => 57419,
+        KeyCode::Down => 57420,
+        KeyCode::PageUp => 57421,
+        KeyCode::PageDown => 57422,
+        KeyCode::Home => 57423,
+        KeyCode::End => 57424,
+        KeyCode::Insert => 57425,
+        KeyCode::Delete => 57426,
+        KeyCode::KeypadBegin => 57427,
+        _ => return None,
+    })
+}
+
+// The kitty key codes of the keys without a legacy encoding, see `translate_functional_key_code`.
+fn functional_code(code: KeyCode) -> Option<u32> {
+    Some(match code {
+        KeyCode::CapsLock => 57358,
+        KeyCode::ScrollLock => 57359,
+        KeyCode::NumLock => 57360,
+        KeyCode::PrintScreen => 57361,
+        KeyCode::Pause => 57362,
+        KeyCode::Menu => 57363,
+        KeyCode::F(n @ 13..=35) => 57376 + u32::from(n - 13),
+        KeyCode::KeypadBegin => 57427,
+        KeyCode::Media(media) => match media {
+            MediaKeyCode::Play => 57428,
+            MediaKeyCode::Pause => 57429,
+            MediaKeyCode::PlayPause => 57430,
+            MediaKeyCode::Reverse => 57431,
+            MediaKeyCode::Stop => 57432,
+            MediaKeyCode::FastForward => 57433,
+            MediaKeyCode::Rewind => 57434,
+            MediaKeyCode::TrackNext => 57435,
+            MediaKeyCode::TrackPrevious => 57436,
+            MediaKeyCode::Record => 57437,
+            MediaKeyCode::LowerVolume => 57438,
+            MediaKeyCode::RaiseVolume => 57439,
+            MediaKeyCode::MuteVolume => 57440,
+        },
+        KeyCode::Modifier(modifier) => match modifier {
+            ModifierKeyCode::LeftShift => 57441,
+            ModifierKeyCode::LeftControl => 57442,
+            ModifierKeyCode::LeftAlt => 57443,
+            ModifierKeyCode::LeftSuper => 57444,
+            ModifierKeyCode::LeftHyper => 57445,
+            ModifierKeyCode::LeftMeta => 57446,
+            ModifierKeyCode::RightShift => 57447,
+            ModifierKeyCode::RightControl => 57448,
+            ModifierKeyCode::RightAlt => 57449,
+            ModifierKeyCode::RightSuper => 57450,
+            ModifierKeyCode::RightHyper => 57451,
+            ModifierKeyCode::RightMeta => 57452,
+            ModifierKeyCode::IsoLevel3Shift => 57453,
+            ModifierKeyCode::IsoLevel5Shift => 57454,
+        },
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::event::{
+        AlternateKeys, Event, InputParser, KeyCode, KeyEncoding, KeyEvent, KeyEventKind,
+        KeyEventState, KeyModifiers, KeyText, KeyboardEnhancementFlags, MediaKeyCode,
+        ModifierKeyCode, MouseButton, MouseEncoding, MouseEvent, MouseEventKind,
+        MousePixelPosition,
+    };
+
+    fn parse(bytes: &[u8]) -> Vec<Event> {
+        let mut parser = InputParser::new();
+        parser.advance(bytes, false);
+        parser.collect()
+    }
+
+    fn key(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    fn assert_round_trip(event: KeyEvent, encoding: KeyEncoding) {
+        let bytes = event
+            .encode(encoding)
+            .unwrap_or_else(|| panic!("{event:?} can't be encoded with {encoding:?}"));
+        let decoded = parse(&bytes);
+        assert_eq!(
+            decoded,
+            vec![Event::Key(event)],
+            "{event:?} encoded as {:?} with {encoding:?}",
+            String::from_utf8_lossy(&bytes)
+        );
+        // The fields excluded from the equality have to survive as well.
+        if let [Event::Key(decoded)] = decoded[..] {
+            assert_eq!(decoded.kind, event.kind);
+            assert_eq!(decoded.state, event.state);
+        }
+    }
+
+    fn legacy_keys() -> Vec<KeyEvent> {
+        let mut keys = vec![
+            key(KeyCode::Char('a'), KeyModifiers::NONE),
+            key(KeyCode::Char('A'), KeyModifiers::SHIFT),
+            key(KeyCode::Char('ü'), KeyModifiers::NONE),
+            key(KeyCode::Char('c'), KeyModifiers::CONTROL),
+            key(
+                KeyCode::Char('c'),
+                KeyModifiers::CONTROL | KeyModifiers::ALT,
+            ),
+            key(KeyCode::Char('x'), KeyModifiers::ALT),
+            key(KeyCode::Char('X'), KeyModifiers::ALT | KeyModifiers::SHIFT),
+            key(KeyCode::Char(' '), KeyModifiers::CONTROL),
+            key(KeyCode::Char('4'), KeyModifiers::CONTROL),
+            key(KeyCode::Enter, KeyModifiers::NONE),
+            key(KeyCode::Enter, KeyModifiers::ALT),
+            key(KeyCode::Tab, KeyModifiers::NONE),
+            key(KeyCode::BackTab, KeyModifiers::SHIFT),
+            key(KeyCode::Backspace, KeyModifiers::NONE),
+            key(KeyCode::Backspace, KeyModifiers::ALT),
+            key(KeyCode::Esc, KeyModifiers::NONE),
+        ];
+        let modifiers = [
+            KeyModifiers::NONE,
+            KeyModifiers::SHIFT,
+            KeyModifiers::CONTROL | KeyModifiers::ALT,
+            KeyModifiers::SUPER,
+        ];
+        for code in [
+            KeyCode::Up,
+            KeyCode::Down,
+            KeyCode::Left,
+            KeyCode::Right,
+            KeyCode::Home,
+            KeyCode::End,
+            KeyCode::Insert,
+            KeyCode::Delete,
+            KeyCode::PageUp,
+            KeyCode::PageDown,
+        ]
+        .into_iter()
+        .chain((1..=12).map(KeyCode::F))
+        {
+            for modifiers in modifiers {
+                keys.push(key(code, modifiers));
+            }
+        }
+        keys
+    }
+
+    #[test]
+    fn test_legacy_round_trip() {
+        for event in legacy_keys() {
+            assert_round_trip(event, KeyEncoding::Legacy);
+        }
+    }
+
+    #[test]
+    fn test_legacy_encoding() {
+        let encode = |code, modifiers| key(code, modifiers).encode(KeyEncoding::Legacy);
+
+        assert_eq!(
+            encode(KeyCode::F(1), KeyModifiers::NONE),
+            Some(b"\x1BOP".to_vec())
+        );
+        assert_eq!(
+            encode(KeyCode::F(3), KeyModifiers::NONE),
+            Some(b"\x1BOR".to_vec())
+        );
+        assert_eq!(
+            encode(KeyCode::F(3), KeyModifiers::CONTROL),
+            Some(b"\x1B[13;5~".to_vec())
+        );
+        assert_eq!(
+            encode(KeyCode::F(12), KeyModifiers::NONE),
+            Some(b"\x1B[24~".to_vec())
+        );
+        assert_eq!(
+            encode(KeyCode::Char('a'), KeyModifiers::SHIFT),
+            Some(b"A".to_vec())
+        );
+        assert_eq!(
+            encode(KeyCode::Char('['), KeyModifiers::CONTROL),
+            Some(b"\x1B".to_vec())
+        );
+        // Modifiers without a legacy encoding are dropped.
+        assert_eq!(
+            encode(KeyCode::Char('1'), KeyModifiers::CONTROL),
+            Some(b"1".to_vec())
+        );
+
+        assert_eq!(encode(KeyCode::F(13), KeyModifiers::NONE), None);
+        assert_eq!(encode(KeyCode::CapsLock, KeyModifiers::NONE), None);
+        assert_eq!(
+            KeyEvent::new_with_kind(KeyCode::Enter, KeyModifiers::NONE, KeyEventKind::Release)
+                .encode(KeyEncoding::Legacy),
+            None
+        );
+    }
+
+    #[test]
+    fn test_modify_other_keys_round_trip() {
+        let mut keys = legacy_keys();
+        keys.extend([
+            key(KeyCode::Enter, KeyModifiers::CONTROL),
+            key(KeyCode::Enter, KeyModifiers::SHIFT),
+            key(KeyCode::Tab, KeyModifiers::CONTROL),
+            key(
+                KeyCode::BackTab,
+                KeyModifiers::SHIFT | KeyModifiers::CONTROL,
+            ),
+            key(KeyCode::Backspace, KeyModifiers::CONTROL),
+            key(KeyCode::Esc, KeyModifiers::ALT),
+            key(KeyCode::Char('1'), KeyModifiers::CONTROL),
+            key(KeyCode::Char('i'), KeyModifiers::CONTROL),
+            key(
+                KeyCode::Char('a'),
+                KeyModifiers::CONTROL | KeyModifiers::SHIFT,
+            ),
+            key(KeyCode::Char('é'), KeyModifiers::ALT),
+        ]);
+
+        for event in keys {
+            assert_round_trip(event, KeyEncoding::ModifyOtherKeys);
+        }
+    }
+
+    #[test]
+    fn test_modify_other_keys_encoding() {
+        let encode = |code, modifiers| key(code, modifiers).encode(KeyEncoding::ModifyOtherKeys);
+
+        assert_eq!(
+            encode(KeyCode::Enter, KeyModifiers::CONTROL),
+            Some(b"\x1B[27;5;13~".to_vec())
+        );
+        assert_eq!(
+            encode(KeyCode::Char('1'), KeyModifiers::CONTROL),
+            Some(b"\x1B[27;5;49~".to_vec())
+        );
+        assert_eq!(
+            encode(
+                KeyCode::Char('a'),
+                KeyModifiers::CONTROL | KeyModifiers::SHIFT
+            ),
+            Some(b"\x1B[27;6;65~".to_vec())
+        );
+        assert_eq!(
+            encode(
+                KeyCode::BackTab,
+                KeyModifiers::CONTROL | KeyModifiers::SHIFT
+            ),
+            Some(b"\x1B[27;6;9~".to_vec())
+        );
+        assert_eq!(
+            encode(KeyCode::Esc, KeyModifiers::ALT),
+            Some(b"\x1B[27;3;27~".to_vec())
+        );
+
+        // Everything else is encoded like in the legacy encoding.
+        assert_eq!(
+            encode(KeyCode::Char('A'), KeyModifiers::SHIFT),
+            Some(b"A".to_vec())
+        );
+        assert_eq!(
+            encode(KeyCode::BackTab, KeyModifiers::SHIFT),
+            Some(b"\x1B[Z".to_vec())
+        );
+        assert_eq!(
+            encode(KeyCode::Up, KeyModifiers::CONTROL),
+            Some(b"\x1B[1;5A".to_vec())
+        );
+    }
+
+    #[test]
+    fn test_kitty_round_trip() {
+        let disambiguate = KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES;
+        let all = KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES
+            | KeyboardEnhancementFlags::REPORT_EVENT_TYPES
+            | KeyboardEnhancementFlags::REPORT_ALL_KEYS_AS_ESCAPE_CODES;
+
+        let mut keys = legacy_keys();
+        keys.extend([
+            key(KeyCode::Char('i'), KeyModifiers::CONTROL),
+            key(KeyCode::Char('1'), KeyModifiers::CONTROL),
+            key(KeyCode::Char('a'), KeyModifiers::SUPER | KeyModifiers::META),
+            key(KeyCode::Enter, KeyModifiers::CONTROL),
+            key(KeyCode::Tab, KeyModifiers::CONTROL),
+            key(
+                KeyCode::BackTab,
+                KeyModifiers::SHIFT | KeyModifiers::CONTROL,
+            ),
+            key(KeyCode::Esc, KeyModifiers::ALT),
+            key(KeyCode::F(13), KeyModifiers::NONE),
+            key(KeyCode::F(35), KeyModifiers::HYPER),
+            key(KeyCode::PrintScreen, KeyModifiers::NONE),
+            key(KeyCode::Media(MediaKeyCode::PlayPause), KeyModifiers::NONE),
+            KeyEvent::new_with_kind_and_state(
+                KeyCode::Char('5'),
+                KeyModifiers::NONE,
+                KeyEventKind::Press,
+                KeyEventState::KEYPAD,
+            ),
+            KeyEvent::new_with_kind_and_state(
+                KeyCode::Enter,
+                KeyModifiers::SHIFT,
+                KeyEventKind::Press,
+                KeyEventState::KEYPAD,
+            ),
+            KeyEvent::new_with_kind_and_state(
+                KeyCode::KeypadBegin,
+                KeyModifiers::NONE,
+                KeyEventKind::Press,
+                KeyEventState::KEYPAD,
+            ),
+            KeyEvent::new_with_kind_and_state(
+                KeyCode::Char('a'),
+                KeyModifiers::CONTROL,
+                KeyEventKind::Press,
+                KeyEventState::CAPS_LOCK | KeyEventState::NUM_LOCK,
+            ),
+        ]);
+
+        for event in &keys {
+            assert_round_trip(*event, KeyEncoding::Kitty(disambiguate));
+        }
+
+        keys.extend([
+            key(
+                KeyCode::Modifier(ModifierKeyCode::LeftShift),
+                KeyModifiers::SHIFT,
+            ),
+            key(KeyCode::CapsLock, KeyModifiers::NONE),
+        ]);
+        for event in keys {
+            for kind in [
+                KeyEventKind::Press,
+                KeyEventKind::Repeat,
+                KeyEventKind::Release,
+            ] {
+                let event = KeyEvent { kind, ..event };
+                assert_round_trip(event, KeyEncoding::Kitty(all));
+            }
+        }
+    }
+
+    #[test]
+    fn test_kitty_alternate_keys() {
+        let flags = KeyEncoding::Kitty(
+            KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES
+                | KeyboardEnhancementFlags::REPORT_ALTERNATE_KEYS,
+        );
+        let with_alternates = |code, modifiers, alternate_keys| KeyEvent {
+            alternate_keys: Some(alternate_keys),
+            ..key(code, modifiers)
+        };
+
+        let events = [
+            (
+                with_alternates(
+                    KeyCode::Char('('),
+                    KeyModifiers::ALT,
+                    AlternateKeys {
+                        unshifted: KeyCode::Char('9'),
+                        shifted: Some('('),
+                        base_layout: None,
+                    },
+                ),
+                "\x1B[57:40;4u",
+            ),
+            (
+                with_alternates(
+                    KeyCode::Char('с'),
+                    KeyModifiers::CONTROL,
+                    AlternateKeys {
+                        unshifted: KeyCode::Char('с'),
+                        shifted: None,
+                        base_layout: Some(KeyCode::Char('c')),
+                    },
+                ),
+                "\x1B[1089::99;5u",
+            ),
+            (
+                with_alternates(
+                    KeyCode::Char('С'),
+                    KeyModifiers::CONTROL,
+                    AlternateKeys {
+                        unshifted: KeyCode::Char('с'),
+                        shifted: Some('С'),
+                        base_layout: Some(KeyCode::Char('c')),
+                    },
+                ),
+                "\x1B[1089:1057:99;6u",
+            ),
+        ];
+        for (event, bytes) in events {
+            assert_eq!(event.encode(flags), Some(bytes.as_bytes().to_vec()));
+            match parse(bytes.as_bytes())[..] {
+                [Event::Key(decoded)] => {
+                    assert_eq!(decoded, event);
+                    assert_eq!(decoded.alternate_keys, event.alternate_keys);
+                }
+                ref events => panic!("unexpected events {events:?}"),
+            }
+        }
+
+        // The alternate keys aren't reported without the flag.
+        assert_eq!(
+            events[0].0.encode(KeyEncoding::Kitty(
+                KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES
+            )),
+            Some(b"\x1B[40;3u".to_vec())
+        );
+    }
+
+    #[test]
+    fn test_kitty_associated_text() {
+        let flags = KeyboardEnhancementFlags::REPORT_ALL_KEYS_AS_ESCAPE_CODES
+            | KeyboardEnhancementFlags::REPORT_ASSOCIATED_TEXT;
+        let mut event = key(KeyCode::Char('e'), KeyModifiers::NONE);
+        event.text = KeyText::new("é");
+
+        let bytes = event.encode(KeyEncoding::Kitty(flags)).unwrap();
+        assert_eq!(bytes, b"\x1B[101;;233u".to_vec());
+        assert_eq!(parse(&bytes), vec![Event::Key(event)]);
+        match parse(&bytes)[..] {
+            [Event::Key(decoded)] => assert_eq!(decoded.text, KeyText::new("é")),
+            ref events => panic!("unexpected events {events:?}"),
+        }
+
+        let mut shifted = key(KeyCode::Char('a'), KeyModifiers::SHIFT);
+        shifted.text = KeyText::new("A");
+        assert_eq!(
+            shifted.encode(KeyEncoding::Kitty(flags)),
+            Some(b"\x1B[97;2;65u".to_vec())
+        );
+
+        // The text needs all keys to be reported, and is never sent for releases.
+        assert_eq!(
+            event.encode(KeyEncoding::Kitty(
+                KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES
+                    | KeyboardEnhancementFlags::REPORT_ASSOCIATED_TEXT
+            )),
+            Some(b"e".to_vec())
+        );
+        event.kind = KeyEventKind::Release;
+        assert_eq!(
+            event.encode(KeyEncoding::Kitty(
+                flags | KeyboardEnhancementFlags::REPORT_EVENT_TYPES
+            )),
+            Some(b"\x1B[101;1:3u".to_vec())
+        );
+    }
+
+    #[test]
+    fn test_kitty_encoding() {
+        let disambiguate = KeyEncoding::Kitty(KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES);
+        let event_types = KeyEncoding::Kitty(
+            KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES
+                | KeyboardEnhancementFlags::REPORT_EVENT_TYPES,
+        );
+        let all_keys =
+            KeyEncoding::Kitty(KeyboardEnhancementFlags::REPORT_ALL_KEYS_AS_ESCAPE_CODES);
+
+        let release =
+            |code| KeyEvent::new_with_kind(code, KeyModifiers::NONE, KeyEventKind::Release);
+
+        assert_eq!(
+            key(KeyCode::Char('a'), KeyModifiers::NONE).encode(disambiguate),
+            Some(b"a".to_vec())
+        );
+        assert_eq!(
+            key(KeyCode::Char('A'), KeyModifiers::SHIFT).encode(all_keys),
+            Some(b"\x1B[97;2u".to_vec())
+        );
+        assert_eq!(
+            key(KeyCode::Char('c'), KeyModifiers::CONTROL).encode(disambiguate),
+            Some(b"\x1B[99;5u".to_vec())
+        );
+        assert_eq!(
+            key(KeyCode::F(3), KeyModifiers::NONE).encode(disambiguate),
+            Some(b"\x1B[13~".to_vec())
+        );
+        assert_eq!(
+            key(KeyCode::F(1), KeyModifiers::NONE).encode(disambiguate),
+            Some(b"\x1B[P".to_vec())
+        );
+
+        // Releases are only reported with the event types, text keys only with all keys.
+        assert_eq!(release(KeyCode::Up).encode(disambiguate), None);
+        assert_eq!(
+            release(KeyCode::Up).encode(event_types),
+            Some(b"\x1B[1;1:3A".to_vec())
+        );
+        assert_eq!(release(KeyCode::Char('a')).encode(event_types), None);
+        assert_eq!(release(KeyCode::Enter).encode(event_types), None);
+
+        // Lock and modifier keys are only reported with all keys.
+        assert_eq!(
+            key(KeyCode::CapsLock, KeyModifiers::NONE).encode(disambiguate),
+            None
+        );
+        assert_eq!(
+            key(KeyCode::CapsLock, KeyModifiers::NONE).encode(all_keys),
+            Some(b"\x1B[57358u".to_vec())
+        );
+
+        // Without any flags the legacy encoding is used.
+        assert_eq!(
+            key(KeyCode::Esc, KeyModifiers::NONE)
+                .encode(KeyEncoding::Kitty(KeyboardEnhancementFlags::empty())),
+            Some(b"\x1B".to_vec())
+        );
+    }
+
+    #[test]
+    fn test_mouse_round_trip() {
+        let kinds = [
+            MouseEventKind::Down(MouseButton::Left),
+            MouseEventKind::Down(MouseButton::Middle),
+            MouseEventKind::Down(MouseButton::Right),
+            MouseEventKind::Up(MouseButton::Left),
+            MouseEventKind::Drag(MouseButton::Right),
+            MouseEventKind::Down(MouseButton::Back),
+            MouseEventKind::Down(MouseButton::Forward),
+            MouseEventKind::Down(MouseButton::Other(11)),
+            MouseEventKind::Drag(MouseButton::Other(10)),
+            MouseEventKind::Moved,
+            MouseEventKind::ScrollUp,
+            MouseEventKind::ScrollDown,
+            MouseEventKind::ScrollLeft,
+            MouseEventKind::ScrollRight,
+        ];
+        let modifiers = [
+            KeyModifiers::NONE,
+            KeyModifiers::SHIFT | KeyModifiers::ALT | KeyModifiers::CONTROL,
+        ];
+
+        for encoding in [MouseEncoding::X10, MouseEncoding::Sgr] {
+            for kind in kinds {
+                for modifiers in modifiers {
+                    let event = MouseEvent {
+                        kind,
+                        column: 0,
+                        row: 222,
+                        modifiers,
+                        pixel: None,
+                    };
+                    let bytes = event.encode(encoding).unwrap();
+                    assert_eq!(parse(&bytes), vec![Event::Mouse(event)], "{encoding:?}");
+                }
+            }
+        }
+
+        // Only SGR reports which button was released and supports large coordinates.
+        let event = MouseEvent {
+            kind: MouseEventKind::Up(MouseButton::Middle),
+            column: 1000,
+            row: 223,
+            modifiers: KeyModifiers::NONE,
+            pixel: None,
+        };
+        assert_eq!(event.encode(MouseEncoding::X10), None);
+        let bytes = event.encode(MouseEncoding::Sgr).unwrap();
+        assert_eq!(parse(&bytes), vec![Event::Mouse(event)]);
+
+        // Terminals report buttons up to 11 only.
+        let event = MouseEvent {
+            kind: MouseEventKind::Down(MouseButton::Other(12)),
+            ..event
+        };
+        assert_eq!(event.encode(MouseEncoding::Sgr), None);
+    }
+
+    #[test]
+    fn test_mouse_pixels_round_trip() {
+        let event = MouseEvent {
+            kind: MouseEventKind::Down(MouseButton::Left),
+            column: 12,
+            row: 3,
+            modifiers: KeyModifiers::NONE,
+            pixel: Some(MousePixelPosition {
+                x: 125,
+                y: 70,
+                cell_width: 10,
+                cell_height: 20,
+            }),
+        };
+        let bytes = event.encode(MouseEncoding::Sgr).unwrap();
+        assert_eq!(bytes, b"\x1B[<0;126;71M");
+
+        let mut parser = InputParser::new();
+        parser.set_mouse_cell_size(Some((10, 20)));
+        parser.advance(&bytes, false);
+        assert_eq!(parser.collect::<Vec<_>>(), vec![Event::Mouse(event)]);
+    }
+
+    #[test]
+    #[cfg(feature = "bracketed-paste")]
+    fn test_paste_round_trip() {
+        use super::encode_paste;
+
+        let encoded = encode_paste("a\x1B[201~b\r\nc");
+        assert_eq!(encoded, "\x1B[200~ab\r\nc\x1B[201~");
+        assert_eq!(
+            parse(encoded.as_bytes()),
+            vec![Event::Paste("ab\r\nc".to_string())]
+        );
+
+        let encoded = encode_paste("a\x1B[2\x1B[201~01~b");
+        assert_eq!(encoded, "\x1B[200~ab\x1B[201~");
+        assert_eq!(
+            parse(encoded.as_bytes()),
+            vec![Event::Paste("ab".to_string())]
+        );
+    }
+}
@@ -1,4 +1,5 @@
 use crate::event::internal::InternalEvent;
+use crate::terminal::ResponseMatcher;
 
 /// Interface for filtering an `InternalEvent`.
 pub(crate) trait Filter: Send + Sync + 'static {
@@ -28,13 +29,78 @@ impl Filter for KeyboardEnhancementFlagsFilter {
         // a PrimaryDeviceAttributes response. If we receive the PrimaryDeviceAttributes
         // response but not KeyboardEnhancementFlags, the terminal does not support
         // progressive keyboard enhancement.
-        matches!(
-            *event,
-            InternalEvent::KeyboardEnhancementFlags(_) | InternalEvent::PrimaryDeviceAttributes
+        matches!(*event, InternalEvent::KeyboardEnhancementFlags(_))
+            || event.is_primary_device_attributes()
+    }
+}
+
+/// Returns whether the event is the response of a query, or the primary device attributes. They
+/// are requested after the query and terminals answer in order, so receiving them first shows the
+/// query isn't supported.
+fn is_response_or_da1(
+    event: &InternalEvent,
+    is_response: impl FnOnce(&InternalEvent) -> bool,
+) -> bool {
+    is_response(event) || event.is_primary_device_attributes()
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct ForegroundColorFilter;
+
+impl Filter for ForegroundColorFilter {
+    fn eval(&self, event: &InternalEvent) -> bool {
+        is_response_or_da1(event, |event| {
+            matches!(*event, InternalEvent::ForegroundColor(_))
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct BackgroundColorFilter;
+
+impl Filter for BackgroundColorFilter {
+    fn eval(&self, event: &InternalEvent) -> bool {
+        is_response_or_da1(event, |event| {
+            matches!(*event, InternalEvent::BackgroundColor(_))
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct PaletteColorFilter(pub(crate) u8);
+
+impl Filter for PaletteColorFilter {
+    fn eval(&self, event: &InternalEvent) -> bool {
+        is_response_or_da1(
+            event,
+            |event| matches!(*event, InternalEvent::PaletteColor(index, _) if index == self.0),
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct ModeReportFilter(pub(crate) u16);
+
+impl Filter for ModeReportFilter {
+    fn eval(&self, event: &InternalEvent) -> bool {
+        is_response_or_da1(
+            event,
+            |event| matches!(*event, InternalEvent::ModeReport(mode, _) if mode == self.0),
         )
     }
 }
 
+#[cfg(feature = "osc52")]
+#[derive(Debug, Clone)]
+pub(crate) struct ClipboardContentFilter;
+
+#[cfg(feature = "osc52")]
+impl Filter for ClipboardContentFilter {
+    fn eval(&self, event: &InternalEvent) -> bool {
+        matches!(*event, InternalEvent::ClipboardContent(_))
+    }
+}
+
 #[cfg(unix)]
 #[derive(Debug, Clone)]
 pub(crate) struct PrimaryDeviceAttributesFilter;
@@ -42,22 +108,52 @@ pub(crate) struct PrimaryDeviceAttributesFilter;
 #[cfg(unix)]
 impl Filter for PrimaryDeviceAttributesFilter {
     fn eval(&self, event: &InternalEvent) -> bool {
-        matches!(*event, InternalEvent::PrimaryDeviceAttributes)
+        event.is_primary_device_attributes()
     }
 }
 
 #[derive(Debug, Clone)]
-pub(crate) struct EventFilter;
+pub(crate) struct CapabilitiesFilter;
 
-impl Filter for EventFilter {
-    #[cfg(unix)]
+impl Filter for CapabilitiesFilter {
+    fn eval(&self, event: &InternalEvent) -> bool {
+        // All responses to the capability probe, which ends with the primary device attributes.
+        matches!(
+            *event,
+            InternalEvent::Response(_)
+                | InternalEvent::ModeReport(_, _)
+                | InternalEvent::KeyboardEnhancementFlags(_)
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct ResponseFilter {
+    pub(crate) matcher: ResponseMatcher,
+    /// Whether the primary device attributes are accepted too, see `Query::sentinel`.
+    pub(crate) sentinel: bool,
+}
+
+impl Filter for ResponseFilter {
     fn eval(&self, event: &InternalEvent) -> bool {
-        matches!(*event, InternalEvent::Event(_))
+        event
+            .to_response()
+            .is_some_and(|response| self.matcher.matches(&response))
+            || (self.sentinel && event.is_primary_device_attributes())
     }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct EventFilter;
 
-    #[cfg(windows)]
-    fn eval(&self, _: &InternalEvent) -> bool {
-        true
+impl Filter for EventFilter {
+    fn eval(&self, event: &InternalEvent) -> bool {
+        // Responses no query waits for are unrecognized input, see `event::set_report_unknown`.
+        match event {
+            InternalEvent::Event(_) => true,
+            InternalEvent::Response(_) => crate::event::parse::report_unknown(),
+            _ => false,
+        }
     }
 }
 
@@ -65,9 +161,23 @@ impl Filter for EventFilter {
 #[cfg(unix)]
 mod tests {
     use super::{
-        super::Event, CursorPositionFilter, EventFilter, Filter, InternalEvent,
-        KeyboardEnhancementFlagsFilter, PrimaryDeviceAttributesFilter,
+        super::Event, BackgroundColorFilter, CapabilitiesFilter, CursorPositionFilter, EventFilter,
+        Filter, ForegroundColorFilter, InternalEvent, KeyboardEnhancementFlagsFilter,
+        ModeReportFilter, PaletteColorFilter, PrimaryDeviceAttributesFilter, ResponseFilter,
+    };
+    use crate::event::{
+        KeyboardEnhancementFlags,
+        parse::{ParseOptions, parse_event},
     };
+    use crate::style::Color;
+    use crate::terminal::{ModeState, Response, ResponseMatcher};
+
+    fn primary_device_attributes() -> InternalEvent {
+        InternalEvent::Response(Response::Csi {
+            parameters: b"?64;1;2".to_vec(),
+            final_byte: b'c',
+        })
+    }
 
     #[derive(Debug, Clone)]
     pub(crate) struct InternalEventFilter;
@@ -92,13 +202,128 @@ mod tests {
                 crate::event::KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES
             ))
         );
-        assert!(KeyboardEnhancementFlagsFilter.eval(&InternalEvent::PrimaryDeviceAttributes));
+        assert!(KeyboardEnhancementFlagsFilter.eval(&primary_device_attributes()));
     }
 
     #[test]
     fn test_primary_device_attributes_filter_filters_primary_device_attributes() {
         assert!(!PrimaryDeviceAttributesFilter.eval(&InternalEvent::Event(Event::Resize(10, 10))));
-        assert!(PrimaryDeviceAttributesFilter.eval(&InternalEvent::PrimaryDeviceAttributes));
+        assert!(PrimaryDeviceAttributesFilter.eval(&primary_device_attributes()));
+        assert!(
+            !PrimaryDeviceAttributesFilter.eval(&InternalEvent::Response(Response::Csi {
+                parameters: b">1;10;0".to_vec(),
+                final_byte: b'c',
+            }))
+        );
+    }
+
+    #[test]
+    fn test_color_filters_filter_colors() {
+        let foreground = InternalEvent::ForegroundColor(Color::Rgb { r: 0, g: 0, b: 0 });
+        let background = InternalEvent::BackgroundColor(Color::Rgb { r: 0, g: 0, b: 0 });
+
+        assert!(ForegroundColorFilter.eval(&foreground));
+        assert!(!ForegroundColorFilter.eval(&background));
+        assert!(ForegroundColorFilter.eval(&primary_device_attributes()));
+        assert!(BackgroundColorFilter.eval(&background));
+        assert!(!BackgroundColorFilter.eval(&foreground));
+        assert!(BackgroundColorFilter.eval(&primary_device_attributes()));
+    }
+
+    #[test]
+    fn test_palette_color_filter_filters_index() {
+        let color = Color::Rgb { r: 0, g: 0, b: 0 };
+
+        assert!(PaletteColorFilter(9).eval(&InternalEvent::PaletteColor(9, color)));
+        assert!(!PaletteColorFilter(9).eval(&InternalEvent::PaletteColor(1, color)));
+        assert!(!PaletteColorFilter(9).eval(&InternalEvent::BackgroundColor(color)));
+        assert!(PaletteColorFilter(9).eval(&primary_device_attributes()));
+    }
+
+    #[cfg(feature = "osc52")]
+    #[test]
+    fn test_clipboard_content_filter_filters_clipboard_content() {
+        use super::ClipboardContentFilter;
+
+        assert!(ClipboardContentFilter.eval(&InternalEvent::ClipboardContent(b"foo".to_vec())));
+        assert!(
+            !ClipboardContentFilter
+                .eval(&InternalEvent::Response(Response::Osc(b"52;c;?".to_vec())))
+        );
+        assert!(!ClipboardContentFilter.eval(&primary_device_attributes()));
+    }
+
+    #[test]
+    fn test_mode_report_filter_filters_mode() {
+        assert!(ModeReportFilter(2026).eval(&InternalEvent::ModeReport(2026, ModeState::Reset)));
+        assert!(!ModeReportFilter(2026).eval(&InternalEvent::ModeReport(2004, ModeState::Reset)));
+        assert!(ModeReportFilter(2026).eval(&primary_device_attributes()));
+    }
+
+    #[test]
+    fn test_response_filter_filters_matching_responses() {
+        let mut filter = ResponseFilter {
+            matcher: ResponseMatcher::Osc {
+                prefix: b"11;".to_vec(),
+            },
+            sentinel: false,
+        };
+        let response = InternalEvent::Response(Response::Osc(b"11;rgb:0000/0000/0000".to_vec()));
+
+        assert!(filter.eval(&response));
+        assert!(!filter.eval(&InternalEvent::Response(Response::Osc(b"10;".to_vec()))));
+        assert!(!filter.eval(&InternalEvent::Event(Event::Resize(10, 10))));
+        assert!(!filter.eval(&primary_device_attributes()));
+
+        filter.sentinel = true;
+        assert!(filter.eval(&response));
+        assert!(filter.eval(&primary_device_attributes()));
+    }
+
+    #[test]
+    fn test_response_filter_filters_decoded_responses() {
+        let options = ParseOptions {
+            awaiting_response: true,
+            ..ParseOptions::default()
+        };
+        let filter = |matcher| ResponseFilter {
+            matcher,
+            sentinel: false,
+        };
+        let background_color = filter(ResponseMatcher::Osc {
+            prefix: b"11;".to_vec(),
+        });
+        let mode_report = filter(ResponseMatcher::Csi {
+            prefix: b"?2004;".to_vec(),
+            final_byte: b'y',
+        });
+
+        for (input, matched_by_background_color) in [
+            (b"\x1B]11;rgb:1e1e/1e1e/2e2e\x1B\\".as_slice(), true),
+            (b"\x1B]10;rgb:1e1e/1e1e/2e2e\x1B\\", false),
+            (b"\x1B]4;11;rgb:1e1e/1e1e/2e2e\x1B\\", false),
+            (b"\x1B[?2004;1$y", false),
+        ] {
+            let event = parse_event(input, false, &options).unwrap().unwrap();
+            assert!(!matches!(event, InternalEvent::Response(_)));
+            assert_eq!(event.to_response().unwrap().to_bytes(), input);
+            assert_eq!(background_color.eval(&event), matched_by_background_color);
+            assert_eq!(mode_report.eval(&event), input.ends_with(b"y"));
+        }
+    }
+
+    #[test]
+    fn test_capabilities_filter_filters_responses() {
+        assert!(CapabilitiesFilter.eval(&InternalEvent::Response(Response::Dcs(b">|".to_vec()))));
+        assert!(
+            CapabilitiesFilter.eval(&InternalEvent::KeyboardEnhancementFlags(
+                KeyboardEnhancementFlags::empty()
+            ))
+        );
+        assert!(CapabilitiesFilter.eval(&InternalEvent::ModeReport(2026, ModeState::Set)));
+        assert!(CapabilitiesFilter.eval(&primary_device_attributes()));
+        assert!(!CapabilitiesFilter.eval(&InternalEvent::CursorPosition(0, 0)));
+        assert!(!CapabilitiesFilter.eval(&InternalEvent::Event(Event::Resize(10, 10))));
     }
 
     #[test]
@@ -1,21 +1,39 @@
+#[cfg(feature = "events")]
 use std::time::Duration;
 
+#[cfg(feature = "events")]
 use parking_lot::{MappedMutexGuard, Mutex, MutexGuard};
 
-#[cfg(unix)]
-use crate::event::KeyboardEnhancementFlags;
-use crate::event::{Event, filter::Filter, read::InternalEventReader, timeout::PollTimeout};
+use crate::event::{Event, KeyboardEnhancementFlags};
+#[cfg(feature = "events")]
+use crate::event::{
+    filter::Filter, read::InternalEventReader, source::InternalEventSource, timeout::PollTimeout,
+};
+use crate::style::Color;
+use crate::terminal::{ModeState, Response};
 
 /// Static instance of `InternalEventReader`.
 /// This needs to be static because there can be one event reader.
+#[cfg(feature = "events")]
 static EVENT_READER: Mutex<Option<InternalEventReader>> = parking_lot::const_mutex(None);
 
+#[cfg(feature = "events")]
 pub(crate) fn lock_event_reader() -> MappedMutexGuard<'static, InternalEventReader> {
     MutexGuard::map(EVENT_READER.lock(), |reader| {
         reader.get_or_insert_with(InternalEventReader::default)
     })
 }
 
+/// Replaces the global `InternalEventReader` with one reading from the given source.
+///
+/// `None` drops the current reader, a new one reading from the terminal is created on the next
+/// use.
+#[cfg(feature = "events")]
+pub(crate) fn replace_event_source(source: Option<Box<dyn InternalEventSource>>) {
+    *EVENT_READER.lock() = source.map(InternalEventReader::with_source);
+}
+
+#[cfg(feature = "events")]
 fn try_lock_event_reader_for(
     duration: Duration,
 ) -> Option<MappedMutexGuard<'static, InternalEventReader>> {
@@ -26,6 +44,7 @@ fn try_lock_event_reader_for(
 }
 
 /// Polls to check if there are any `InternalEvent`s that can be read within the given duration.
+#[cfg(feature = "events")]
 pub(crate) fn poll<F>(timeout: Option<Duration>, filter: &F) -> std::io::Result<bool>
 where
     F: Filter,
@@ -44,6 +63,7 @@ where
 }
 
 /// Reads a single `InternalEvent`.
+#[cfg(feature = "events")]
 pub(crate) fn read<F>(filter: &F) -> std::io::Result<InternalEvent>
 where
     F: Filter,
@@ -53,6 +73,7 @@ where
 }
 
 /// Reads a single `InternalEvent`. Non-blocking.
+#[cfg(feature = "events")]
 pub(crate) fn try_read<F>(filter: &F) -> Option<InternalEvent>
 where
     F: Filter,
@@ -70,12 +91,95 @@ pub(crate) enum InternalEvent {
     /// An event.
     Event(Event),
     /// A cursor position (`col`, `row`).
-    #[cfg(unix)]
     CursorPosition(u16, u16),
     /// The progressive keyboard enhancement flags enabled by the terminal.
-    #[cfg(unix)]
     KeyboardEnhancementFlags(KeyboardEnhancementFlags),
-    /// Attributes and architectural class of the terminal.
-    #[cfg(unix)]
-    PrimaryDeviceAttributes,
+    /// The default foreground color of the terminal.
+    ForegroundColor(Color),
+    /// The default background color of the terminal.
+    BackgroundColor(Color),
+    /// A color of the terminal's 256-color palette (`index`, `color`).
+    PaletteColor(u8, Color),
+    /// The state of a DEC private mode (`mode`, `state`).
+    ModeReport(u16, ModeState),
+    /// The decoded content of a clipboard.
+    #[cfg(feature = "osc52")]
+    ClipboardContent(Vec<u8>),
+    /// A response to a terminal query which isn't decoded by crossterm.
+    Response(Response),
+}
+
+impl InternalEvent {
+    /// Converts the event into the [`Event`] returned by the event reader. A response which wasn't
+    /// consumed by a query is unrecognized input, see `Event::Unknown`.
+    pub(crate) fn into_event(self) -> Option<Event> {
+        match self {
+            InternalEvent::Event(event) => Some(event),
+            InternalEvent::Response(response) => Some(Event::Unknown(response.to_bytes())),
+            _ => None,
+        }
+    }
+
+    /// Returns the response the event was decoded from, so that queries for responses crossterm
+    /// decodes itself are matched too. Colors are encoded with 16 bits per channel.
+    #[cfg(feature = "events")]
+    pub(crate) fn to_response(&self) -> Option<Response> {
+        let osc = |payload: String| Some(Response::Osc(payload.into_bytes()));
+        let csi = |parameters: String, final_byte| {
+            Some(Response::Csi {
+                parameters: parameters.into_bytes(),
+                final_byte,
+            })
+        };
+        let rgb = |color: &Color| match *color {
+            Color::Rgb { r, g, b } => {
+                Some(format!("rgb:{r:02x}{r:02x}/{g:02x}{g:02x}/{b:02x}{b:02x}"))
+            }
+            _ => None,
+        };
+
+        match self {
+            // ESC [ row ; column R
+            InternalEvent::CursorPosition(column, row) => csi(
+                format!("{};{}", u32::from(*row) + 1, u32::from(*column) + 1),
+                b'R',
+            ),
+            // ESC [ ? flags u
+            InternalEvent::KeyboardEnhancementFlags(flags) => {
+                csi(format!("?{}", flags.bits()), b'u')
+            }
+            // ESC ] 10 ; rgb:RRRR/GGGG/BBBB ST
+            InternalEvent::ForegroundColor(color) => osc(format!("10;{}", rgb(color)?)),
+            // ESC ] 11 ; rgb:RRRR/GGGG/BBBB ST
+            InternalEvent::BackgroundColor(color) => osc(format!("11;{}", rgb(color)?)),
+            // ESC ] 4 ; index ; rgb:RRRR/GGGG/BBBB ST
+            InternalEvent::PaletteColor(index, color) => osc(format!("4;{index};{}", rgb(color)?)),
+            // ESC [ ? mode ; value $ y
+            InternalEvent::ModeReport(mode, state) => {
+                let value = match state {
+                    ModeState::NotRecognized => 0,
+                    ModeState::Set => 1,
+                    ModeState::Reset => 2,
+                    ModeState::PermanentlySet => 3,
+                    ModeState::PermanentlyReset => 4,
+                };
+                csi(format!("?{mode};{value}$"), b'y')
+            }
+            InternalEvent::Response(response) => Some(response.clone()),
+            _ => None,
+        }
+    }
+
+    /// Returns whether the event is the terminal's primary device attributes (DA1), which are
+    /// used to detect unanswered queries.
+    ///
+    /// See <https://vt100.net/docs/vt510-rm/DA1.html>
+    #[cfg(feature = "events")]
+    pub(crate) fn is_primary_device_attributes(&self) -> bool {
+        matches!(
+            self,
+            InternalEvent::Response(Response::Csi { parameters, final_byte: b'c' })
+                if parameters.starts_with(b"?")
+        )
+    }
 }
@@ -0,0 +1,3056 @@
+use std::{
+    collections::VecDeque,
+    io,
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+};
+
+#[cfg(feature = "bracketed-paste")]
+use crate::event::PasteMode;
+use crate::event::{
+    AlternateKeys, Event, KeyCode, KeyEvent, KeyEventKind, KeyEventState, KeyModifiers, KeyText,
+    KeyboardEnhancementFlags, MediaKeyCode, ModifierKeyCode, MouseButton, MouseEvent,
+    MouseEventKind, MousePixelPosition,
+};
+
+use crate::event::internal::InternalEvent;
+use crate::style::Color;
+use crate::terminal::{ModeState, Response};
+
+// Event parsing
+//
+// This code (& previous one) are kind of ugly. We have to think about this,
+// because it's really not maintainable, no tests, etc.
+//
+// Every fn returns Result<Option<InputEvent>>
+//
+// Ok(None) -> wait for more bytes
+// Err(_) -> failed to parse event, clear the buffer
+// Ok(Some(event)) -> we have event, clear the buffer
+//
+
+fn could_not_parse_event_error() -> io::Error {
+    io::Error::other("Could not parse an event.")
+}
+
+/// Whether the terminal reports mouse positions in pixels, see `event::set_mouse_pixel_reporting`.
+static MOUSE_PIXEL_REPORTING: AtomicBool = AtomicBool::new(false);
+
+#[cfg(feature = "events")]
+pub(crate) fn set_mouse_pixel_reporting(enabled: bool) {
+    MOUSE_PIXEL_REPORTING.store(enabled, Ordering::Relaxed);
+}
+
+/// Whether the event reader reports unrecognized input as `Event::Unknown`, see
+/// `event::set_report_unknown`.
+static REPORT_UNKNOWN: AtomicBool = AtomicBool::new(false);
+
+#[cfg(feature = "events")]
+pub(crate) fn set_report_unknown(enabled: bool) {
+    REPORT_UNKNOWN.store(enabled, Ordering::Relaxed);
+}
+
+pub(crate) fn report_unknown() -> bool {
+    REPORT_UNKNOWN.load(Ordering::Relaxed)
+}
+
+/// The number of terminal queries waiting for their response, see `PendingQuery`.
+static PENDING_QUERIES: AtomicUsize = AtomicUsize::new(0);
+
+/// Marks a terminal query as waiting for its response while it's alive.
+///
+/// Terminals only send operating system commands and device control strings in response to
+/// queries, so `ESC ]` and `ESC P` are only decoded as their start while a query is waiting.
+#[cfg(all(unix, feature = "events"))]
+pub(crate) struct PendingQuery(());
+
+#[cfg(all(unix, feature = "events"))]
+impl PendingQuery {
+    pub(crate) fn new() -> PendingQuery {
+        PENDING_QUERIES.fetch_add(1, Ordering::Relaxed);
+        PendingQuery(())
+    }
+}
+
+#[cfg(all(unix, feature = "events"))]
+impl Drop for PendingQuery {
+    fn drop(&mut self) {
+        PENDING_QUERIES.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+fn awaiting_response() -> bool {
+    PENDING_QUERIES.load(Ordering::Relaxed) > 0
+}
+
+/// The longest control string kept while waiting for its end, which bounds the memory used by
+/// garbage input. It's large to fit clipboard contents.
+const MAX_CONTROL_STRING_LEN: usize = 8 << 20;
+
+/// How the event reader reports bracketed pastes, see `event::set_paste_mode`.
+#[cfg(feature = "bracketed-paste")]
+static PASTE_MODE: parking_lot::Mutex<PasteMode> = parking_lot::const_mutex(PasteMode::Whole);
+
+#[cfg(all(feature = "events", feature = "bracketed-paste"))]
+pub(crate) fn set_paste_mode(mode: PasteMode) {
+    *PASTE_MODE.lock() = mode;
+}
+
+/// Options which change how the input bytes are decoded.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct ParseOptions {
+    /// Decode a bare `\n` as Enter instead of Ctrl+J.
+    pub(crate) newline_is_enter: bool,
+    /// Decode SGR mouse positions as pixels, using the cell size `(width, height)` to derive the
+    /// cell positions.
+    pub(crate) mouse_cell_size: Option<(u16, u16)>,
+    /// Report the bytes of sequences which can't be decoded as `Event::Unknown`.
+    pub(crate) report_unknown: bool,
+    /// Report bracketed pastes as a whole or in chunks.
+    #[cfg(feature = "bracketed-paste")]
+    pub(crate) paste_mode: PasteMode,
+    /// Decode `ESC ]` and `ESC P` followed by more input as the start of a control string instead
+    /// of Alt+] and Alt+Shift+P, because a query waits for the response of the terminal.
+    pub(crate) awaiting_response: bool,
+}
+
+pub(crate) fn parse_event(
+    buffer: &[u8],
+    input_available: bool,
+    options: &ParseOptions,
+) -> io::Result<Option<InternalEvent>> {
+    if buffer.is_empty() {
+        return Ok(None);
+    }
+
+    match buffer[0] {
+        b'\x1B' => {
+            if buffer.len() == 1 {
+                if input_available {
+                    // Possible Esc sequence
+                    Ok(None)
+                } else {
+                    Ok(Some(InternalEvent::Event(Event::Key(KeyCode::Esc.into()))))
+                }
+            } else {
+                match buffer[1] {
+                    b'O' => {
+                        if buffer.len() == 2 {
+                            Ok(None)
+                        } else {
+                            match buffer[2] {
+                                b'D' => {
+                                    Ok(Some(InternalEvent::Event(Event::Key(KeyCode::Left.into()))))
+                                }
+                                b'C' => Ok(Some(InternalEvent::Event(Event::Key(
+                                    KeyCode::Right.into(),
+                                )))),
+                                b'A' => {
+                                    Ok(Some(InternalEvent::Event(Event::Key(KeyCode::Up.into()))))
+                                }
+                                b'B' => {
+                                    Ok(Some(InternalEvent::Event(Event::Key(KeyCode::Down.into()))))
+                                }
+                                b'H' => {
+                                    Ok(Some(InternalEvent::Event(Event::Key(KeyCode::Home.into()))))
+                                }
+                                b'F' => {
+                                    Ok(Some(InternalEvent::Event(Event::Key(KeyCode::End.into()))))
+                                }
+                                // F1-F4
+                                val @ b'P'..=b'S' => Ok(Some(InternalEvent::Event(Event::Key(
+                                    KeyCode::F(1 + val - b'P').into(),
+                                )))),
+                                _ => Err(could_not_parse_event_error()),
+                            }
+                        }
+                    }
+                    // Unknown CSI sequences are passed on as responses to terminal queries.
+                    b'[' => parse_csi(buffer, options)
+                        .or_else(|error| parse_csi_response(buffer).map_err(|_| error)),
+                    // The terminal only sends operating system commands and device control
+                    // strings in response to queries, otherwise `ESC ]` or `ESC P` is a key press.
+                    b']' | b'P'
+                        if buffer.len() > 2 || (input_available && options.awaiting_response) =>
+                    {
+                        parse_control_string(buffer)
+                    }
+                    b'\x1B' => Ok(Some(InternalEvent::Event(Event::Key(KeyCode::Esc.into())))),
+                    _ => parse_event(&buffer[1..], input_available, options).map(|event_option| {
+                        event_option.map(|event| {
+                            if let InternalEvent::Event(Event::Key(key_event)) = event {
+                                let mut alt_key_event = key_event;
+                                alt_key_event.modifiers |= KeyModifiers::ALT;
+                                InternalEvent::Event(Event::Key(alt_key_event))
+                            } else {
+                                event
+                            }
+                        })
+                    }),
+                }
+            }
+        }
+        b'\r' => Ok(Some(InternalEvent::Event(Event::Key(
+            KeyCode::Enter.into(),
+        )))),
+        // Issue #371: \n = 0xA, which is also the keycode for Ctrl+J. The only reason we get
+        // newlines as input is because the terminal converts \r into \n for us. When we
+        // enter raw mode, we disable that, so \n no longer has any meaning - it's better to
+        // use Ctrl+J. Waiting to handle it here means it gets picked up later
+        b'\n' if options.newline_is_enter => Ok(Some(InternalEvent::Event(Event::Key(
+            KeyCode::Enter.into(),
+        )))),
+        b'\t' => Ok(Some(InternalEvent::Event(Event::Key(KeyCode::Tab.into())))),
+        b'\x7F' => Ok(Some(InternalEvent::Event(Event::Key(
+            KeyCode::Backspace.into(),
+        )))),
+        c @ b'\x01'..=b'\x1A' => Ok(Some(InternalEvent::Event(Event::Key(KeyEvent::new(
+            KeyCode::Char((c - 0x1 + b'a') as char),
+            KeyModifiers::CONTROL,
+        ))))),
+        c @ b'\x1C'..=b'\x1F' => Ok(Some(InternalEvent::Event(Event::Key(KeyEvent::new(
+            KeyCode::Char((c - 0x1C + b'4') as char),
+            KeyModifiers::CONTROL,
+        ))))),
+        b'\0' => Ok(Some(InternalEvent::Event(Event::Key(KeyEvent::new(
+            KeyCode::Char(' '),
+            KeyModifiers::CONTROL,
+        ))))),
+        _ => parse_utf8_char(buffer).map(|maybe_char| {
+            maybe_char
+                .map(KeyCode::Char)
+                .map(char_code_to_event)
+                .map(Event::Key)
+                .map(InternalEvent::Event)
+        }),
+    }
+}
+
+// converts KeyCode to KeyEvent (adds shift modifier in case of uppercase characters)
+fn char_code_to_event(code: KeyCode) -> KeyEvent {
+    let modifiers = match code {
+        KeyCode::Char(c) if c.is_uppercase() => KeyModifiers::SHIFT,
+        _ => KeyModifiers::empty(),
+    };
+    KeyEvent::new(code, modifiers)
+}
+
+pub(crate) fn parse_csi(
+    buffer: &[u8],
+    options: &ParseOptions,
+) -> io::Result<Option<InternalEvent>> {
+    assert!(buffer.starts_with(b"\x1B[")); // ESC [
+
+    if buffer.len() == 2 {
+        return Ok(None);
+    }
+
+    let input_event = match buffer[2] {
+        b'[' => {
+            if buffer.len() == 3 {
+                None
+            } else {
+                match buffer[3] {
+                    // NOTE (@imdaveho): cannot find when this occurs;
+                    // having another '[' after ESC[ not a likely scenario
+                    val @ b'A'..=b'E' => Some(Event::Key(KeyCode::F(1 + val - b'A').into())),
+                    _ => return Err(could_not_parse_event_error()),
+                }
+            }
+        }
+        b'D' => Some(Event::Key(KeyCode::Left.into())),
+        b'C' => Some(Event::Key(KeyCode::Right.into())),
+        b'A' => Some(Event::Key(KeyCode::Up.into())),
+        b'B' => Some(Event::Key(KeyCode::Down.into())),
+        b'H' => Some(Event::Key(KeyCode::Home.into())),
+        b'F' => Some(Event::Key(KeyCode::End.into())),
+        b'Z' => Some(Event::Key(KeyEvent::new_with_kind(
+            KeyCode::BackTab,
+            KeyModifiers::SHIFT,
+            KeyEventKind::Press,
+        ))),
+        b'M' => return parse_csi_normal_mouse(buffer),
+        b'<' => return parse_csi_sgr_mouse(buffer, options),
+        b'I' => Some(Event::FocusGained),
+        b'O' => Some(Event::FocusLost),
+        b';' => return parse_csi_modifier_key_code(buffer),
+        // P, Q, and S for compatibility with Kitty keyboard protocol,
+        // as the 1 in 'CSI 1 P' etc. must be omitted if there are no
+        // modifiers pressed:
+        // https://sw.kovidgoyal.net/kitty/keyboard-protocol/#legacy-functional-keys
+        b'P' => Some(Event::Key(KeyCode::F(1).into())),
+        b'Q' => Some(Event::Key(KeyCode::F(2).into())),
+        b'S' => Some(Event::Key(KeyCode::F(4).into())),
+        b'?' => match buffer[buffer.len() - 1] {
+            b'u' => return parse_csi_keyboard_enhancement_flags(buffer),
+            b'y' if buffer.ends_with(b"$y") => return parse_csi_mode_report(buffer),
+            64..=126 => return parse_csi_response(buffer),
+            _ => None,
+        },
+        b'=' | b'>' => match buffer[buffer.len() - 1] {
+            64..=126 => return parse_csi_response(buffer),
+            _ => None,
+        },
+        b'0'..=b'9' => {
+            // Numbered escape code.
+            if buffer.len() == 3 {
+                None
+            } else {
+                // The final byte of a CSI sequence can be in the range 64-126, so
+                // let's keep reading anything else.
+                let last_byte = buffer[buffer.len() - 1];
+                if !(64..=126).contains(&last_byte) {
+                    None
+                } else {
+                    #[cfg(feature = "bracketed-paste")]
+                    if buffer.starts_with(b"\x1B[200~") {
+                        return parse_csi_bracketed_paste(buffer);
+                    }
+                    match last_byte {
+                        b'M' => return parse_csi_rxvt_mouse(buffer),
+                        b'~' => return parse_csi_special_key_code(buffer, options),
+                        b'u' => return parse_csi_u_encoded_key_code(buffer, options),
+                        b'R' => return parse_csi_cursor_position(buffer),
+                        _ => return parse_csi_modifier_key_code(buffer),
+                    }
+                }
+            }
+        }
+        _ => return Err(could_not_parse_event_error()),
+    };
+
+    Ok(input_event.map(InternalEvent::Event))
+}
+
+pub(crate) fn next_parsed<T>(iter: &mut dyn Iterator<Item = &str>) -> io::Result<T>
+where
+    T: std::str::FromStr,
+{
+    iter.next()
+        .ok_or_else(could_not_parse_event_error)?
+        .parse::<T>()
+        .map_err(|_| could_not_parse_event_error())
+}
+
+fn modifier_and_kind_parsed(iter: &mut dyn Iterator<Item = &str>) -> io::Result<(u8, u8)> {
+    let mut sub_split = iter
+        .next()
+        .ok_or_else(could_not_parse_event_error)?
+        .split(':');
+
+    let modifier_mask = next_parsed::<u8>(&mut sub_split)?;
+
+    if let Ok(kind_code) = next_parsed::<u8>(&mut sub_split) {
+        Ok((modifier_mask, kind_code))
+    } else {
+        Ok((modifier_mask, 1))
+    }
+}
+
+pub(crate) fn parse_csi_cursor_position(buffer: &[u8]) -> io::Result<Option<InternalEvent>> {
+    // ESC [ Cy ; Cx R
+    //   Cy - cursor row number (starting from 1)
+    //   Cx - cursor column number (starting from 1)
+    assert!(buffer.starts_with(b"\x1B[")); // ESC [
+    assert!(buffer.ends_with(b"R"));
+
+    let s = std::str::from_utf8(&buffer[2..buffer.len() - 1])
+        .map_err(|_| could_not_parse_event_error())?;
+
+    let mut split = s.split(';');
+
+    let y = next_parsed::<u16>(&mut split)?.saturating_sub(1);
+    let x = next_parsed::<u16>(&mut split)?.saturating_sub(1);
+
+    Ok(Some(InternalEvent::CursorPosition(x, y)))
+}
+
+fn parse_csi_keyboard_enhancement_flags(buffer: &[u8]) -> io::Result<Option<InternalEvent>> {
+    // ESC [ ? flags u
+    assert!(buffer.starts_with(b"\x1B[?")); // ESC [ ?
+    assert!(buffer.ends_with(b"u"));
+
+    if buffer.len() < 5 {
+        return Ok(None);
+    }
+
+    let bits = std::str::from_utf8(&buffer[3..buffer.len() - 1])
+        .ok()
+        .and_then(|bits| bits.parse::<u8>().ok())
+        .ok_or_else(could_not_parse_event_error)?;
+    let mut flags = KeyboardEnhancementFlags::empty();
+
+    if bits & 1 != 0 {
+        flags |= KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES;
+    }
+    if bits & 2 != 0 {
+        flags |= KeyboardEnhancementFlags::REPORT_EVENT_TYPES;
+    }
+    if bits & 4 != 0 {
+        flags |= KeyboardEnhancementFlags::REPORT_ALTERNATE_KEYS;
+    }
+    if bits & 8 != 0 {
+        flags |= KeyboardEnhancementFlags::REPORT_ALL_KEYS_AS_ESCAPE_CODES;
+    }
+    if bits & 16 != 0 {
+        flags |= KeyboardEnhancementFlags::REPORT_ASSOCIATED_TEXT;
+    }
+
+    Ok(Some(InternalEvent::KeyboardEnhancementFlags(flags)))
+}
+
+fn parse_csi_mode_report(buffer: &[u8]) -> io::Result<Option<InternalEvent>> {
+    // ESC [ ? mode ; value $ y
+    assert!(buffer.starts_with(b"\x1B[?")); // ESC [ ?
+    assert!(buffer.ends_with(b"$y"));
+
+    let s = std::str::from_utf8(&buffer[3..buffer.len() - 2])
+        .map_err(|_| could_not_parse_event_error())?;
+    let mut split = s.split(';');
+
+    let mode = next_parsed::<u16>(&mut split)?;
+    let state = match next_parsed::<u8>(&mut split)? {
+        0 => ModeState::NotRecognized,
+        1 => ModeState::Set,
+        2 => ModeState::Reset,
+        3 => ModeState::PermanentlySet,
+        4 => ModeState::PermanentlyReset,
+        _ => return Err(could_not_parse_event_error()),
+    };
+
+    Ok(Some(InternalEvent::ModeReport(mode, state)))
+}
+
+fn parse_csi_response(buffer: &[u8]) -> io::Result<Option<InternalEvent>> {
+    // ESC [ parameter-bytes intermediate-bytes final-byte
+    //
+    // A CSI sequence crossterm doesn't know, e.g. the primary device attributes
+    // `ESC [ ? 64 ; attr1 ; ... ; attrn c`. It's reported as-is for terminal queries.
+    assert!(buffer.starts_with(b"\x1B[")); // ESC [
+
+    let (&final_byte, parameters) = buffer[2..]
+        .split_last()
+        .ok_or_else(could_not_parse_event_error)?;
+    if !(64..=126).contains(&final_byte)
+        || !parameters.iter().all(|byte| (0x20..=0x3F).contains(byte))
+    {
+        return Err(could_not_parse_event_error());
+    }
+
+    Ok(Some(InternalEvent::Response(Response::Csi {
+        parameters: parameters.to_vec(),
+        final_byte,
+    })))
+}
+
+fn parse_control_string(buffer: &[u8]) -> io::Result<Option<InternalEvent>> {
+    // ESC ] payload (BEL | ESC \)     Operating system command
+    // ESC P payload ESC \             Device control string
+    assert!(buffer.starts_with(b"\x1B]") || buffer.starts_with(b"\x1BP"));
+    let osc = buffer[1] == b']';
+
+    // The payload can't contain control characters, the first one has to be the terminator.
+    let Some(end) = buffer[2..].iter().position(|byte| *byte < 0x20) else {
+        if buffer.len() > MAX_CONTROL_STRING_LEN {
+            return Err(could_not_parse_event_error());
+        }
+        return Ok(None);
+    };
+    let payload = buffer[2..2 + end].to_vec();
+    match &buffer[2 + end..] {
+        [b'\x07', ..] if osc => {}
+        [b'\x1B'] => return Ok(None),
+        [b'\x1B', b'\\', ..] => {}
+        _ => return Err(could_not_parse_event_error()),
+    }
+
+    if osc {
+        if let Some(event) = parse_osc_color(&payload) {
+            return Ok(Some(event));
+        }
+        #[cfg(feature = "osc52")]
+        if let Some(event) = parse_osc_clipboard(&payload) {
+            return Ok(Some(event));
+        }
+    }
+
+    Ok(Some(InternalEvent::Response(if osc {
+        Response::Osc(payload)
+    } else {
+        Response::Dcs(payload)
+    })))
+}
+
+#[cfg(feature = "osc52")]
+fn parse_osc_clipboard(payload: &[u8]) -> Option<InternalEvent> {
+    use base64::prelude::{BASE64_STANDARD, Engine};
+
+    // OSC 52 ; selection ; base64 ST       Clipboard content
+    let (_, data) = std::str::from_utf8(payload.strip_prefix(b"52;")?)
+        .ok()?
+        .split_once(';')?;
+    Some(InternalEvent::ClipboardContent(
+        BASE64_STANDARD.decode(data).ok()?,
+    ))
+}
+
+fn parse_osc_color(payload: &[u8]) -> Option<InternalEvent> {
+    // OSC 4 ; index ; rgb:RRRR/GGGG/BBBB ST    Palette color
+    // OSC 10 ; rgb:RRRR/GGGG/BBBB ST           Default foreground color
+    // OSC 11 ; rgb:RRRR/GGGG/BBBB ST           Default background color
+    let (code, spec) = std::str::from_utf8(payload).ok()?.split_once(';')?;
+
+    match code {
+        "4" => {
+            let (index, spec) = spec.split_once(';')?;
+            Some(InternalEvent::PaletteColor(
+                index.parse().ok()?,
+                Color::parse_x11_rgb(spec)?,
+            ))
+        }
+        "10" => Some(InternalEvent::ForegroundColor(Color::parse_x11_rgb(spec)?)),
+        "11" => Some(InternalEvent::BackgroundColor(Color::parse_x11_rgb(spec)?)),
+        _ => None,
+    }
+}
+
+fn parse_modifiers(mask: u8) -> KeyModifiers {
+    let modifier_mask = mask.saturating_sub(1);
+    let mut modifiers = KeyModifiers::empty();
+    if modifier_mask & 1 != 0 {
+        modifiers |= KeyModifiers::SHIFT;
+    }
+    if modifier_mask & 2 != 0 {
+        modifiers |= KeyModifiers::ALT;
+    }
+    if modifier_mask & 4 != 0 {
+        modifiers |= KeyModifiers::CONTROL;
+    }
+    if modifier_mask & 8 != 0 {
+        modifiers |= KeyModifiers::SUPER;
+    }
+    if modifier_mask & 16 != 0 {
+        modifiers |= KeyModifiers::HYPER;
+    }
+    if modifier_mask & 32 != 0 {
+        modifiers |= KeyModifiers::META;
+    }
+    modifiers
+}
+
+fn parse_modifiers_to_state(mask: u8) -> KeyEventState {
+    let modifier_mask = mask.saturating_sub(1);
+    let mut state = KeyEventState::empty();
+    if modifier_mask & 64 != 0 {
+        state |= KeyEventState::CAPS_LOCK;
+    }
+    if modifier_mask & 128 != 0 {
+        state |= KeyEventState::NUM_LOCK;
+    }
+    state
+}
+
+fn parse_key_event_kind(kind: u8) -> KeyEventKind {
+    match kind {
+        1 => KeyEventKind::Press,
+        2 => KeyEventKind::Repeat,
+        3 => KeyEventKind::Release,
+        _ => KeyEventKind::Press,
+    }
+}
+
+pub(crate) fn parse_csi_modifier_key_code(buffer: &[u8]) -> io::Result<Option<InternalEvent>> {
+    assert!(buffer.starts_with(b"\x1B[")); // ESC [
+    let s = std::str::from_utf8(&buffer[2..buffer.len() - 1])
+        .map_err(|_| could_not_parse_event_error())?;
+    let mut split = s.split(';');
+
+    split.next();
+
+    let (modifiers, kind) =
+        if let Ok((modifier_mask, kind_code)) = modifier_and_kind_parsed(&mut split) {
+            (
+                parse_modifiers(modifier_mask),
+                parse_key_event_kind(kind_code),
+            )
+        } else if buffer.len() > 3 {
+            (
+                parse_modifiers(
+                    (buffer[buffer.len() - 2] as char)
+                        .to_digit(10)
+                        .ok_or_else(could_not_parse_event_error)? as u8,
+                ),
+                KeyEventKind::Press,
+            )
+        } else {
+            (KeyModifiers::NONE, KeyEventKind::Press)
+        };
+    let key = buffer[buffer.len() - 1];
+
+    let keycode = match key {
+        b'A' => KeyCode::Up,
+        b'B' => KeyCode::Down,
+        b'C' => KeyCode::Right,
+        b'D' => KeyCode::Left,
+        b'F' => KeyCode::End,
+        b'H' => KeyCode::Home,
+        b'P' => KeyCode::F(1),
+        b'Q' => KeyCode::F(2),
+        b'R' => KeyCode::F(3),
+        b'S' => KeyCode::F(4),
+        _ => return Err(could_not_parse_event_error()),
+    };
+
+    let input_event = Event::Key(KeyEvent::new_with_kind(keycode, modifiers, kind));
+
+    Ok(Some(InternalEvent::Event(input_event)))
+}
+
+fn translate_functional_key_code(codepoint: u32) -> Option<(KeyCode, KeyEventState)> {
+    if let Some(keycode) = match codepoint {
+        57399 => Some(KeyCode::Char('0')),
+        57400 => Some(KeyCode::Char('1')),
+        57401 => Some(KeyCode::Char('2')),
+        57402 => Some(KeyCode::Char('3')),
+        57403 => Some(KeyCode::Char('4')),
+        57404 => Some(KeyCode::Char('5')),
+        57405 => Some(KeyCode::Char('6')),
+        57406 => Some(KeyCode::Char('7')),
+        57407 => Some(KeyCode::Char('8')),
+        57408 => Some(KeyCode::Char('9')),
+        57409 => Some(KeyCode::Char('.')),
+        57410 => Some(KeyCode::Char('/')),
+        57411 => Some(KeyCode::Char('*')),
+        57412 => Some(KeyCode::Char('-')),
+        57413 => Some(KeyCode::Char('+')),
+        57414 => Some(KeyCode::Enter),
+        57415 => Some(KeyCode::Char('=')),
+        57416 => Some(KeyCode::Char(',')),
+        57417 => Some(KeyCode::Left),
+        57418 => Some(KeyCode::Right),
+        57419 => Some(KeyCode::Up),
+        57420 => Some(KeyCode::Down),
+        57421 => Some(KeyCode::PageUp),
+        57422 => Some(KeyCode::PageDown),
+        57423 => Some(KeyCode::Home),
+        57424 => Some(KeyCode::End),
+        57425 => Some(KeyCode::Insert),
+        57426 => Some(KeyCode::Delete),
+        57427 => Some(KeyCode::KeypadBegin),
+        _ => None,
+    } {
+        return Some((keycode, KeyEventState::KEYPAD));
+    }
+
+    if let Some(keycode) = match codepoint {
+        57358 => Some(KeyCode::CapsLock),
+        57359 => Some(KeyCode::ScrollLock),
+        57360 => Some(KeyCode::NumLock),
+        57361 => Some(KeyCode::PrintScreen),
+        57362 => Some(KeyCode::Pause),
+        57363 => Some(KeyCode::Menu),
+        57376 => Some(KeyCode::F(13)),
+        57377 => Some(KeyCode::F(14)),
+        57378 => Some(KeyCode::F(15)),
+        57379 => Some(KeyCode::F(16)),
+        57380 => Some(KeyCode::F(17)),
+        57381 => Some(KeyCode::F(18)),
+        57382 => Some(KeyCode::F(19)),
+        57383 => Some(KeyCode::F(20)),
+        57384 => Some(KeyCode::F(21)),
+        57385 => Some(KeyCode::F(22)),
+        57386 => Some(KeyCode::F(23)),
+        57387 => Some(KeyCode::F(24)),
+        57388 => Some(KeyCode::F(25)),
+        57389 => Some(KeyCode::F(26)),
+        57390 => Some(KeyCode::F(27)),
+        57391 => Some(KeyCode::F(28)),
+        57392 => Some(KeyCode::F(29)),
+        57393 => Some(KeyCode::F(30)),
+        57394 => Some(KeyCode::F(31)),
+        57395 => Some(KeyCode::F(32)),
+        57396 => Some(KeyCode::F(33)),
+        57397 => Some(KeyCode::F(34)),
+        57398 => Some(KeyCode::F(35)),
+        57428 => Some(KeyCode::Media(MediaKeyCode::Play)),
+        57429 => Some(KeyCode::Media(MediaKeyCode::Pause)),
+        57430 => Some(KeyCode::Media(MediaKeyCode::PlayPause)),
+        57431 => Some(KeyCode::Media(MediaKeyCode::Reverse)),
+        57432 => Some(KeyCode::Media(MediaKeyCode::Stop)),
+        57433 => Some(KeyCode::Media(MediaKeyCode::FastForward)),
+        57434 => Some(KeyCode::Media(MediaKeyCode::Rewind)),
+        57435 => Some(KeyCode::Media(MediaKeyCode::TrackNext)),
+        57436 => Some(KeyCode::Media(MediaKeyCode::TrackPrevious)),
+        57437 => Some(KeyCode::Media(MediaKeyCode::Record)),
+        57438 => Some(KeyCode::Media(MediaKeyCode::LowerVolume)),
+        57439 => Some(KeyCode::Media(MediaKeyCode::RaiseVolume)),
+        57440 => Some(KeyCode::Media(MediaKeyCode::MuteVolume)),
+        57441 => Some(KeyCode::Modifier(ModifierKeyCode::LeftShift)),
+        57442 => Some(KeyCode::Modifier(ModifierKeyCode::LeftControl)),
+        57443 => Some(KeyCode::Modifier(ModifierKeyCode::LeftAlt)),
+        57444 => Some(KeyCode::Modifier(ModifierKeyCode::LeftSuper)),
+        57445 => Some(KeyCode::Modifier(ModifierKeyCode::LeftHyper)),
+        57446 => Some(KeyCode::Modifier(ModifierKeyCode::LeftMeta)),
+        57447 => Some(KeyCode::Modifier(ModifierKeyCode::RightShift)),
+        57448 => Some(KeyCode::Modifier(ModifierKeyCode::RightControl)),
+        57449 => Some(KeyCode::Modifier(ModifierKeyCode::RightAlt)),
+        57450 => Some(KeyCode::Modifier(ModifierKeyCode::RightSuper)),
+        57451 => Some(KeyCode::Modifier(ModifierKeyCode::RightHyper)),
+        57452 => Some(KeyCode::Modifier(ModifierKeyCode::RightMeta)),
+        57453 => Some(KeyCode::Modifier(ModifierKeyCode::IsoLevel3Shift)),
+        57454 => Some(KeyCode::Modifier(ModifierKeyCode::IsoLevel5Shift)),
+        _ => None,
+    } {
+        return Some((keycode, KeyEventState::empty()));
+    }
+
+    None
+}
+
+pub(crate) fn parse_csi_u_encoded_key_code(
+    buffer: &[u8],
+    options: &ParseOptions,
+) -> io::Result<Option<InternalEvent>> {
+    assert!(buffer.starts_with(b"\x1B[")); // ESC [
+    assert!(buffer.ends_with(b"u"));
+
+    // This function parses `CSI … u` sequences. These are sequences defined in either
+    // the `CSI u` (a.k.a. "Fix Keyboard Input on Terminals - Please", https://www.leonerd.org.uk/hacks/fixterms/)
+    // or Kitty Keyboard Protocol (https://sw.kovidgoyal.net/kitty/keyboard-protocol/) specifications.
+    // This CSI sequence is a tuple of semicolon-separated numbers.
+    let s = std::str::from_utf8(&buffer[2..buffer.len() - 1])
+        .map_err(|_| could_not_parse_event_error())?;
+    let mut split = s.split(';');
+
+    // In `CSI u`, this is parsed as:
+    //
+    //     CSI codepoint ; modifiers u
+    //     codepoint: ASCII Dec value
+    //
+    // The Kitty Keyboard Protocol extends this with optional components that can be
+    // enabled progressively. The full sequence is parsed as:
+    //
+    //     CSI unicode-key-code:alternate-key-codes ; modifiers:event-type ; text-as-codepoints u
+    let mut codepoints = split
+        .next()
+        .ok_or_else(could_not_parse_event_error)?
+        .split(':');
+
+    let codepoint = codepoints
+        .next()
+        .ok_or_else(could_not_parse_event_error)?
+        .parse::<u32>()
+        .map_err(|_| could_not_parse_event_error())?;
+
+    let (mut modifiers, kind, state_from_modifiers) =
+        if let Ok((modifier_mask, kind_code)) = modifier_and_kind_parsed(&mut split) {
+            (
+                parse_modifiers(modifier_mask),
+                parse_key_event_kind(kind_code),
+                parse_modifiers_to_state(modifier_mask),
+            )
+        } else {
+            (KeyModifiers::NONE, KeyEventKind::Press, KeyEventState::NONE)
+        };
+
+    let (mut keycode, state_from_keycode) = parse_csi_u_key_code(codepoint, modifiers, options)
+        .ok_or_else(could_not_parse_event_error)?;
+
+    if let KeyCode::Modifier(modifier_keycode) = keycode {
+        match modifier_keycode {
+            ModifierKeyCode::LeftAlt | ModifierKeyCode::RightAlt => {
+                modifiers.set(KeyModifiers::ALT, true)
+            }
+            ModifierKeyCode::LeftControl | ModifierKeyCode::RightControl => {
+                modifiers.set(KeyModifiers::CONTROL, true)
+            }
+            ModifierKeyCode::LeftShift | ModifierKeyCode::RightShift => {
+                modifiers.set(KeyModifiers::SHIFT, true)
+            }
+            ModifierKeyCode::LeftSuper | ModifierKeyCode::RightSuper => {
+                modifiers.set(KeyModifiers::SUPER, true)
+            }
+            ModifierKeyCode::LeftHyper | ModifierKeyCode::RightHyper => {
+                modifiers.set(KeyModifiers::HYPER, true)
+            }
+            ModifierKeyCode::LeftMeta | ModifierKeyCode::RightMeta => {
+                modifiers.set(KeyModifiers::META, true)
+            }
+            _ => {}
+        }
+    }
+
+    // When the "report alternate keys" flag is enabled in the Kitty Keyboard Protocol, the
+    // sequence contains up to two additional codepoints separated by ':' characters: the
+    // shifted character according to the keyboard layout (only when the event contains shift,
+    // possibly empty) and the key in the standard PC-101 layout.
+    let shifted = codepoints
+        .next()
+        .and_then(|codepoint| codepoint.parse::<u32>().ok())
+        .and_then(char::from_u32);
+    let base_layout = codepoints
+        .next()
+        .and_then(|codepoint| codepoint.parse::<u32>().ok())
+        .and_then(|codepoint| parse_csi_u_key_code(codepoint, modifiers, options))
+        .map(|(keycode, _)| keycode);
+    let alternate_keys = (shifted.is_some() || base_layout.is_some()).then_some(AlternateKeys {
+        unshifted: keycode,
+        shifted,
+        base_layout,
+    });
+
+    // The shifted character replaces the key code, shift is then part of it.
+    if modifiers.contains(KeyModifiers::SHIFT) {
+        if let Some(shifted_c) = shifted {
+            keycode = KeyCode::Char(shifted_c);
+            modifiers.set(KeyModifiers::SHIFT, false);
+        }
+    }
+
+    let mut key_event = KeyEvent::new_with_kind_and_state(
+        keycode,
+        modifiers,
+        kind,
+        state_from_keycode | state_from_modifiers,
+    );
+    // When the "report associated text" flag is enabled in the Kitty Keyboard Protocol, the
+    // text produced by the key is sent as colon-separated codepoints in the third field.
+    key_event.text = split.next().and_then(parse_associated_text);
+    key_event.alternate_keys = alternate_keys;
+
+    Ok(Some(InternalEvent::Event(Event::Key(key_event))))
+}
+
+// Translates a `CSI u` unicode key code to the key it represents.
+fn parse_csi_u_key_code(
+    codepoint: u32,
+    modifiers: KeyModifiers,
+    options: &ParseOptions,
+) -> Option<(KeyCode, KeyEventState)> {
+    if let Some(keycode) = translate_functional_key_code(codepoint) {
+        return Some(keycode);
+    }
+
+    let keycode = match char::from_u32(codepoint)? {
+        '\x1B' => KeyCode::Esc,
+        '\r' => KeyCode::Enter,
+        // Issue #371: \n = 0xA, which is also the keycode for Ctrl+J. The only reason we get
+        // newlines as input is because the terminal converts \r into \n for us. When we
+        // enter raw mode, we disable that, so \n no longer has any meaning - it's better to
+        // use Ctrl+J. Waiting to handle it here means it gets picked up later
+        '\n' if options.newline_is_enter => KeyCode::Enter,
+        '\t' => {
+            if modifiers.contains(KeyModifiers::SHIFT) {
+                KeyCode::BackTab
+            } else {
+                KeyCode::Tab
+            }
+        }
+        '\x7F' => KeyCode::Backspace,
+        c => KeyCode::Char(c),
+    };
+    Some((keycode, KeyEventState::empty()))
+}
+
+fn parse_associated_text(codepoints: &str) -> Option<KeyText> {
+    let mut text = String::new();
+    for codepoint in codepoints.split(':') {
+        text.push(char::from_u32(codepoint.parse::<u32>().ok()?)?);
+    }
+    KeyText::new(&text)
+}
+
+pub(crate) fn parse_csi_special_key_code(
+    buffer: &[u8],
+    options: &ParseOptions,
+) -> io::Result<Option<InternalEvent>> {
+    assert!(buffer.starts_with(b"\x1B[")); // ESC [
+    assert!(buffer.ends_with(b"~"));
+
+    let s = std::str::from_utf8(&buffer[2..buffer.len() - 1])
+        .map_err(|_| could_not_parse_event_error())?;
+    let mut split = s.split(';');
+
+    // This CSI sequence can be a list of semicolon-separated numbers.
+    let first = next_parsed::<u8>(&mut split)?;
+
+    let (modifiers, kind, state) =
+        if let Ok((modifier_mask, kind_code)) = modifier_and_kind_parsed(&mut split) {
+            (
+                parse_modifiers(modifier_mask),
+                parse_key_event_kind(kind_code),
+                parse_modifiers_to_state(modifier_mask),
+            )
+        } else {
+            (KeyModifiers::NONE, KeyEventKind::Press, KeyEventState::NONE)
+        };
+
+    let keycode = match first {
+        // xterm's modifyOtherKeys sends modified keys without a legacy encoding as
+        // `CSI 27 ; modifiers ; codepoint ~`, see https://invisible-island.net/xterm/modified-keys.html
+        27 => {
+            let codepoint = next_parsed::<u32>(&mut split)?;
+            parse_csi_u_key_code(codepoint, modifiers, options)
+                .ok_or_else(could_not_parse_event_error)?
+                .0
+        }
+        1 | 7 => KeyCode::Home,
+        2 => KeyCode::Insert,
+        3 => KeyCode::Delete,
+        4 | 8 => KeyCode::End,
+        5 => KeyCode::PageUp,
+        6 => KeyCode::PageDown,
+        v @ 11..=15 => KeyCode::F(v - 10),
+        v @ 17..=21 => KeyCode::F(v - 11),
+        v @ 23..=26 => KeyCode::F(v - 12),
+        v @ 28..=29 => KeyCode::F(v - 15),
+        v @ 31..=34 => KeyCode::F(v - 17),
+        _ => return Err(could_not_parse_event_error()),
+    };
+
+    let input_event = Event::Key(KeyEvent::new_with_kind_and_state(
+        keycode, modifiers, kind, state,
+    ));
+
+    Ok(Some(InternalEvent::Event(input_event)))
+}
+
+pub(crate) fn parse_csi_rxvt_mouse(buffer: &[u8]) -> io::Result<Option<InternalEvent>> {
+    // rxvt mouse encoding:
+    // ESC [ Cb ; Cx ; Cy ; M
+
+    assert!(buffer.starts_with(b"\x1B[")); // ESC [
+    assert!(buffer.ends_with(b"M"));
+
+    let s = std::str::from_utf8(&buffer[2..buffer.len() - 1])
+        .map_err(|_| could_not_parse_event_error())?;
+    let mut split = s.split(';');
+
+    let cb = next_parsed::<u8>(&mut split)?
+        .checked_sub(32)
+        .ok_or_else(could_not_parse_event_error)?;
+    let (kind, modifiers) = parse_cb(cb)?;
+
+    let cx = next_parsed::<u16>(&mut split)?.saturating_sub(1);
+    let cy = next_parsed::<u16>(&mut split)?.saturating_sub(1);
+
+    Ok(Some(InternalEvent::Event(Event::Mouse(MouseEvent {
+        kind,
+        column: cx,
+        row: cy,
+        modifiers,
+        pixel: None,
+    }))))
+}
+
+pub(crate) fn parse_csi_normal_mouse(buffer: &[u8]) -> io::Result<Option<InternalEvent>> {
+    // Normal mouse encoding: ESC [ M CB Cx Cy (6 characters only).
+
+    assert!(buffer.starts_with(b"\x1B[M")); // ESC [ M
+
+    if buffer.len() < 6 {
+        return Ok(None);
+    }
+
+    let cb = buffer[3]
+        .checked_sub(32)
+        .ok_or_else(could_not_parse_event_error)?;
+    let (kind, modifiers) = parse_cb(cb)?;
+
+    // See http://www.xfree86.org/current/ctlseqs.html#Mouse%20Tracking
+    // The upper left character position on the terminal is denoted as 1,1.
+    // Subtract 1 to keep it synced with cursor. Folded with the +32 protocol
+    // offset into a single saturating op so coord byte 32 (1-indexed origin)
+    // clamps to 0 rather than underflowing.
+    let cx = u16::from(buffer[4].saturating_sub(33));
+    let cy = u16::from(buffer[5].saturating_sub(33));
+
+    Ok(Some(InternalEvent::Event(Event::Mouse(MouseEvent {
+        kind,
+        column: cx,
+        row: cy,
+        modifiers,
+        pixel: None,
+    }))))
+}
+
+pub(crate) fn parse_csi_sgr_mouse(
+    buffer: &[u8],
+    options: &ParseOptions,
+) -> io::Result<Option<InternalEvent>> {
+    // ESC [ < Cb ; Cx ; Cy (;) (M or m)
+
+    assert!(buffer.starts_with(b"\x1B[<")); // ESC [ <
+
+    if !buffer.ends_with(b"m") && !buffer.ends_with(b"M") {
+        return Ok(None);
+    }
+
+    let s = std::str::from_utf8(&buffer[3..buffer.len() - 1])
+        .map_err(|_| could_not_parse_event_error())?;
+    let mut split = s.split(';');
+
+    let cb = next_parsed::<u8>(&mut split)?;
+    let (kind, modifiers) = parse_cb(cb)?;
+
+    // See http://www.xfree86.org/current/ctlseqs.html#Mouse%20Tracking
+    // The upper left character position on the terminal is denoted as 1,1.
+    // Subtract 1 to keep it synced with cursor
+    let cx = next_parsed::<u16>(&mut split)?.saturating_sub(1);
+    let cy = next_parsed::<u16>(&mut split)?.saturating_sub(1);
+
+    // In SGR-Pixels mode the positions are pixels, the cells are derived from the cell size.
+    let (column, row, pixel) = match options.mouse_cell_size {
+        Some((cell_width, cell_height)) => (
+            cx.checked_div(cell_width).unwrap_or(0),
+            cy.checked_div(cell_height).unwrap_or(0),
+            Some(MousePixelPosition {
+                x: cx,
+                y: cy,
+                cell_width,
+                cell_height,
+            }),
+        ),
+        None => (cx, cy, None),
+    };
+
+    // When button 3 in Cb is used to represent mouse release, you can't tell which button was
+    // released. SGR mode solves this by having the sequence end with a lowercase m if it's a
+    // button release and an uppercase M if it's a button press.
+    //
+    // We've already checked that the last character is a lowercase or uppercase M at the start of
+    // this function, so we just need one if.
+    let kind = if buffer.last() == Some(&b'm') {
+        match kind {
+            MouseEventKind::Down(button) => MouseEventKind::Up(button),
+            other => other,
+        }
+    } else {
+        kind
+    };
+
+    Ok(Some(InternalEvent::Event(Event::Mouse(MouseEvent {
+        kind,
+        column,
+        row,
+        modifiers,
+        pixel,
+    }))))
+}
+
+/// Cb is the byte of a mouse input that contains the button being used, the key modifiers being
+/// held and whether the mouse is dragging or not.
+///
+/// Bit layout of cb, from low to high:
+///
+/// - button number
+/// - button number
+/// - shift
+/// - meta (alt)
+/// - control
+/// - mouse is dragging
+/// - button number
+/// - button number
+fn parse_cb(cb: u8) -> io::Result<(MouseEventKind, KeyModifiers)> {
+    let button_number = (cb & 0b0000_0011) | ((cb & 0b1100_0000) >> 4);
+    let dragging = cb & 0b0010_0000 == 0b0010_0000;
+
+    let kind = match (button_number, dragging) {
+        (0, false) => MouseEventKind::Down(MouseButton::Left),
+        (1, false) => MouseEventKind::Down(MouseButton::Middle),
+        (2, false) => MouseEventKind::Down(MouseButton::Right),
+        (0, true) => MouseEventKind::Drag(MouseButton::Left),
+        (1, true) => MouseEventKind::Drag(MouseButton::Middle),
+        (2, true) => MouseEventKind::Drag(MouseButton::Right),
+        (3, false) => MouseEventKind::Up(MouseButton::Left),
+        (3, true) | (4, true) | (5, true) => MouseEventKind::Moved,
+        (4, false) => MouseEventKind::ScrollUp,
+        (5, false) => MouseEventKind::ScrollDown,
+        (6, false) => MouseEventKind::ScrollLeft,
+        (7, false) => MouseEventKind::ScrollRight,
+        // Buttons 8 to 11, e.g. back and forward.
+        (8..=11, dragging) => {
+            let button = match button_number {
+                8 => MouseButton::Back,
+                9 => MouseButton::Forward,
+                other => MouseButton::Other(other),
+            };
+            if dragging {
+                MouseEventKind::Drag(button)
+            } else {
+                MouseEventKind::Down(button)
+            }
+        }
+        // We do not support other buttons.
+        _ => return Err(could_not_parse_event_error()),
+    };
+
+    let mut modifiers = KeyModifiers::empty();
+
+    if cb & 0b0000_0100 == 0b0000_0100 {
+        modifiers |= KeyModifiers::SHIFT;
+    }
+    if cb & 0b0000_1000 == 0b0000_1000 {
+        modifiers |= KeyModifiers::ALT;
+    }
+    if cb & 0b0001_0000 == 0b0001_0000 {
+        modifiers |= KeyModifiers::CONTROL;
+    }
+
+    Ok((kind, modifiers))
+}
+
+#[cfg(feature = "bracketed-paste")]
+pub(crate) fn parse_csi_bracketed_paste(buffer: &[u8]) -> io::Result<Option<InternalEvent>> {
+    // ESC [ 2 0 0 ~ pasted text ESC 2 0 1 ~
+    assert!(buffer.starts_with(b"\x1B[200~"));
+
+    if !buffer.ends_with(b"\x1b[201~") {
+        Ok(None)
+    } else {
+        let paste = String::from_utf8_lossy(&buffer[6..buffer.len() - 6]).to_string();
+        Ok(Some(InternalEvent::Event(Event::Paste(paste))))
+    }
+}
+
+/// Returns the length of the incomplete UTF-8 character at the end of the bytes.
+#[cfg(feature = "bracketed-paste")]
+fn incomplete_utf8_len(bytes: &[u8]) -> usize {
+    for len in 1..=bytes.len().min(3) {
+        let required_bytes = match bytes[bytes.len() - len] {
+            // A continuation byte, 10xxxxxx.
+            0x80..=0xBF => continue,
+            0xC0..=0xDF => 2,
+            0xE0..=0xEF => 3,
+            0xF0..=0xF7 => 4,
+            _ => 1,
+        };
+        return if required_bytes > len { len } else { 0 };
+    }
+    0
+}
+
+pub(crate) fn parse_utf8_char(buffer: &[u8]) -> io::Result<Option<char>> {
+    match std::str::from_utf8(buffer) {
+        Ok(s) => {
+            let ch = s.chars().next().ok_or_else(could_not_parse_event_error)?;
+
+            Ok(Some(ch))
+        }
+        Err(_) => {
+            // from_utf8 failed, but we have to check if we need more bytes for code point
+            // and if all the bytes we have no are valid
+
+            let required_bytes = match buffer[0] {
+                // https://en.wikipedia.org/wiki/UTF-8#Description
+                (0x00..=0x7F) => 1, // 0xxxxxxx
+                (0xC0..=0xDF) => 2, // 110xxxxx 10xxxxxx
+                (0xE0..=0xEF) => 3, // 1110xxxx 10xxxxxx 10xxxxxx
+                (0xF0..=0xF7) => 4, // 11110xxx 10xxxxxx 10xxxxxx 10xxxxxx
+                (0x80..=0xBF) | (0xF8..=0xFF) => return Err(could_not_parse_event_error()),
+            };
+
+            // More than 1 byte, check them for 10xxxxxx pattern
+            if required_bytes > 1 && buffer.len() > 1 {
+                for byte in &buffer[1..] {
+                    if byte & !0b0011_1111 != 0b1000_0000 {
+                        return Err(could_not_parse_event_error());
+                    }
+                }
+            }
+
+            if buffer.len() < required_bytes {
+                // All bytes looks good so far, but we need more of them
+                Ok(None)
+            } else {
+                Err(could_not_parse_event_error())
+            }
+        }
+    }
+}
+
+//
+// Following `Parser` structure exists for two reasons:
+//
+//  * mimic anes Parser interface
+//  * move the advancing, parsing, ... stuff out of the `try_read` method
+//
+// It's shared by all the UNIX event sources, the `FakeTerminal` and the `InputParser`.
+//
+#[derive(Debug)]
+pub(crate) struct Parser {
+    buffer: Vec<u8>,
+    internal_events: VecDeque<InternalEvent>,
+    // `None` decodes `\n` as Enter only if the raw mode is disabled, like the terminal does.
+    newline_is_enter: Option<bool>,
+    // `None` decodes mouse positions as pixels if `event::set_mouse_pixel_reporting` enabled it,
+    // with the cell size of the terminal.
+    mouse_cell_size: Option<Option<(u16, u16)>>,
+    // `None` reports unrecognized input if `event::set_report_unknown` enabled it.
+    report_unknown: Option<bool>,
+    // `None` reports pastes as set by `event::set_paste_mode`.
+    #[cfg(feature = "bracketed-paste")]
+    paste_mode: Option<PasteMode>,
+    // `None` decodes control strings only while a terminal query waits for its response.
+    awaiting_response: Option<bool>,
+    // The paste being reported in chunks, see `PasteMode::Chunked`.
+    #[cfg(feature = "bracketed-paste")]
+    paste: Option<ChunkedPaste>,
+}
+
+/// The state of a paste reported in chunks.
+#[cfg(feature = "bracketed-paste")]
+#[derive(Debug, Default)]
+struct ChunkedPaste {
+    /// The number of bytes reported.
+    size: usize,
+    truncated: bool,
+}
+
+impl Default for Parser {
+    fn default() -> Self {
+        Parser {
+            // This buffer is used for -> 1 <- ANSI escape sequence. Are we
+            // aware of any ANSI escape sequence that is bigger? Can we make
+            // it smaller?
+            //
+            // Probably not worth spending more time on this as "there's a plan"
+            // to use the anes crate parser.
+            buffer: Vec::with_capacity(256),
+            // The event sources read at most 1_024 bytes at once. How many ANSI escape
+            // sequences can fit? What is an average sequence length? Let's guess here
+            // and say that the average ANSI escape sequence length is 8 bytes. Thus
+            // the buffer size should be 1024/8=128 to avoid additional allocations
+            // when processing large amounts of data.
+            //
+            // There's no need to make it bigger, because when you look at the `try_read`
+            // method implementation, all events are consumed before the next TTY_BUFFER
+            // is processed -> events pushed.
+            internal_events: VecDeque::with_capacity(128),
+            newline_is_enter: None,
+            mouse_cell_size: None,
+            report_unknown: None,
+            #[cfg(feature = "bracketed-paste")]
+            paste_mode: None,
+            #[cfg(feature = "bracketed-paste")]
+            paste: None,
+            awaiting_response: None,
+        }
+    }
+}
+
+impl Parser {
+    pub(crate) fn advance(&mut self, buffer: &[u8], more: bool) {
+        let options = self.options();
+        self.advance_with(buffer, more, &options);
+    }
+
+    fn advance_with(&mut self, buffer: &[u8], more: bool, options: &ParseOptions) {
+        let mut buffer = buffer;
+
+        while let Some((byte, rest)) = buffer.split_first() {
+            #[cfg(feature = "bracketed-paste")]
+            if self.paste.is_some() {
+                buffer = self.advance_paste(buffer, options);
+                continue;
+            }
+
+            // The content of a paste or control string can't complete it, append it at once
+            // instead of parsing the growing sequence after every byte.
+            let content_len = self.string_content_len(buffer);
+            if content_len > 0 {
+                self.buffer.extend_from_slice(&buffer[..content_len]);
+                buffer = &buffer[content_len..];
+                continue;
+            }
+
+            self.buffer.push(*byte);
+            self.parse_buffer(!rest.is_empty() || more, options);
+            buffer = rest;
+
+            #[cfg(feature = "bracketed-paste")]
+            if matches!(options.paste_mode, PasteMode::Chunked { .. })
+                && self.buffer == b"\x1B[200~"
+            {
+                self.buffer.clear();
+                self.paste = Some(ChunkedPaste::default());
+                self.internal_events
+                    .push_back(InternalEvent::Event(Event::PasteStart));
+            }
+        }
+    }
+
+    /// Reports the pasted text of the input up to the end of the paste, and returns the input
+    /// following it.
+    ///
+    /// The buffer only keeps the bytes which could be the start of the end of the paste or of an
+    /// incomplete UTF-8 character.
+    #[cfg(feature = "bracketed-paste")]
+    fn advance_paste<'a>(&mut self, input: &'a [u8], options: &ParseOptions) -> &'a [u8] {
+        // ESC [ 2 0 1 ~
+        const END: &[u8] = b"\x1B[201~";
+
+        let kept = self.buffer.len();
+        self.buffer.extend_from_slice(input);
+        let end = self.buffer.windows(END.len()).position(|end| end == END);
+        let (data_len, rest) = match end {
+            Some(end) => (end, &input[end + END.len() - kept..]),
+            None => {
+                let end_len = (1..END.len())
+                    .rev()
+                    .find(|len| self.buffer.ends_with(&END[..*len]))
+                    .unwrap_or(0);
+                let data_len = self.buffer.len() - end_len;
+                let data_len = data_len - incomplete_utf8_len(&self.buffer[..data_len]);
+                (data_len, &[][..])
+            }
+        };
+
+        let paste = self.paste.as_mut().expect("no chunked paste");
+        if !paste.truncated && data_len > 0 {
+            let mut data = String::from_utf8_lossy(&self.buffer[..data_len]).into_owned();
+            if let PasteMode::Chunked {
+                max_size: Some(max_size),
+            } = options.paste_mode
+            {
+                let mut len = max_size.saturating_sub(paste.size);
+                if data.len() > len {
+                    while !data.is_char_boundary(len) {
+                        len -= 1;
+                    }
+                    data.truncate(len);
+                    paste.truncated = true;
+                }
+            }
+            paste.size += data.len();
+            if !data.is_empty() {
+                self.internal_events
+                    .push_back(InternalEvent::Event(Event::PasteData(data)));
+            }
+        }
+        self.buffer.drain(..data_len);
+
+        if end.is_some() {
+            let truncated = paste.truncated;
+            self.paste = None;
+            self.buffer.clear();
+            self.internal_events
+                .push_back(InternalEvent::Event(Event::PasteEnd { truncated }));
+        }
+        rest
+    }
+
+    /// Returns how many bytes of the input continue the bracketed paste or control string in the
+    /// buffer without terminating it.
+    fn string_content_len(&self, input: &[u8]) -> usize {
+        let terminator = |is_terminator: fn(&u8) -> bool| {
+            input.iter().position(is_terminator).unwrap_or(input.len())
+        };
+
+        #[cfg(feature = "bracketed-paste")]
+        if let Some(content) = self.buffer.strip_prefix(b"\x1B[200~") {
+            // The paste ends with `ESC [ 2 0 1 ~`, unless the buffer ends with the start of it.
+            const END: &[u8] = b"\x1B[201~";
+            if (1..END.len()).any(|len| content.ends_with(&END[..len])) {
+                return 0;
+            }
+            return terminator(|byte| *byte == b'\x1B');
+        }
+        // `ESC ]` or `ESC P` has been decoded as a key press already unless a query waits for a
+        // response, so the buffer holds a control string. It ends with the first control character,
+        // unless the buffer ends with it already, which is the `ESC` of `ESC \`. Past the maximum
+        // length, the bytes are parsed one by one to give up on the control string.
+        if (self.buffer.starts_with(b"\x1B]") || self.buffer.starts_with(b"\x1BP"))
+            && self.buffer.last().is_some_and(|byte| *byte >= 0x20)
+            && self.buffer.len() <= MAX_CONTROL_STRING_LEN
+        {
+            return terminator(|byte| *byte < 0x20);
+        }
+        0
+    }
+
+    /// Decodes the input with the state of a terminal other than the one of the process, see
+    /// `Tty`.
+    #[cfg(all(unix, feature = "events"))]
+    pub(crate) fn set_terminal_state(
+        &mut self,
+        newline_is_enter: bool,
+        mouse_cell_size: Option<(u16, u16)>,
+    ) {
+        self.newline_is_enter = Some(newline_is_enter);
+        self.mouse_cell_size = Some(mouse_cell_size);
+    }
+
+    /// Parses the buffered bytes as if there's no more input available.
+    pub(crate) fn flush(&mut self) {
+        #[cfg(feature = "bracketed-paste")]
+        if self.paste.is_some() {
+            // The kept bytes are pasted text.
+            return;
+        }
+
+        if !self.buffer.is_empty() {
+            let options = self.options();
+            self.parse_buffer(false, &options);
+            // No terminator arrived in time, so it's Alt+] or Alt+Shift+P followed by other input.
+            while self.buffer.starts_with(b"\x1B]") || self.buffer.starts_with(b"\x1BP") {
+                self.parse_control_string_as_keys(false, &options);
+            }
+        }
+    }
+
+    /// Decodes the `ESC ]` or `ESC P` starting the buffer as a key press and parses the rest
+    /// again, for input which isn't a control string.
+    fn parse_control_string_as_keys(&mut self, more: bool, options: &ParseOptions) {
+        let rest = self.buffer.split_off(2);
+        if let Ok(Some(key)) = parse_event(&self.buffer, false, options) {
+            self.internal_events.push_back(key);
+        }
+        self.buffer.clear();
+        self.advance_with(&rest, more, options);
+    }
+
+    /// Returns whether bytes are kept because they could start an escape sequence.
+    #[cfg(all(unix, feature = "events"))]
+    pub(crate) fn has_pending_input(&self) -> bool {
+        !self.buffer.is_empty()
+    }
+
+    fn options(&self) -> ParseOptions {
+        ParseOptions {
+            newline_is_enter: self
+                .newline_is_enter
+                .unwrap_or_else(|| !crate::terminal::is_raw_mode_enabled().unwrap_or(false)),
+            mouse_cell_size: self.mouse_cell_size.unwrap_or_else(|| {
+                MOUSE_PIXEL_REPORTING
+                    .load(Ordering::Relaxed)
+                    .then(terminal_cell_size)
+            }),
+            report_unknown: self.report_unknown.unwrap_or_else(report_unknown),
+            #[cfg(feature = "bracketed-paste")]
+            paste_mode: self.paste_mode.unwrap_or_else(|| *PASTE_MODE.lock()),
+            awaiting_response: self.awaiting_response.unwrap_or_else(awaiting_response),
+        }
+    }
+
+    fn parse_buffer(&mut self, more: bool, options: &ParseOptions) {
+        match parse_event(&self.buffer, more, options) {
+            Ok(Some(ie)) => {
+                // Responses no query waits for would never be read, unless they are reported as
+                // unknown input.
+                if !matches!(ie, InternalEvent::Response(_))
+                    || options.awaiting_response
+                    || options.report_unknown
+                {
+                    self.internal_events.push_back(ie);
+                }
+                self.buffer.clear();
+            }
+            Ok(None) => {
+                // Event can't be parsed, because we don't have enough bytes for
+                // the current sequence. Keep the buffer and process next bytes.
+            }
+            Err(_) if self.buffer.starts_with(b"\x1B]") || self.buffer.starts_with(b"\x1BP") => {
+                // Not a control string, but Alt+] or Alt+Shift+P followed by other input, which
+                // contains no control string to fall back again, see `string_content_len`.
+                self.parse_control_string_as_keys(more, options);
+            }
+            Err(_) => {
+                // Event can't be parsed (not enough parameters, parameter is not a number, ...).
+                // Clear the buffer and continue with another sequence.
+                if options.report_unknown {
+                    let bytes = std::mem::take(&mut self.buffer);
+                    self.internal_events
+                        .push_back(InternalEvent::Event(Event::Unknown(bytes)));
+                } else {
+                    self.buffer.clear();
+                }
+            }
+        }
+    }
+}
+
+impl Iterator for Parser {
+    type Item = InternalEvent;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.internal_events.pop_front()
+    }
+}
+
+/// A streaming decoder of terminal input.
+///
+/// `InputParser` turns the bytes sent by a terminal into [`Event`]s, exactly like the event
+/// reader does on UNIX, but without reading from the terminal itself. Feed it the input received
+/// from elsewhere, e.g. an SSH channel or a WebSocket, and iterate over the decoded events. It's
+/// available on all platforms and doesn't require the `events` feature.
+///
+/// Escape sequences can be split across chunks. A lone `ESC` byte is either the Esc key or the
+/// start of an escape sequence, so [`advance`](InputParser::advance) has to be told whether more
+/// input is already pending. If it is, the `ESC` is kept until the next chunk arrives, or until
+/// [`flush`](InputParser::flush) is called because no input arrived in time.
+///
+/// Responses to terminal queries (e.g. the cursor position) don't produce events. `ESC ]` and
+/// `ESC P` followed by input which doesn't form an operating system command or device control
+/// string are decoded as Alt+] and Alt+Shift+P.
+///
+/// # Examples
+///
+/// ```
+/// use crossterm::event::{Event, InputParser, KeyCode, KeyEvent, KeyModifiers};
+///
+/// let mut parser = InputParser::new();
+///
+/// // The escape sequence is completed by the second chunk.
+/// parser.advance(b"a\x1B[1;5", false);
+/// parser.advance(b"A", false);
+/// assert_eq!(parser.next(), Some(Event::Key(KeyCode::Char('a').into())));
+/// assert_eq!(
+///     parser.next(),
+///     Some(Event::Key(KeyEvent::new(KeyCode::Up, KeyModifiers::CONTROL)))
+/// );
+///
+/// // More input is pending, the `ESC` could start an escape sequence.
+/// parser.advance(b"\x1B", true);
+/// assert_eq!(parser.next(), None);
+///
+/// // No input arrived in time, it's the Esc key.
+/// parser.flush();
+/// assert_eq!(parser.next(), Some(Event::Key(KeyCode::Esc.into())));
+/// ```
+#[derive(Debug)]
+pub struct InputParser {
+    parser: Parser,
+}
+
+/// Returns the cell size of the terminal in pixels, or `(0, 0)` if it's unknown.
+fn terminal_cell_size() -> (u16, u16) {
+    crate::terminal::window_size()
+        .map(|size| size.cell_size())
+        .unwrap_or((0, 0))
+}
+
+impl Default for InputParser {
+    fn default() -> Self {
+        InputParser {
+            parser: Parser {
+                newline_is_enter: Some(false),
+                mouse_cell_size: Some(None),
+                report_unknown: Some(false),
+                #[cfg(feature = "bracketed-paste")]
+                paste_mode: Some(PasteMode::Whole),
+                awaiting_response: Some(true),
+                ..Parser::default()
+            },
+        }
+    }
+}
+
+impl InputParser {
+    /// Creates a new `InputParser` with no buffered input.
+    pub fn new() -> InputParser {
+        InputParser::default()
+    }
+
+    /// Decodes the given chunk of input.
+    ///
+    /// `more` tells whether more input is already pending. It only matters when the chunk ends
+    /// with a lone `ESC`, which is reported as the Esc key if `more` is `false`. Incomplete escape
+    /// sequences are kept until the next chunk completes them.
+    pub fn advance(&mut self, bytes: &[u8], more: bool) {
+        self.parser.advance(bytes, more);
+    }
+
+    /// Decodes the buffered input as if no more input is pending.
+    ///
+    /// Call it when no input arrived within the escape timeout after [`advance`](Self::advance)
+    /// was called with `more` set to `true`, so that a lone `ESC` is reported as the Esc key. Call
+    /// it as well when input stalls after `ESC ]` or `ESC P`, which are then decoded as Alt+] and
+    /// Alt+Shift+P instead of the start of an unterminated control string.
+    pub fn flush(&mut self) {
+        self.parser.flush();
+    }
+
+    /// Sets whether a bare `\n` is decoded as [`KeyCode::Enter`] instead of Ctrl+J.
+    ///
+    /// A terminal in raw mode sends `\r` for the Enter key, so `\n` is Ctrl+J by default. Enable
+    /// it if the input comes from a terminal that is not in raw mode.
+    pub fn set_newline_is_enter(&mut self, enabled: bool) {
+        self.parser.newline_is_enter = Some(enabled);
+    }
+
+    /// Sets whether mouse positions are decoded as pixels, because the terminal was switched to
+    /// pixel reporting with [`EnableMousePixelReporting`](crate::event::EnableMousePixelReporting).
+    ///
+    /// `Some` holds the cell size `(width, height)` in pixels, which is used to derive the
+    /// `column` and `row` of the [`MouseEvent`]s. Pass `(0, 0)` if it's unknown. `None` decodes
+    /// the positions as cells, which is the default.
+    pub fn set_mouse_cell_size(&mut self, cell_size: Option<(u16, u16)>) {
+        self.parser.mouse_cell_size = Some(cell_size);
+    }
+
+    /// Sets whether input which can't be decoded is reported as [`Event::Unknown`] instead of
+    /// being dropped, which is the default.
+    ///
+    /// This includes malformed or unsupported escape sequences, invalid UTF-8 and control
+    /// sequences which are neither a known event nor a response crossterm decodes.
+    pub fn set_report_unknown(&mut self, enabled: bool) {
+        self.parser.report_unknown = Some(enabled);
+    }
+
+    /// Sets how bracketed pastes are reported, as a whole by default, see [`PasteMode`].
+    #[cfg(feature = "bracketed-paste")]
+    pub fn set_paste_mode(&mut self, mode: PasteMode) {
+        self.parser.paste_mode = Some(mode);
+    }
+}
+
+impl Iterator for InputParser {
+    type Item = Event;
+
+    fn next(&mut self) -> Option<Event> {
+        let report_unknown = self.parser.report_unknown == Some(true);
+        self.parser.find_map(|event| match event {
+            InternalEvent::Event(event) => Some(event),
+            InternalEvent::Response(_) if report_unknown => event.into_event(),
+            _ => None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::event::{KeyEventState, KeyModifiers, MouseButton, MouseEvent};
+
+    use super::*;
+
+    // The parse functions with the options used by the event sources when the raw mode is
+    // disabled and a query waits for its response.
+    const OPTIONS: ParseOptions = ParseOptions {
+        newline_is_enter: true,
+        mouse_cell_size: None,
+        report_unknown: false,
+        #[cfg(feature = "bracketed-paste")]
+        paste_mode: PasteMode::Whole,
+        awaiting_response: true,
+    };
+
+    fn parse_event(buffer: &[u8], input_available: bool) -> io::Result<Option<InternalEvent>> {
+        super::parse_event(buffer, input_available, &OPTIONS)
+    }
+
+    fn parse_csi(buffer: &[u8]) -> io::Result<Option<InternalEvent>> {
+        super::parse_csi(buffer, &OPTIONS)
+    }
+
+    fn parse_csi_u_encoded_key_code(buffer: &[u8]) -> io::Result<Option<InternalEvent>> {
+        super::parse_csi_u_encoded_key_code(buffer, &OPTIONS)
+    }
+
+    fn parse_csi_special_key_code(buffer: &[u8]) -> io::Result<Option<InternalEvent>> {
+        super::parse_csi_special_key_code(buffer, &OPTIONS)
+    }
+
+    fn parse_csi_sgr_mouse(buffer: &[u8]) -> io::Result<Option<InternalEvent>> {
+        super::parse_csi_sgr_mouse(buffer, &OPTIONS)
+    }
+
+    #[test]
+    fn test_esc_key() {
+        assert_eq!(
+            parse_event(b"\x1B", false).unwrap(),
+            Some(InternalEvent::Event(Event::Key(KeyCode::Esc.into()))),
+        );
+    }
+
+    #[test]
+    fn test_possible_esc_sequence() {
+        assert_eq!(parse_event(b"\x1B", true).unwrap(), None,);
+    }
+
+    #[test]
+    fn test_alt_key() {
+        assert_eq!(
+            parse_event(b"\x1Bc", false).unwrap(),
+            Some(InternalEvent::Event(Event::Key(KeyEvent::new(
+                KeyCode::Char('c'),
+                KeyModifiers::ALT
+            )))),
+        );
+    }
+
+    #[test]
+    fn test_alt_shift() {
+        assert_eq!(
+            parse_event(b"\x1BH", false).unwrap(),
+            Some(InternalEvent::Event(Event::Key(KeyEvent::new(
+                KeyCode::Char('H'),
+                KeyModifiers::ALT | KeyModifiers::SHIFT
+            )))),
+        );
+    }
+
+    #[test]
+    fn test_alt_ctrl() {
+        assert_eq!(
+            parse_event(b"\x1B\x14", false).unwrap(),
+            Some(InternalEvent::Event(Event::Key(KeyEvent::new(
+                KeyCode::Char('t'),
+                KeyModifiers::ALT | KeyModifiers::CONTROL
+            )))),
+        );
+    }
+
+    #[test]
+    fn test_parse_event_subsequent_calls() {
+        // The main purpose of this test is to check if we're passing
+        // correct slice to other parse_ functions.
+
+        // parse_csi_cursor_position
+        assert_eq!(
+            parse_event(b"\x1B[20;10R", false).unwrap(),
+            Some(InternalEvent::CursorPosition(9, 19))
+        );
+
+        // parse_csi
+        assert_eq!(
+            parse_event(b"\x1B[D", false).unwrap(),
+            Some(InternalEvent::Event(Event::Key(KeyCode::Left.into()))),
+        );
+
+        // parse_csi_modifier_key_code
+        assert_eq!(
+            parse_event(b"\x1B[2D", false).unwrap(),
+            Some(InternalEvent::Event(Event::Key(KeyEvent::new(
+                KeyCode::Left,
+                KeyModifiers::SHIFT
+            ))))
+        );
+
+        // parse_csi_special_key_code
+        assert_eq!(
+            parse_event(b"\x1B[3~", false).unwrap(),
+            Some(InternalEvent::Event(Event::Key(KeyCode::Delete.into()))),
+        );
+
+        // parse_csi_bracketed_paste
+        #[cfg(feature = "bracketed-paste")]
+        assert_eq!(
+            parse_event(b"\x1B[200~on and on and on\x1B[201~", false).unwrap(),
+            Some(InternalEvent::Event(Event::Paste(
+                "on and on and on".to_string()
+            ))),
+        );
+
+        // parse_csi_rxvt_mouse
+        assert_eq!(
+            parse_event(b"\x1B[32;30;40;M", false).unwrap(),
+            Some(InternalEvent::Event(Event::Mouse(MouseEvent {
+                kind: MouseEventKind::Down(MouseButton::Left),
+                column: 29,
+                row: 39,
+                modifiers: KeyModifiers::empty(),
+                pixel: None,
+            })))
+        );
+
+        // parse_csi_normal_mouse
+        assert_eq!(
+            parse_event(b"\x1B[M0\x60\x70", false).unwrap(),
+            Some(InternalEvent::Event(Event::Mouse(MouseEvent {
+                kind: MouseEventKind::Down(MouseButton::Left),
+                column: 63,
+                row: 79,
+                modifiers: KeyModifiers::CONTROL,
+                pixel: None,
+            })))
+        );
+
+        // parse_csi_sgr_mouse
+        assert_eq!(
+            parse_event(b"\x1B[<0;20;10;M", false).unwrap(),
+            Some(InternalEvent::Event(Event::Mouse(MouseEvent {
+                kind: MouseEventKind::Down(MouseButton::Left),
+                column: 19,
+                row: 9,
+                modifiers: KeyModifiers::empty(),
+                pixel: None,
+            })))
+        );
+
+        // parse_utf8_char
+        assert_eq!(
+            parse_event("Ž".as_bytes(), false).unwrap(),
+            Some(InternalEvent::Event(Event::Key(KeyEvent::new(
+                KeyCode::Char('Ž'),
+                KeyModifiers::SHIFT
+            )))),
+        );
+    }
+
+    #[test]
+    fn test_parse_event() {
+        assert_eq!(
+            parse_event(b"\t", false).unwrap(),
+            Some(InternalEvent::Event(Event::Key(KeyCode::Tab.into()))),
+        );
+    }
+
+    #[test]
+    fn test_parse_csi_cursor_position() {
+        assert_eq!(
+            parse_csi_cursor_position(b"\x1B[20;10R").unwrap(),
+            Some(InternalEvent::CursorPosition(9, 19))
+        );
+    }
+
+    #[test]
+    fn test_parse_csi() {
+        assert_eq!(
+            parse_csi(b"\x1B[D").unwrap(),
+            Some(InternalEvent::Event(Event::Key(KeyCode::Left.into()))),
+        );
+    }
+
+    #[test]
+    fn test_parse_csi_modifier_key_code() {
+        assert_eq!(
+            parse_csi_modifier_key_code(b"\x1B[2D").unwrap(),
+            Some(InternalEvent::Event(Event::Key(KeyEvent::new(
+                KeyCode::Left,
+                KeyModifiers::SHIFT
+            )))),
+        );
+    }
+
+    #[test]
+    fn test_parse_csi_special_key_code() {
+        assert_eq!(
+            parse_csi_special_key_code(b"\x1B[3~").unwrap(),
+            Some(InternalEvent::Event(Event::Key(KeyCode::Delete.into()))),
+        );
+    }
+
+    #[test]
+    fn test_parse_csi_modify_other_keys() {
+        assert_eq!(
+            parse_csi_special_key_code(b"\x1B[27;5;13~").unwrap(),
+            Some(InternalEvent::Event(Event::Key(KeyEvent::new(
+                KeyCode::Enter,
+                KeyModifiers::CONTROL
+            )))),
+        );
+        assert_eq!(
+            parse_csi_special_key_code(b"\x1B[27;5;49~").unwrap(),
+            Some(InternalEvent::Event(Event::Key(KeyEvent::new(
+                KeyCode::Char('1'),
+                KeyModifiers::CONTROL
+            )))),
+        );
+        assert_eq!(
+            parse_csi_special_key_code(b"\x1B[27;6;9~").unwrap(),
+            Some(InternalEvent::Event(Event::Key(KeyEvent::new(
+                KeyCode::BackTab,
+                KeyModifiers::CONTROL | KeyModifiers::SHIFT
+            )))),
+        );
+        assert_eq!(
+            parse_csi_special_key_code(b"\x1B[27;6;65~").unwrap(),
+            Some(InternalEvent::Event(Event::Key(KeyEvent::new(
+                KeyCode::Char('A'),
+                KeyModifiers::CONTROL | KeyModifiers::SHIFT
+            )))),
+        );
+        // xterm sends `CSI codepoint ; modifiers u` instead when formatOtherKeys is set.
+        assert_eq!(
+            parse_csi_u_encoded_key_code(b"\x1B[13;5u").unwrap(),
+            Some(InternalEvent::Event(Event::Key(KeyEvent::new(
+                KeyCode::Enter,
+                KeyModifiers::CONTROL
+            )))),
+        );
+        assert!(parse_csi_special_key_code(b"\x1B[27;5~").is_err());
+    }
+
+    #[test]
+    fn test_parse_csi_special_key_code_multiple_values_not_supported() {
+        assert_eq!(
+            parse_csi_special_key_code(b"\x1B[3;2~").unwrap(),
+            Some(InternalEvent::Event(Event::Key(KeyEvent::new(
+                KeyCode::Delete,
+                KeyModifiers::SHIFT
+            )))),
+        );
+    }
+
+    #[cfg(feature = "bracketed-paste")]
+    #[test]
+    fn test_parse_csi_bracketed_paste() {
+        //
+        assert_eq!(
+            parse_event(b"\x1B[200~o", false).unwrap(),
+            None,
+            "A partial bracketed paste isn't parsed"
+        );
+        assert_eq!(
+            parse_event(b"\x1B[200~o\x1B[2D", false).unwrap(),
+            None,
+            "A partial bracketed paste containing another escape code isn't parsed"
+        );
+        assert_eq!(
+            parse_event(b"\x1B[200~o\x1B[2D\x1B[201~", false).unwrap(),
+            Some(InternalEvent::Event(Event::Paste("o\x1B[2D".to_string())))
+        );
+    }
+
+    #[test]
+    fn test_parse_csi_focus() {
+        assert_eq!(
+            parse_csi(b"\x1B[O").unwrap(),
+            Some(InternalEvent::Event(Event::FocusLost))
+        );
+    }
+
+    #[test]
+    fn test_parse_csi_rxvt_mouse() {
+        assert_eq!(
+            parse_csi_rxvt_mouse(b"\x1B[32;30;40;M").unwrap(),
+            Some(InternalEvent::Event(Event::Mouse(MouseEvent {
+                kind: MouseEventKind::Down(MouseButton::Left),
+                column: 29,
+                row: 39,
+                modifiers: KeyModifiers::empty(),
+                pixel: None,
+            })))
+        );
+    }
+
+    #[test]
+    fn test_parse_csi_normal_mouse() {
+        assert_eq!(
+            parse_csi_normal_mouse(b"\x1B[M0\x60\x70").unwrap(),
+            Some(InternalEvent::Event(Event::Mouse(MouseEvent {
+                kind: MouseEventKind::Down(MouseButton::Left),
+                column: 63,
+                row: 79,
+                modifiers: KeyModifiers::CONTROL,
+                pixel: None,
+            })))
+        );
+    }
+
+    // Regression: coord byte 32 (the protocol-encoded origin) used
+    // to panic in debug (wrap to 65535 in release) because the
+    // parser did `saturating_sub(32)` then `- 1`. Folded into a
+    // single `saturating_sub(33)`; see the comment on the parser.
+    #[test]
+    fn test_parse_csi_normal_mouse_zero_coords_clamp() {
+        assert_eq!(
+            parse_csi_normal_mouse(b"\x1B[M\x20\x20\x20").unwrap(),
+            Some(InternalEvent::Event(Event::Mouse(MouseEvent {
+                kind: MouseEventKind::Down(MouseButton::Left),
+                column: 0,
+                row: 0,
+                modifiers: KeyModifiers::empty(),
+                pixel: None,
+            })))
+        );
+    }
+
+    // Pins the 1-indexed → 0-indexed mapping: byte 33 = column 1
+    // on the wire → column 0 in the event; byte 34 on the row
+    // axis → row 1.
+    #[test]
+    fn test_parse_csi_normal_mouse_one_is_zero_indexed() {
+        assert_eq!(
+            parse_csi_normal_mouse(b"\x1B[M\x20\x21\x22").unwrap(),
+            Some(InternalEvent::Event(Event::Mouse(MouseEvent {
+                kind: MouseEventKind::Down(MouseButton::Left),
+                column: 0,
+                row: 1,
+                modifiers: KeyModifiers::empty(),
+                pixel: None,
+            })))
+        );
+    }
+
+    // ESC [ M reaches this parser purely by byte pattern, so coord
+    // bytes below the protocol floor (< 32) can arrive from pastes
+    // containing terminal escape data, subprocess output emitting
+    // VT100 DL (CSI M = Delete Line), or a garbled SGR-enable.
+    // Must clamp to 0 rather than wrap or panic.
+    #[test]
+    fn test_parse_csi_normal_mouse_sub_protocol_bytes_clamp() {
+        assert_eq!(
+            parse_csi_normal_mouse(b"\x1B[M\x20\x10\x05").unwrap(),
+            Some(InternalEvent::Event(Event::Mouse(MouseEvent {
+                kind: MouseEventKind::Down(MouseButton::Left),
+                column: 0,
+                row: 0,
+                modifiers: KeyModifiers::empty(),
+                pixel: None,
+            })))
+        );
+    }
+
+    #[test]
+    fn test_parse_csi_sgr_mouse() {
+        assert_eq!(
+            parse_csi_sgr_mouse(b"\x1B[<0;20;10;M").unwrap(),
+            Some(InternalEvent::Event(Event::Mouse(MouseEvent {
+                kind: MouseEventKind::Down(MouseButton::Left),
+                column: 19,
+                row: 9,
+                modifiers: KeyModifiers::empty(),
+                pixel: None,
+            })))
+        );
+        assert_eq!(
+            parse_csi_sgr_mouse(b"\x1B[<0;20;10M").unwrap(),
+            Some(InternalEvent::Event(Event::Mouse(MouseEvent {
+                kind: MouseEventKind::Down(MouseButton::Left),
+                column: 19,
+                row: 9,
+                modifiers: KeyModifiers::empty(),
+                pixel: None,
+            })))
+        );
+        assert_eq!(
+            parse_csi_sgr_mouse(b"\x1B[<0;20;10;m").unwrap(),
+            Some(InternalEvent::Event(Event::Mouse(MouseEvent {
+                kind: MouseEventKind::Up(MouseButton::Left),
+                column: 19,
+                row: 9,
+                modifiers: KeyModifiers::empty(),
+                pixel: None,
+            })))
+        );
+        assert_eq!(
+            parse_csi_sgr_mouse(b"\x1B[<0;20;10m").unwrap(),
+            Some(InternalEvent::Event(Event::Mouse(MouseEvent {
+                kind: MouseEventKind::Up(MouseButton::Left),
+                column: 19,
+                row: 9,
+                modifiers: KeyModifiers::empty(),
+                pixel: None,
+            })))
+        );
+    }
+
+    #[test]
+    fn test_parse_csi_mouse_extra_buttons() {
+        let mouse_event = |kind| {
+            Some(InternalEvent::Event(Event::Mouse(MouseEvent {
+                kind,
+                column: 19,
+                row: 9,
+                modifiers: KeyModifiers::empty(),
+                pixel: None,
+            })))
+        };
+
+        assert_eq!(
+            parse_csi_sgr_mouse(b"\x1B[<128;20;10M").unwrap(),
+            mouse_event(MouseEventKind::Down(MouseButton::Back)),
+        );
+        assert_eq!(
+            parse_csi_sgr_mouse(b"\x1B[<129;20;10m").unwrap(),
+            mouse_event(MouseEventKind::Up(MouseButton::Forward)),
+        );
+        assert_eq!(
+            parse_csi_sgr_mouse(b"\x1B[<162;20;10M").unwrap(),
+            mouse_event(MouseEventKind::Drag(MouseButton::Other(10))),
+        );
+        assert_eq!(
+            parse_csi_normal_mouse(b"\x1B[M\xA3\x34\x2A").unwrap(),
+            mouse_event(MouseEventKind::Down(MouseButton::Other(11))),
+        );
+        assert_eq!(
+            parse_csi_rxvt_mouse(b"\x1B[160;20;10M").unwrap(),
+            mouse_event(MouseEventKind::Down(MouseButton::Back)),
+        );
+        // There are no buttons above 11.
+        assert!(parse_csi_sgr_mouse(b"\x1B[<192;20;10M").is_err());
+    }
+
+    // Regression: column or row of 0 in an SGR mouse sequence used
+    // to panic in debug (wrap to 65535 in release) because the
+    // parser unconditionally subtracted 1 from the wire value.
+    #[test]
+    fn test_parse_csi_sgr_mouse_zero_coords_clamp() {
+        assert_eq!(
+            parse_csi_sgr_mouse(b"\x1B[<0;0;0M").unwrap(),
+            Some(InternalEvent::Event(Event::Mouse(MouseEvent {
+                kind: MouseEventKind::Down(MouseButton::Left),
+                column: 0,
+                row: 0,
+                modifiers: KeyModifiers::empty(),
+                pixel: None,
+            })))
+        );
+    }
+
+    // Pins the 1-indexed → 0-indexed mapping for the SGR parser:
+    // wire value 1 lands at coordinate 0.
+    #[test]
+    fn test_parse_csi_sgr_mouse_one_is_zero_indexed() {
+        assert_eq!(
+            parse_csi_sgr_mouse(b"\x1B[<0;1;2M").unwrap(),
+            Some(InternalEvent::Event(Event::Mouse(MouseEvent {
+                kind: MouseEventKind::Down(MouseButton::Left),
+                column: 0,
+                row: 1,
+                modifiers: KeyModifiers::empty(),
+                pixel: None,
+            })))
+        );
+    }
+
+    #[test]
+    fn test_utf8() {
+        // https://www.php.net/manual/en/reference.pcre.pattern.modifiers.php#54805
+
+        // 'Valid ASCII' => "a",
+        assert_eq!(parse_utf8_char(b"a").unwrap(), Some('a'),);
+
+        // 'Valid 2 Octet Sequence' => "\xc3\xb1",
+        assert_eq!(parse_utf8_char(&[0xC3, 0xB1]).unwrap(), Some('ñ'),);
+
+        // 'Invalid 2 Octet Sequence' => "\xc3\x28",
+        assert!(parse_utf8_char(&[0xC3, 0x28]).is_err());
+
+        // 'Invalid Sequence Identifier' => "\xa0\xa1",
+        assert!(parse_utf8_char(&[0xA0, 0xA1]).is_err());
+
+        // 'Valid 3 Octet Sequence' => "\xe2\x82\xa1",
+        assert_eq!(
+            parse_utf8_char(&[0xE2, 0x81, 0xA1]).unwrap(),
+            Some('\u{2061}'),
+        );
+
+        // 'Invalid 3 Octet Sequence (in 2nd Octet)' => "\xe2\x28\xa1",
+        assert!(parse_utf8_char(&[0xE2, 0x28, 0xA1]).is_err());
+
+        // 'Invalid 3 Octet Sequence (in 3rd Octet)' => "\xe2\x82\x28",
+        assert!(parse_utf8_char(&[0xE2, 0x82, 0x28]).is_err());
+
+        // 'Valid 4 Octet Sequence' => "\xf0\x90\x8c\xbc",
+        assert_eq!(
+            parse_utf8_char(&[0xF0, 0x90, 0x8C, 0xBC]).unwrap(),
+            Some('𐌼'),
+        );
+
+        // 'Invalid 4 Octet Sequence (in 2nd Octet)' => "\xf0\x28\x8c\xbc",
+        assert!(parse_utf8_char(&[0xF0, 0x28, 0x8C, 0xBC]).is_err());
+
+        // 'Invalid 4 Octet Sequence (in 3rd Octet)' => "\xf0\x90\x28\xbc",
+        assert!(parse_utf8_char(&[0xF0, 0x90, 0x28, 0xBC]).is_err());
+
+        // 'Invalid 4 Octet Sequence (in 4th Octet)' => "\xf0\x28\x8c\x28",
+        assert!(parse_utf8_char(&[0xF0, 0x28, 0x8C, 0x28]).is_err());
+    }
+
+    #[test]
+    fn test_parse_char_event_lowercase() {
+        assert_eq!(
+            parse_event(b"c", false).unwrap(),
+            Some(InternalEvent::Event(Event::Key(KeyEvent::new(
+                KeyCode::Char('c'),
+                KeyModifiers::empty()
+            )))),
+        );
+    }
+
+    #[test]
+    fn test_parse_char_event_uppercase() {
+        assert_eq!(
+            parse_event(b"C", false).unwrap(),
+            Some(InternalEvent::Event(Event::Key(KeyEvent::new(
+                KeyCode::Char('C'),
+                KeyModifiers::SHIFT
+            )))),
+        );
+    }
+
+    #[test]
+    fn test_parse_basic_csi_u_encoded_key_code() {
+        assert_eq!(
+            parse_csi_u_encoded_key_code(b"\x1B[97u").unwrap(),
+            Some(InternalEvent::Event(Event::Key(KeyEvent::new(
+                KeyCode::Char('a'),
+                KeyModifiers::empty()
+            )))),
+        );
+        assert_eq!(
+            parse_csi_u_encoded_key_code(b"\x1B[97;2u").unwrap(),
+            Some(InternalEvent::Event(Event::Key(KeyEvent::new(
+                KeyCode::Char('A'),
+                KeyModifiers::SHIFT
+            )))),
+        );
+        assert_eq!(
+            parse_csi_u_encoded_key_code(b"\x1B[97;7u").unwrap(),
+            Some(InternalEvent::Event(Event::Key(KeyEvent::new(
+                KeyCode::Char('a'),
+                KeyModifiers::ALT | KeyModifiers::CONTROL
+            )))),
+        );
+    }
+
+    #[test]
+    fn test_parse_basic_csi_u_encoded_key_code_special_keys() {
+        assert_eq!(
+            parse_csi_u_encoded_key_code(b"\x1B[13u").unwrap(),
+            Some(InternalEvent::Event(Event::Key(KeyEvent::new(
+                KeyCode::Enter,
+                KeyModifiers::empty()
+            )))),
+        );
+        assert_eq!(
+            parse_csi_u_encoded_key_code(b"\x1B[27u").unwrap(),
+            Some(InternalEvent::Event(Event::Key(KeyEvent::new(
+                KeyCode::Esc,
+                KeyModifiers::empty()
+            )))),
+        );
+        assert_eq!(
+            parse_csi_u_encoded_key_code(b"\x1B[57358u").unwrap(),
+            Some(InternalEvent::Event(Event::Key(KeyEvent::new(
+                KeyCode::CapsLock,
+                KeyModifiers::empty()
+            )))),
+        );
+        assert_eq!(
+            parse_csi_u_encoded_key_code(b"\x1B[57376u").unwrap(),
+            Some(InternalEvent::Event(Event::Key(KeyEvent::new(
+                KeyCode::F(13),
+                KeyModifiers::empty()
+            )))),
+        );
+        assert_eq!(
+            parse_csi_u_encoded_key_code(b"\x1B[57428u").unwrap(),
+            Some(InternalEvent::Event(Event::Key(KeyEvent::new(
+                KeyCode::Media(MediaKeyCode::Play),
+                KeyModifiers::empty()
+            )))),
+        );
+        assert_eq!(
+            parse_csi_u_encoded_key_code(b"\x1B[57441u").unwrap(),
+            Some(InternalEvent::Event(Event::Key(KeyEvent::new(
+                KeyCode::Modifier(ModifierKeyCode::LeftShift),
+                KeyModifiers::SHIFT,
+            )))),
+        );
+    }
+
+    #[test]
+    fn test_parse_csi_u_encoded_keypad_code() {
+        assert_eq!(
+            parse_csi_u_encoded_key_code(b"\x1B[57399u").unwrap(),
+            Some(InternalEvent::Event(Event::Key(
+                KeyEvent::new_with_kind_and_state(
+                    KeyCode::Char('0'),
+                    KeyModifiers::empty(),
+                    KeyEventKind::Press,
+                    KeyEventState::KEYPAD,
+                )
+            ))),
+        );
+        assert_eq!(
+            parse_csi_u_encoded_key_code(b"\x1B[57419u").unwrap(),
+            Some(InternalEvent::Event(Event::Key(
+                KeyEvent::new_with_kind_and_state(
+                    KeyCode::Up,
+                    KeyModifiers::empty(),
+                    KeyEventKind::Press,
+                    KeyEventState::KEYPAD,
+                )
+            ))),
+        );
+    }
+
+    #[test]
+    fn test_parse_csi_u_encoded_key_code_with_types() {
+        assert_eq!(
+            parse_csi_u_encoded_key_code(b"\x1B[97;1u").unwrap(),
+            Some(InternalEvent::Event(Event::Key(KeyEvent::new_with_kind(
+                KeyCode::Char('a'),
+                KeyModifiers::empty(),
+                KeyEventKind::Press,
+            )))),
+        );
+        assert_eq!(
+            parse_csi_u_encoded_key_code(b"\x1B[97;1:1u").unwrap(),
+            Some(InternalEvent::Event(Event::Key(KeyEvent::new_with_kind(
+                KeyCode::Char('a'),
+                KeyModifiers::empty(),
+                KeyEventKind::Press,
+            )))),
+        );
+        assert_eq!(
+            parse_csi_u_encoded_key_code(b"\x1B[97;5:1u").unwrap(),
+            Some(InternalEvent::Event(Event::Key(KeyEvent::new_with_kind(
+                KeyCode::Char('a'),
+                KeyModifiers::CONTROL,
+                KeyEventKind::Press,
+            )))),
+        );
+        assert_eq!(
+            parse_csi_u_encoded_key_code(b"\x1B[97;1:2u").unwrap(),
+            Some(InternalEvent::Event(Event::Key(KeyEvent::new_with_kind(
+                KeyCode::Char('a'),
+                KeyModifiers::empty(),
+                KeyEventKind::Repeat,
+            )))),
+        );
+        assert_eq!(
+            parse_csi_u_encoded_key_code(b"\x1B[97;1:3u").unwrap(),
+            Some(InternalEvent::Event(Event::Key(KeyEvent::new_with_kind(
+                KeyCode::Char('a'),
+                KeyModifiers::empty(),
+                KeyEventKind::Release,
+            )))),
+        );
+    }
+
+    #[test]
+    fn test_parse_csi_u_encoded_key_code_has_modifier_on_modifier_press() {
+        assert_eq!(
+            parse_csi_u_encoded_key_code(b"\x1B[57449u").unwrap(),
+            Some(InternalEvent::Event(Event::Key(KeyEvent::new_with_kind(
+                KeyCode::Modifier(ModifierKeyCode::RightAlt),
+                KeyModifiers::ALT,
+                KeyEventKind::Press,
+            )))),
+        );
+        assert_eq!(
+            parse_csi_u_encoded_key_code(b"\x1B[57449;3:3u").unwrap(),
+            Some(InternalEvent::Event(Event::Key(KeyEvent::new_with_kind(
+                KeyCode::Modifier(ModifierKeyCode::RightAlt),
+                KeyModifiers::ALT,
+                KeyEventKind::Release,
+            )))),
+        );
+        assert_eq!(
+            parse_csi_u_encoded_key_code(b"\x1B[57450u").unwrap(),
+            Some(InternalEvent::Event(Event::Key(KeyEvent::new(
+                KeyCode::Modifier(ModifierKeyCode::RightSuper),
+                KeyModifiers::SUPER,
+            )))),
+        );
+        assert_eq!(
+            parse_csi_u_encoded_key_code(b"\x1B[57451u").unwrap(),
+            Some(InternalEvent::Event(Event::Key(KeyEvent::new(
+                KeyCode::Modifier(ModifierKeyCode::RightHyper),
+                KeyModifiers::HYPER,
+            )))),
+        );
+        assert_eq!(
+            parse_csi_u_encoded_key_code(b"\x1B[57452u").unwrap(),
+            Some(InternalEvent::Event(Event::Key(KeyEvent::new(
+                KeyCode::Modifier(ModifierKeyCode::RightMeta),
+                KeyModifiers::META,
+            )))),
+        );
+    }
+
+    #[test]
+    fn test_parse_csi_u_encoded_key_code_with_extra_modifiers() {
+        assert_eq!(
+            parse_csi_u_encoded_key_code(b"\x1B[97;9u").unwrap(),
+            Some(InternalEvent::Event(Event::Key(KeyEvent::new(
+                KeyCode::Char('a'),
+                KeyModifiers::SUPER
+            )))),
+        );
+        assert_eq!(
+            parse_csi_u_encoded_key_code(b"\x1B[97;17u").unwrap(),
+            Some(InternalEvent::Event(Event::Key(KeyEvent::new(
+                KeyCode::Char('a'),
+                KeyModifiers::HYPER,
+            )))),
+        );
+        assert_eq!(
+            parse_csi_u_encoded_key_code(b"\x1B[97;33u").unwrap(),
+            Some(InternalEvent::Event(Event::Key(KeyEvent::new(
+                KeyCode::Char('a'),
+                KeyModifiers::META,
+            )))),
+        );
+    }
+
+    #[test]
+    fn test_parse_csi_u_encoded_key_code_with_extra_state() {
+        assert_eq!(
+            parse_csi_u_encoded_key_code(b"\x1B[97;65u").unwrap(),
+            Some(InternalEvent::Event(Event::Key(
+                KeyEvent::new_with_kind_and_state(
+                    KeyCode::Char('a'),
+                    KeyModifiers::empty(),
+                    KeyEventKind::Press,
+                    KeyEventState::CAPS_LOCK,
+                )
+            ))),
+        );
+        assert_eq!(
+            parse_csi_u_encoded_key_code(b"\x1B[49;129u").unwrap(),
+            Some(InternalEvent::Event(Event::Key(
+                KeyEvent::new_with_kind_and_state(
+                    KeyCode::Char('1'),
+                    KeyModifiers::empty(),
+                    KeyEventKind::Press,
+                    KeyEventState::NUM_LOCK,
+                )
+            ))),
+        );
+    }
+
+    #[test]
+    fn test_parse_csi_u_with_shifted_keycode() {
+        assert_eq!(
+            // A-S-9 is equivalent to A-(
+            parse_event(b"\x1B[57:40;4u", false).unwrap(),
+            Some(InternalEvent::Event(Event::Key(KeyEvent::new(
+                KeyCode::Char('('),
+                KeyModifiers::ALT,
+            )))),
+        );
+        assert_eq!(
+            // A-S-minus is equivalent to A-_
+            parse_event(b"\x1B[45:95;4u", false).unwrap(),
+            Some(InternalEvent::Event(Event::Key(KeyEvent::new(
+                KeyCode::Char('_'),
+                KeyModifiers::ALT,
+            )))),
+        );
+    }
+
+    #[test]
+    fn test_parse_csi_keyboard_enhancement_flags() {
+        assert_eq!(
+            parse_event(b"\x1B[?25u", false).unwrap(),
+            Some(InternalEvent::KeyboardEnhancementFlags(
+                KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES
+                    | KeyboardEnhancementFlags::REPORT_ALL_KEYS_AS_ESCAPE_CODES
+                    | KeyboardEnhancementFlags::REPORT_ASSOCIATED_TEXT
+            )),
+        );
+    }
+
+    #[test]
+    fn test_parse_csi_u_with_alternate_keys() {
+        let alternate_keys = |bytes: &[u8]| match parse_event(bytes, false).unwrap() {
+            Some(InternalEvent::Event(Event::Key(event))) => event.alternate_keys,
+            event => panic!("unexpected event {event:?}"),
+        };
+
+        assert_eq!(
+            alternate_keys(b"\x1B[57:40;4u"),
+            Some(AlternateKeys {
+                unshifted: KeyCode::Char('9'),
+                shifted: Some('('),
+                base_layout: None,
+            })
+        );
+        // Ctrl+С on a Cyrillic layout is on the position of Ctrl+C.
+        assert_eq!(
+            parse_event("\x1B[1089::99;5u".as_bytes(), false).unwrap(),
+            Some(InternalEvent::Event(Event::Key(KeyEvent::new(
+                KeyCode::Char('с'),
+                KeyModifiers::CONTROL,
+            )))),
+        );
+        assert_eq!(
+            alternate_keys(b"\x1B[1089::99;5u"),
+            Some(AlternateKeys {
+                unshifted: KeyCode::Char('с'),
+                shifted: None,
+                base_layout: Some(KeyCode::Char('c')),
+            })
+        );
+        assert_eq!(
+            alternate_keys(b"\x1B[1089:1057:99;2u"),
+            Some(AlternateKeys {
+                unshifted: KeyCode::Char('с'),
+                shifted: Some('С'),
+                base_layout: Some(KeyCode::Char('c')),
+            })
+        );
+        assert_eq!(alternate_keys(b"\x1B[97;5u"), None);
+    }
+
+    #[test]
+    fn test_parse_csi_u_with_associated_text() {
+        let text = |bytes: &[u8]| match parse_event(bytes, false).unwrap() {
+            Some(InternalEvent::Event(Event::Key(event))) => event.text,
+            event => panic!("unexpected event {event:?}"),
+        };
+
+        assert_eq!(
+            parse_event(b"\x1B[97;;97u", false).unwrap(),
+            Some(InternalEvent::Event(Event::Key(KeyEvent::new(
+                KeyCode::Char('a'),
+                KeyModifiers::empty(),
+            )))),
+        );
+        assert_eq!(text(b"\x1B[97;;97u"), KeyText::new("a"));
+        // A dead key followed by `e` produces `é`.
+        assert_eq!(text(b"\x1B[101;;233u"), KeyText::new("é"));
+        assert_eq!(text(b"\x1B[97;2;65u"), KeyText::new("A"));
+        assert_eq!(text(b"\x1B[97;1;97:98:99u"), KeyText::new("abc"));
+        assert_eq!(text(b"\x1B[97;1u"), None);
+        // Invalid or oversized text is ignored rather than failing the whole event.
+        assert_eq!(text(b"\x1B[97;1;55296u"), None);
+        assert_eq!(
+            text(format!("\x1B[97;1;{}u", ["97"; 32].join(":")).as_bytes()),
+            None
+        );
+    }
+
+    #[test]
+    fn test_parse_csi_special_key_code_with_types() {
+        assert_eq!(
+            parse_event(b"\x1B[;1:3B", false).unwrap(),
+            Some(InternalEvent::Event(Event::Key(KeyEvent::new_with_kind(
+                KeyCode::Down,
+                KeyModifiers::empty(),
+                KeyEventKind::Release,
+            )))),
+        );
+        assert_eq!(
+            parse_event(b"\x1B[1;1:3B", false).unwrap(),
+            Some(InternalEvent::Event(Event::Key(KeyEvent::new_with_kind(
+                KeyCode::Down,
+                KeyModifiers::empty(),
+                KeyEventKind::Release,
+            )))),
+        );
+    }
+
+    #[test]
+    fn test_parse_csi_numbered_escape_code_with_types() {
+        assert_eq!(
+            parse_event(b"\x1B[5;1:3~", false).unwrap(),
+            Some(InternalEvent::Event(Event::Key(KeyEvent::new_with_kind(
+                KeyCode::PageUp,
+                KeyModifiers::empty(),
+                KeyEventKind::Release,
+            )))),
+        );
+        assert_eq!(
+            parse_event(b"\x1B[6;5:3~", false).unwrap(),
+            Some(InternalEvent::Event(Event::Key(KeyEvent::new_with_kind(
+                KeyCode::PageDown,
+                KeyModifiers::CONTROL,
+                KeyEventKind::Release,
+            )))),
+        );
+    }
+
+    #[cfg(feature = "bracketed-paste")]
+    #[test]
+    fn test_parser_split_paste() {
+        let input = b"\x1B[200~a\x1B[31mb\x1B\x1B[201c\x1B[201~x";
+
+        for split in 0..input.len() {
+            let mut parser = Parser::default();
+            parser.advance(&input[..split], true);
+            parser.advance(&input[split..], false);
+            assert_eq!(
+                parser.collect::<Vec<_>>(),
+                vec![
+                    InternalEvent::Event(Event::Paste("a\x1B[31mb\x1B\x1B[201c".to_string())),
+                    InternalEvent::Event(Event::Key(KeyCode::Char('x').into())),
+                ],
+                "split at {split}"
+            );
+        }
+    }
+
+    #[cfg(feature = "bracketed-paste")]
+    #[test]
+    fn test_input_parser_chunked_paste() {
+        let mut parser = InputParser::new();
+        parser.set_paste_mode(PasteMode::Chunked { max_size: None });
+
+        parser.advance(b"a\x1B[200~h\xC3", true);
+        parser.advance(b"\xA9llo \x1B[31m\x1B[20", true);
+        parser.advance(b"1~b", false);
+        assert_eq!(
+            parser.collect::<Vec<_>>(),
+            vec![
+                Event::Key(KeyCode::Char('a').into()),
+                Event::PasteStart,
+                Event::PasteData("h".to_string()),
+                Event::PasteData("\u{e9}llo \x1B[31m".to_string()),
+                Event::PasteEnd { truncated: false },
+                Event::Key(KeyCode::Char('b').into()),
+            ]
+        );
+    }
+
+    #[cfg(feature = "bracketed-paste")]
+    #[test]
+    fn test_input_parser_chunked_paste_max_size() {
+        let mut parser = InputParser::new();
+        parser.set_paste_mode(PasteMode::Chunked { max_size: Some(4) });
+
+        parser.advance(b"\x1B[200~ab", true);
+        parser.advance("c\u{e9}d".as_bytes(), true);
+        parser.advance(b"ef\x1B[201~\x1B[200~xy\x1B[201~", false);
+        assert_eq!(
+            parser.collect::<Vec<_>>(),
+            vec![
+                Event::PasteStart,
+                Event::PasteData("ab".to_string()),
+                Event::PasteData("c".to_string()),
+                Event::PasteEnd { truncated: true },
+                Event::PasteStart,
+                Event::PasteData("xy".to_string()),
+                Event::PasteEnd { truncated: false },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parser_split_control_strings() {
+        let input = b"\x1BP>|foot(1.0)\x1B\\\x1B]12;rgb:ffff/0000/0000\x07";
+
+        for split in 0..input.len() {
+            let mut parser = Parser {
+                awaiting_response: Some(true),
+                ..Parser::default()
+            };
+            parser.advance(&input[..split], true);
+            parser.advance(&input[split..], false);
+            assert_eq!(
+                parser.collect::<Vec<_>>(),
+                vec![
+                    InternalEvent::Response(Response::Dcs(b">|foot(1.0)".to_vec())),
+                    InternalEvent::Response(Response::Osc(b"12;rgb:ffff/0000/0000".to_vec())),
+                ],
+                "split at {split}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_input_parser_split_sequences() {
+        let mut parser = InputParser::new();
+
+        parser.advance(b"\x1B", true);
+        parser.advance(b"[", true);
+        parser.advance(b"1;2", false);
+        assert_eq!(parser.next(), None);
+
+        parser.advance(b"Dx", false);
+        assert_eq!(
+            parser.next(),
+            Some(Event::Key(KeyEvent::new(
+                KeyCode::Left,
+                KeyModifiers::SHIFT
+            )))
+        );
+        assert_eq!(parser.next(), Some(Event::Key(KeyCode::Char('x').into())));
+        assert_eq!(parser.next(), None);
+    }
+
+    #[test]
+    fn test_input_parser_lone_esc() {
+        let mut parser = InputParser::new();
+
+        parser.advance(b"\x1B", false);
+        assert_eq!(parser.next(), Some(Event::Key(KeyCode::Esc.into())));
+
+        parser.advance(b"\x1B", true);
+        assert_eq!(parser.next(), None);
+        parser.flush();
+        assert_eq!(parser.next(), Some(Event::Key(KeyCode::Esc.into())));
+
+        // Flushing doesn't complete escape sequences.
+        parser.advance(b"\x1B[", true);
+        parser.flush();
+        assert_eq!(parser.next(), None);
+        parser.advance(b"A", false);
+        assert_eq!(parser.next(), Some(Event::Key(KeyCode::Up.into())));
+    }
+
+    #[test]
+    fn test_parse_osc_colors() {
+        assert_eq!(
+            parse_event(b"\x1B]10;rgb:ffff/ffff/ffff\x1B\\", false).unwrap(),
+            Some(InternalEvent::ForegroundColor(Color::Rgb {
+                r: 255,
+                g: 255,
+                b: 255
+            })),
+        );
+        assert_eq!(
+            parse_event(b"\x1B]11;rgb:1e1e/1e1e/2e2e\x07", false).unwrap(),
+            Some(InternalEvent::BackgroundColor(Color::Rgb {
+                r: 30,
+                g: 30,
+                b: 46
+            })),
+        );
+    }
+
+    #[cfg(feature = "osc52")]
+    #[test]
+    fn test_parse_osc_clipboard() {
+        assert_eq!(
+            parse_event(b"\x1B]52;c;Zm9v\x1B\\", false).unwrap(),
+            Some(InternalEvent::ClipboardContent(b"foo".to_vec())),
+        );
+        assert_eq!(
+            parse_event(b"\x1B]52;;\x07", false).unwrap(),
+            Some(InternalEvent::ClipboardContent(Vec::new())),
+        );
+        // Invalid base64 is passed on as-is.
+        assert_eq!(
+            parse_event(b"\x1B]52;c;?\x07", false).unwrap(),
+            Some(InternalEvent::Response(Response::Osc(b"52;c;?".to_vec()))),
+        );
+    }
+
+    #[test]
+    fn test_parse_osc_palette_color() {
+        assert_eq!(
+            parse_event(b"\x1B]4;9;rgb:ffff/0000/0000\x1B\\", false).unwrap(),
+            Some(InternalEvent::PaletteColor(
+                9,
+                Color::Rgb { r: 255, g: 0, b: 0 }
+            )),
+        );
+        // Indexes outside the 256-color palette are passed on as-is.
+        assert_eq!(
+            parse_event(b"\x1B]4;256;rgb:ffff/0000/0000\x07", false).unwrap(),
+            Some(InternalEvent::Response(Response::Osc(
+                b"4;256;rgb:ffff/0000/0000".to_vec()
+            ))),
+        );
+    }
+
+    #[test]
+    fn test_parse_csi_mode_report() {
+        assert_eq!(
+            parse_event(b"\x1B[?2026;2$y", false).unwrap(),
+            Some(InternalEvent::ModeReport(2026, ModeState::Reset)),
+        );
+        assert_eq!(
+            parse_event(b"\x1B[?1049;0$y", false).unwrap(),
+            Some(InternalEvent::ModeReport(1049, ModeState::NotRecognized)),
+        );
+        assert_eq!(
+            parse_event(b"\x1B[?2027;3$y", false).unwrap(),
+            Some(InternalEvent::ModeReport(2027, ModeState::PermanentlySet)),
+        );
+        // Unknown states are passed on as-is.
+        assert_eq!(
+            parse_event(b"\x1B[?2026;5$y", false).unwrap(),
+            Some(InternalEvent::Response(Response::Csi {
+                parameters: b"?2026;5$".to_vec(),
+                final_byte: b'y',
+            })),
+        );
+    }
+
+    #[test]
+    fn test_parse_csi_response() {
+        assert_eq!(
+            parse_event(b"\x1B[?64;1;2c", false).unwrap(),
+            Some(InternalEvent::Response(Response::Csi {
+                parameters: b"?64;1;2".to_vec(),
+                final_byte: b'c',
+            })),
+        );
+        assert_eq!(
+            parse_event(b"\x1B[?997;1n", false).unwrap(),
+            Some(InternalEvent::Response(Response::Csi {
+                parameters: b"?997;1".to_vec(),
+                final_byte: b'n',
+            })),
+        );
+        assert_eq!(
+            parse_event(b"\x1B[>1;10;0c", false).unwrap(),
+            Some(InternalEvent::Response(Response::Csi {
+                parameters: b">1;10;0".to_vec(),
+                final_byte: b'c',
+            })),
+        );
+        assert_eq!(
+            parse_event(b"\x1B[8;24;80t", false).unwrap(),
+            Some(InternalEvent::Response(Response::Csi {
+                parameters: b"8;24;80".to_vec(),
+                final_byte: b't',
+            })),
+        );
+        // Incomplete responses wait for the final byte.
+        assert_eq!(parse_event(b"\x1B[?2004;1$", true).unwrap(), None);
+        assert_eq!(parse_event(b"\x1B[>1", true).unwrap(), None);
+    }
+
+    #[test]
+    fn test_parse_control_string() {
+        assert_eq!(
+            parse_event(b"\x1B]12;rgb:0000/0000/0000\x07", false).unwrap(),
+            Some(InternalEvent::Response(Response::Osc(
+                b"12;rgb:0000/0000/0000".to_vec()
+            ))),
+        );
+        assert_eq!(
+            parse_event(b"\x1B]11;rgba:ffff/ffff/ffff/ffff\x1B\\", false).unwrap(),
+            Some(InternalEvent::Response(Response::Osc(
+                b"11;rgba:ffff/ffff/ffff/ffff".to_vec()
+            ))),
+        );
+        assert_eq!(
+            parse_event(b"\x1BP>|kitty(0.40.0)\x1B\\", false).unwrap(),
+            Some(InternalEvent::Response(Response::Dcs(
+                b">|kitty(0.40.0)".to_vec()
+            ))),
+        );
+        assert_eq!(parse_event(b"\x1B]11;rgb:00", true).unwrap(), None);
+        assert_eq!(parse_event(b"\x1BP>|kitty\x1B", true).unwrap(), None);
+        assert!(parse_event(b"\x1BP>|kitty\x07", false).is_err());
+        assert!(parse_event(b"\x1B]11\x1Bx", false).is_err());
+        assert!(parse_event(b"\x1B]11\r", false).is_err());
+
+        // Without anything following, these are key presses with Alt.
+        assert_eq!(
+            parse_event(b"\x1B]", false).unwrap(),
+            Some(InternalEvent::Event(Event::Key(KeyEvent::new(
+                KeyCode::Char(']'),
+                KeyModifiers::ALT
+            )))),
+        );
+        assert_eq!(
+            parse_event(b"\x1BP", false).unwrap(),
+            Some(InternalEvent::Event(Event::Key(KeyEvent::new(
+                KeyCode::Char('P'),
+                KeyModifiers::ALT | KeyModifiers::SHIFT
+            )))),
+        );
+    }
+
+    #[test]
+    fn test_parse_control_string_without_query() {
+        let options = ParseOptions {
+            awaiting_response: false,
+            ..OPTIONS
+        };
+        assert_eq!(
+            super::parse_event(b"\x1B]", true, &options).unwrap(),
+            Some(InternalEvent::Event(Event::Key(KeyEvent::new(
+                KeyCode::Char(']'),
+                KeyModifiers::ALT
+            )))),
+        );
+
+        let mut parser = Parser::default();
+        parser.advance(b"\x1B]a\x1BPb", false);
+        assert_eq!(
+            parser.collect::<Vec<_>>(),
+            vec![
+                InternalEvent::Event(Event::Key(KeyEvent::new(
+                    KeyCode::Char(']'),
+                    KeyModifiers::ALT
+                ))),
+                InternalEvent::Event(Event::Key(KeyCode::Char('a').into())),
+                InternalEvent::Event(Event::Key(KeyEvent::new(
+                    KeyCode::Char('P'),
+                    KeyModifiers::ALT | KeyModifiers::SHIFT
+                ))),
+                InternalEvent::Event(Event::Key(KeyCode::Char('b').into())),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parser_falls_back_from_invalid_control_strings() {
+        let mut parser = Parser {
+            awaiting_response: Some(true),
+            ..Parser::default()
+        };
+        parser.advance(b"\x1B]ab\r\x1BPc\x1Bd", false);
+        assert_eq!(
+            parser.collect::<Vec<_>>(),
+            vec![
+                InternalEvent::Event(Event::Key(KeyEvent::new(
+                    KeyCode::Char(']'),
+                    KeyModifiers::ALT
+                ))),
+                InternalEvent::Event(Event::Key(KeyCode::Char('a').into())),
+                InternalEvent::Event(Event::Key(KeyCode::Char('b').into())),
+                InternalEvent::Event(Event::Key(KeyCode::Enter.into())),
+                InternalEvent::Event(Event::Key(KeyEvent::new(
+                    KeyCode::Char('P'),
+                    KeyModifiers::ALT | KeyModifiers::SHIFT
+                ))),
+                InternalEvent::Event(Event::Key(KeyCode::Char('c').into())),
+                InternalEvent::Event(Event::Key(KeyEvent::new(
+                    KeyCode::Char('d'),
+                    KeyModifiers::ALT
+                ))),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_input_parser_skips_query_responses() {
+        let mut parser = InputParser::new();
+
+        parser.advance(
+            b"\x1B[20;10R\x1B[?1u\x1B[?64;1;2c\x1B]11;rgb:0000/0000/0000\x07\r",
+            false,
+        );
+        assert_eq!(parser.next(), Some(Event::Key(KeyCode::Enter.into())));
+        assert_eq!(parser.next(), None);
+    }
+
+    #[test]
+    fn test_input_parser_reports_unknown_sequences() {
+        let mut parser = InputParser::new();
+        parser.advance(b"\x1B[99~\x1BOZ\xFF", false);
+        assert_eq!(parser.next(), None);
+
+        parser.set_report_unknown(true);
+        parser.advance(b"\x1B[99~\x1BOZa\xFF\x1BP1+r\x1B\\\x1B[20;10R", false);
+        assert_eq!(parser.next(), Some(Event::Unknown(b"\x1B[99~".to_vec())));
+        assert_eq!(parser.next(), Some(Event::Unknown(b"\x1BOZ".to_vec())));
+        assert_eq!(parser.next(), Some(Event::Key(KeyCode::Char('a').into())));
+        assert_eq!(parser.next(), Some(Event::Unknown(b"\xFF".to_vec())));
+        assert_eq!(
+            parser.next(),
+            Some(Event::Unknown(b"\x1BP1+r\x1B\\".to_vec()))
+        );
+        // Decoded responses are known.
+        assert_eq!(parser.next(), None);
+    }
+
+    #[test]
+    fn test_parser_drops_responses_without_query() {
+        let mut parser = Parser {
+            report_unknown: Some(false),
+            awaiting_response: Some(false),
+            ..Parser::default()
+        };
+        parser.advance(b"\x1B[99;1xa", false);
+        assert_eq!(
+            parser.next(),
+            Some(InternalEvent::Event(Event::Key(KeyCode::Char('a').into())))
+        );
+        assert_eq!(parser.next(), None);
+
+        parser.report_unknown = Some(true);
+        parser.advance(b"\x1B[99;1x", false);
+        assert_eq!(
+            parser.next(),
+            Some(InternalEvent::Response(Response::Csi {
+                parameters: b"99;1".to_vec(),
+                final_byte: b'x',
+            }))
+        );
+    }
+
+    #[test]
+    fn test_input_parser_flushes_unterminated_control_strings() {
+        let alt = |c| {
+            Some(Event::Key(KeyEvent::new(
+                KeyCode::Char(c),
+                KeyModifiers::ALT,
+            )))
+        };
+        let mut parser = InputParser::new();
+
+        parser.advance(b"\x1B]ab", false);
+        assert_eq!(parser.next(), None);
+        parser.flush();
+        assert_eq!(parser.next(), alt(']'));
+        assert_eq!(parser.next(), Some(Event::Key(KeyCode::Char('a').into())));
+        assert_eq!(parser.next(), Some(Event::Key(KeyCode::Char('b').into())));
+        assert_eq!(parser.next(), None);
+
+        parser.advance(b"\x1BPx\x1B]", false);
+        parser.flush();
+        assert_eq!(
+            parser.next(),
+            Some(Event::Key(KeyEvent::new(
+                KeyCode::Char('P'),
+                KeyModifiers::ALT | KeyModifiers::SHIFT
+            )))
+        );
+        assert_eq!(parser.next(), Some(Event::Key(KeyCode::Char('x').into())));
+        assert_eq!(parser.next(), alt(']'));
+        assert_eq!(parser.next(), None);
+    }
+
+    #[test]
+    fn test_input_parser_newline() {
+        let mut parser = InputParser::new();
+
+        parser.advance(b"\n", false);
+        assert_eq!(
+            parser.next(),
+            Some(Event::Key(KeyEvent::new(
+                KeyCode::Char('j'),
+                KeyModifiers::CONTROL
+            )))
+        );
+
+        parser.set_newline_is_enter(true);
+        parser.advance(b"\n\x1B[10u", false);
+        assert_eq!(parser.next(), Some(Event::Key(KeyCode::Enter.into())));
+        assert_eq!(parser.next(), Some(Event::Key(KeyCode::Enter.into())));
+    }
+
+    #[test]
+    fn test_input_parser_mouse_pixels() {
+        let mouse_event = |column, row, pixel| {
+            Some(Event::Mouse(MouseEvent {
+                kind: MouseEventKind::Down(MouseButton::Left),
+                column,
+                row,
+                modifiers: KeyModifiers::empty(),
+                pixel,
+            }))
+        };
+        let mut parser = InputParser::new();
+
+        parser.set_mouse_cell_size(Some((10, 20)));
+        parser.advance(b"\x1B[<0;106;41M", false);
+        assert_eq!(
+            parser.next(),
+            mouse_event(
+                10,
+                2,
+                Some(MousePixelPosition {
+                    x: 105,
+                    y: 40,
+                    cell_width: 10,
+                    cell_height: 20,
+                })
+            )
+        );
+
+        // The cells can't be derived without the cell size.
+        parser.set_mouse_cell_size(Some((0, 0)));
+        parser.advance(b"\x1B[<0;106;41M", false);
+        assert_eq!(
+            parser.next(),
+            mouse_event(
+                0,
+                0,
+                Some(MousePixelPosition {
+                    x: 105,
+                    y: 40,
+                    cell_width: 0,
+                    cell_height: 0,
+                })
+            )
+        );
+
+        parser.set_mouse_cell_size(None);
+        parser.advance(b"\x1B[<0;106;41M", false);
+        assert_eq!(parser.next(), mouse_event(105, 40, None));
+    }
+}
@@ -7,13 +7,13 @@ use crate::event::source::windows::WindowsEventSource;
 #[cfg(feature = "event-stream")]
 use crate::event::sys::Waker;
 use crate::event::{
-    filter::Filter, internal::InternalEvent, source::EventSource, timeout::PollTimeout,
+    filter::Filter, internal::InternalEvent, source::InternalEventSource, timeout::PollTimeout,
 };
 
 /// Can be used to read `InternalEvent`s.
 pub(crate) struct InternalEventReader {
     events: VecDeque<InternalEvent>,
-    source: Option<Box<dyn EventSource>>,
+    source: Option<Box<dyn InternalEventSource>>,
     skipped_events: Vec<InternalEvent>,
 }
 
@@ -24,7 +24,9 @@ impl Default for InternalEventReader {
         #[cfg(unix)]
         let source = UnixInternalEventSource::new();
 
-        let source = source.ok().map(|x| Box::new(x) as Box<dyn EventSource>);
+        let source = source
+            .ok()
+            .map(|x| Box::new(x) as Box<dyn InternalEventSource>);
 
         InternalEventReader {
             source,
@@ -35,9 +37,20 @@ impl Default for InternalEventReader {
 }
 
 impl InternalEventReader {
+    /// Creates a reader that reads from the given source instead of the terminal.
+    pub(crate) fn with_source(source: Box<dyn InternalEventSource>) -> Self {
+        InternalEventReader {
+            source: Some(source),
+            events: VecDeque::with_capacity(32),
+            skipped_events: Vec::with_capacity(32),
+        }
+    }
+
     /// Returns a `Waker` allowing to wake/force the `poll` method to return `Ok(false)`.
+    ///
+    /// Returns `None` if the source can't be woken up.
     #[cfg(feature = "event-stream")]
-    pub(crate) fn waker(&self) -> Waker {
+    pub(crate) fn waker(&self) -> Option<Waker> {
         self.source.as_ref().expect("reader source not set").waker()
     }
 
@@ -147,7 +160,7 @@ mod tests {
 
     #[cfg(unix)]
     use super::super::filter::CursorPositionFilter;
-    use super::{super::Event, EventSource, Filter, InternalEvent, InternalEventReader};
+    use super::{super::Event, Filter, InternalEvent, InternalEventReader, InternalEventSource};
 
     #[derive(Debug, Clone)]
     pub(crate) struct InternalEventFilter;
@@ -454,7 +467,7 @@ mod tests {
         }
     }
 
-    impl EventSource for FakeSource {
+    impl InternalEventSource for FakeSource {
         fn try_read(&mut self, _timeout: Option<Duration>) -> io::Result<Option<InternalEvent>> {
             // Return error if set in case there's just one remaining event
             if self.events.len() == 1 {
@@ -478,7 +491,7 @@ mod tests {
         }
 
         #[cfg(feature = "event-stream")]
-        fn waker(&self) -> super::super::sys::Waker {
+        fn waker(&self) -> Option<super::super::sys::Waker> {
             unimplemented!();
         }
     }
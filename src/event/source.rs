@@ -1,16 +1,37 @@
 use std::{io, time::Duration};
 
-use super::internal::InternalEvent;
 #[cfg(feature = "event-stream")]
 use super::sys::Waker;
+use super::{Event, internal::InternalEvent};
 
+pub(crate) mod fake;
 #[cfg(unix)]
 pub(crate) mod unix;
 #[cfg(windows)]
 pub(crate) mod windows;
 
+/// A source of [`Event`]s that can replace the terminal as the input of the global event reader.
+///
+/// Install it with [`set_event_source`](super::set_event_source) to drive [`read`](super::read),
+/// [`poll`](super::poll) and [`try_read`](super::try_read) from something other than the
+/// terminal, e.g. a scripted sequence of events in a test.
+///
+/// If you want to feed raw terminal input and get the same events the terminal would produce,
+/// use [`FakeTerminal`](super::FakeTerminal) instead.
+pub trait EventSource: Send {
+    /// Tries to read an `Event` within the given duration.
+    ///
+    /// # Arguments
+    ///
+    /// * `timeout` - `None` block indefinitely until an event is available, `Some(duration)` blocks
+    ///   for the given timeout
+    ///
+    /// Returns `Ok(None)` if there's no event available and timeout expires.
+    fn try_read(&mut self, timeout: Option<Duration>) -> io::Result<Option<Event>>;
+}
+
 /// An interface for trying to read an `InternalEvent` within an optional `Duration`.
-pub(crate) trait EventSource: Sync + Send {
+pub(crate) trait InternalEventSource: Send {
     /// Tries to read an `InternalEvent` within the given duration.
     ///
     /// # Arguments
@@ -22,6 +43,22 @@ pub(crate) trait EventSource: Sync + Send {
     fn try_read(&mut self, timeout: Option<Duration>) -> io::Result<Option<InternalEvent>>;
 
     /// Returns a `Waker` allowing to wake/force the `try_read` method to return `Ok(None)`.
+    ///
+    /// Sources which can't be woken up return `None`.
+    #[cfg(feature = "event-stream")]
+    fn waker(&self) -> Option<Waker>;
+}
+
+/// Adapts a public [`EventSource`] to the internal interface.
+pub(crate) struct CustomEventSource(pub(crate) Box<dyn EventSource>);
+
+impl InternalEventSource for CustomEventSource {
+    fn try_read(&mut self, timeout: Option<Duration>) -> io::Result<Option<InternalEvent>> {
+        Ok(self.0.try_read(timeout)?.map(InternalEvent::Event))
+    }
+
     #[cfg(feature = "event-stream")]
-    fn waker(&self) -> Waker;
+    fn waker(&self) -> Option<Waker> {
+        None
+    }
 }
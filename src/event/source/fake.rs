@@ -0,0 +1,237 @@
+use std::{collections::VecDeque, io, sync::Arc, time::Duration};
+
+use parking_lot::{Condvar, Mutex};
+
+#[cfg(feature = "event-stream")]
+use crate::event::sys::Waker;
+use crate::event::{
+    Event,
+    internal::{self, InternalEvent},
+    parse::Parser,
+    source::InternalEventSource,
+    timeout::PollTimeout,
+};
+
+/// A stand-in for the terminal, allowing to test input handling without a TTY.
+///
+/// The bytes passed to [`FakeTerminal::input`] go through the same parser as the bytes read from
+/// the terminal, so [`read`](crate::event::read) and [`poll`](crate::event::poll) return exactly
+/// the [`Event`]s a terminal sending those bytes would produce.
+///
+/// Every call to [`input`](FakeTerminal::input) behaves like a single read from the terminal. A
+/// lone `ESC` at the end of the bytes is reported as the Esc key, while an `ESC` followed by more
+/// bytes in the same call starts an escape sequence.
+///
+/// `FakeTerminal` is a handle, its clones share the same input.
+///
+/// # Examples
+///
+/// ```
+/// use crossterm::event::{self, Event, FakeTerminal, KeyCode};
+///
+/// let terminal = FakeTerminal::new();
+/// terminal.install();
+///
+/// terminal.input(b"\x1B[A");
+/// terminal.resize(80, 24);
+///
+/// assert_eq!(event::read()?, Event::Key(KeyCode::Up.into()));
+/// assert_eq!(event::read()?, Event::Resize(80, 24));
+///
+/// // Read from the terminal again.
+/// event::reset_event_source();
+/// # std::io::Result::Ok(())
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct FakeTerminal {
+    shared: Arc<Shared>,
+}
+
+#[derive(Debug, Default)]
+struct Shared {
+    input: Mutex<VecDeque<Input>>,
+    input_available: Condvar,
+}
+
+#[derive(Debug)]
+enum Input {
+    Bytes(Vec<u8>),
+    Resize(u16, u16),
+}
+
+impl FakeTerminal {
+    /// Creates a new `FakeTerminal` without any input.
+    pub fn new() -> FakeTerminal {
+        FakeTerminal::default()
+    }
+
+    /// Makes this terminal the source of the global event reader.
+    ///
+    /// Events which were read from the previous source, but not consumed yet, are discarded.
+    /// Use [`reset_event_source`](crate::event::reset_event_source) to read from the terminal
+    /// again.
+    pub fn install(&self) {
+        internal::replace_event_source(Some(Box::new(FakeTerminalSource {
+            terminal: self.clone(),
+            parser: Parser::default(),
+        })));
+    }
+
+    /// Sends the given bytes as if they were typed into the terminal.
+    pub fn input(&self, bytes: impl AsRef<[u8]>) {
+        self.push(Input::Bytes(bytes.as_ref().to_vec()));
+    }
+
+    /// Resizes the terminal, which emits an [`Event::Resize`] with the given size.
+    pub fn resize(&self, columns: u16, rows: u16) {
+        self.push(Input::Resize(columns, rows));
+    }
+
+    fn push(&self, input: Input) {
+        self.shared.input.lock().push_back(input);
+        self.shared.input_available.notify_all();
+    }
+}
+
+struct FakeTerminalSource {
+    terminal: FakeTerminal,
+    parser: Parser,
+}
+
+impl InternalEventSource for FakeTerminalSource {
+    fn try_read(&mut self, timeout: Option<Duration>) -> io::Result<Option<InternalEvent>> {
+        let timeout = PollTimeout::new(timeout);
+        let shared = &self.terminal.shared;
+
+        loop {
+            // check if there are buffered events from the last input
+            if let Some(event) = self.parser.next() {
+                return Ok(Some(event));
+            }
+
+            let mut input = shared.input.lock();
+            let next = loop {
+                if let Some(next) = input.pop_front() {
+                    break next;
+                }
+
+                match timeout.leftover() {
+                    None => shared.input_available.wait(&mut input),
+                    Some(leftover) if leftover.is_zero() => return Ok(None),
+                    Some(leftover) => {
+                        shared.input_available.wait_for(&mut input, leftover);
+                    }
+                }
+            };
+            drop(input);
+
+            match next {
+                Input::Bytes(bytes) => self.parser.advance(&bytes, false),
+                Input::Resize(columns, rows) => {
+                    return Ok(Some(InternalEvent::Event(Event::Resize(columns, rows))));
+                }
+            }
+        }
+    }
+
+    #[cfg(feature = "event-stream")]
+    fn waker(&self) -> Option<Waker> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{thread, time::Duration};
+
+    use super::{FakeTerminal, FakeTerminalSource, InternalEventSource, Parser};
+    use crate::event::{Event, KeyCode, KeyEvent, KeyModifiers, internal::InternalEvent};
+
+    fn source(terminal: &FakeTerminal) -> FakeTerminalSource {
+        FakeTerminalSource {
+            terminal: terminal.clone(),
+            parser: Parser::default(),
+        }
+    }
+
+    fn key(code: KeyCode, modifiers: KeyModifiers) -> Option<InternalEvent> {
+        Some(InternalEvent::Event(Event::Key(KeyEvent::new(
+            code, modifiers,
+        ))))
+    }
+
+    #[test]
+    fn test_try_read_times_out_without_input() {
+        let terminal = FakeTerminal::new();
+        let mut source = source(&terminal);
+
+        assert_eq!(source.try_read(Some(Duration::ZERO)).unwrap(), None);
+        assert_eq!(
+            source.try_read(Some(Duration::from_millis(10))).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_input_is_parsed_in_order() {
+        let terminal = FakeTerminal::new();
+        let mut source = source(&terminal);
+
+        terminal.input(b"a\x1B[1;5A");
+        terminal.resize(10, 20);
+        terminal.input(b"\x1B");
+
+        let timeout = Some(Duration::ZERO);
+        assert_eq!(
+            source.try_read(timeout).unwrap(),
+            key(KeyCode::Char('a'), KeyModifiers::NONE)
+        );
+        assert_eq!(
+            source.try_read(timeout).unwrap(),
+            key(KeyCode::Up, KeyModifiers::CONTROL)
+        );
+        assert_eq!(
+            source.try_read(timeout).unwrap(),
+            Some(InternalEvent::Event(Event::Resize(10, 20)))
+        );
+        assert_eq!(
+            source.try_read(timeout).unwrap(),
+            key(KeyCode::Esc, KeyModifiers::NONE)
+        );
+        assert_eq!(source.try_read(timeout).unwrap(), None);
+    }
+
+    #[test]
+    fn test_escape_sequence_split_across_inputs() {
+        let terminal = FakeTerminal::new();
+        let mut source = source(&terminal);
+
+        // Like a split read from the terminal, `ESC [` followed by `A` in the next input is an
+        // escape sequence as long as the `ESC` isn't the last byte of an input.
+        terminal.input(b"\x1B[");
+        terminal.input(b"A");
+
+        assert_eq!(
+            source.try_read(Some(Duration::ZERO)).unwrap(),
+            key(KeyCode::Up, KeyModifiers::NONE)
+        );
+    }
+
+    #[test]
+    fn test_try_read_wakes_up_on_input() {
+        let terminal = FakeTerminal::new();
+        let mut source = source(&terminal);
+
+        let sender = terminal.clone();
+        let handle = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(10));
+            sender.input("x");
+        });
+
+        assert_eq!(
+            source.try_read(None).unwrap(),
+            key(KeyCode::Char('x'), KeyModifiers::NONE)
+        );
+        handle.join().unwrap();
+    }
+}
@@ -1,3 +1,17 @@
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::Duration,
+};
+
+use crate::event::{internal::InternalEvent, parse::Parser, timeout::PollTimeout};
+use crate::terminal::{
+    sys::{file_descriptor::FileDesc, window_size_of},
+    tty::TtyState,
+};
+
 #[cfg(feature = "use-dev-tty")]
 pub(crate) mod tty;
 
@@ -9,3 +23,224 @@ pub(crate) use self::tty::UnixInternalEventSource;
 
 #[cfg(not(feature = "use-dev-tty"))]
 pub(crate) use self::mio::UnixInternalEventSource;
+
+/// The time to wait for the rest of an escape sequence in nanoseconds, see
+/// `event::set_escape_timeout`.
+static ESCAPE_TIMEOUT: AtomicU64 = AtomicU64::new(0);
+
+pub(crate) fn set_escape_timeout(timeout: Duration) {
+    let nanos = u64::try_from(timeout.as_nanos()).unwrap_or(u64::MAX);
+    ESCAPE_TIMEOUT.store(nanos, Ordering::Relaxed);
+}
+
+/// Returns the size `(columns, rows)` of the terminal the events are read from, falling back to
+/// `terminal::size`.
+fn terminal_size(fd: &FileDesc) -> std::io::Result<(u16, u16)> {
+    match window_size_of(fd) {
+        Ok(size) => Ok((size.columns, size.rows)),
+        Err(_) => crate::terminal::size(),
+    }
+}
+
+fn escape_timeout() -> Duration {
+    Duration::from_nanos(ESCAPE_TIMEOUT.load(Ordering::Relaxed))
+}
+
+/// The `Parser` of the terminal input, which waits up to the escape timeout for the rest of an
+/// escape sequence split across reads before it decodes a lone `ESC` as the Esc key.
+#[derive(Debug, Default)]
+pub(crate) struct TtyParser {
+    parser: Parser,
+    // Elapses when the bytes kept by the parser are decoded as if no more input is pending.
+    escape_deadline: Option<PollTimeout>,
+    // `None` decodes the input of the terminal of the process.
+    tty: Option<Arc<TtyState>>,
+}
+
+impl TtyParser {
+    /// Creates the parser of the input of a `Tty`, which is decoded with the state of the handle.
+    pub(crate) fn for_tty(tty: Arc<TtyState>) -> TtyParser {
+        TtyParser {
+            tty: Some(tty),
+            ..TtyParser::default()
+        }
+    }
+
+    /// Decodes the bytes read from the terminal, `more` tells whether more input is pending.
+    pub(crate) fn advance(&mut self, buffer: &[u8], more: bool) {
+        self.advance_with_timeout(buffer, more, escape_timeout());
+    }
+
+    fn advance_with_timeout(&mut self, buffer: &[u8], more: bool, escape_timeout: Duration) {
+        self.update_terminal_state();
+        if escape_timeout.is_zero() {
+            self.parser.advance(buffer, more);
+            self.escape_deadline = None;
+        } else {
+            self.parser.advance(buffer, true);
+            self.escape_deadline = self
+                .parser
+                .has_pending_input()
+                .then(|| PollTimeout::new(Some(escape_timeout)));
+        }
+    }
+
+    fn update_terminal_state(&mut self) {
+        if let Some(tty) = &self.tty {
+            self.parser
+                .set_terminal_state(tty.newline_is_enter(), tty.mouse_cell_size());
+        }
+    }
+
+    /// Returns the timeout to poll the terminal with, which ends early if the escape timeout
+    /// elapses first.
+    pub(crate) fn poll_timeout(&self, timeout: &PollTimeout) -> Option<Duration> {
+        let escape_timeout = self
+            .escape_deadline
+            .as_ref()
+            .and_then(PollTimeout::leftover);
+        match (timeout.leftover(), escape_timeout) {
+            (Some(timeout), Some(escape_timeout)) => Some(timeout.min(escape_timeout)),
+            (timeout, None) => timeout,
+            (None, escape_timeout) => escape_timeout,
+        }
+    }
+}
+
+impl Iterator for TtyParser {
+    type Item = InternalEvent;
+
+    fn next(&mut self) -> Option<InternalEvent> {
+        if let Some(event) = self.parser.next() {
+            return Some(event);
+        }
+        if self
+            .escape_deadline
+            .as_ref()
+            .is_some_and(PollTimeout::elapsed)
+        {
+            self.escape_deadline = None;
+            self.update_terminal_state();
+            self.parser.flush();
+            return self.parser.next();
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "libc")]
+    use std::os::unix::io::IntoRawFd;
+    use std::{io::Write, os::unix::net::UnixStream, time::Duration};
+
+    use super::{TtyParser, UnixInternalEventSource};
+    use crate::event::{
+        Event, KeyCode, KeyEvent, KeyModifiers, internal::InternalEvent,
+        source::InternalEventSource, timeout::PollTimeout,
+    };
+    use crate::terminal::{
+        RawModeOptions,
+        sys::{RawMode, file_descriptor::FileDesc, open_pty},
+    };
+
+    fn key(event: impl Into<KeyEvent>) -> Option<InternalEvent> {
+        Some(InternalEvent::Event(Event::Key(event.into())))
+    }
+
+    #[test]
+    fn test_tty_parser_without_escape_timeout() {
+        let mut parser = TtyParser::default();
+
+        parser.advance_with_timeout(b"\x1B", false, Duration::ZERO);
+        assert_eq!(parser.next(), key(KeyCode::Esc));
+        parser.advance_with_timeout(b"[A", false, Duration::ZERO);
+        assert_eq!(parser.next(), key(KeyCode::Char('[')));
+        assert_eq!(
+            parser.next(),
+            key(KeyEvent::new(KeyCode::Char('A'), KeyModifiers::SHIFT))
+        );
+
+        // Incomplete sequences don't end the poll early.
+        parser.advance_with_timeout(b"\x1B[1;", false, Duration::ZERO);
+        assert_eq!(parser.next(), None);
+        assert_eq!(parser.poll_timeout(&PollTimeout::new(None)), None);
+    }
+
+    #[test]
+    fn test_tty_parser_completes_split_sequences() {
+        let mut parser = TtyParser::default();
+        let timeout = Duration::from_secs(60);
+
+        parser.advance_with_timeout(b"\x1B", false, timeout);
+        assert_eq!(parser.next(), None);
+        assert!(parser.poll_timeout(&PollTimeout::new(None)).unwrap() <= timeout);
+
+        parser.advance_with_timeout(b"[A\x1B", false, timeout);
+        assert_eq!(parser.next(), key(KeyCode::Up));
+        assert_eq!(parser.next(), None);
+        parser.advance_with_timeout(b"x", false, timeout);
+        assert_eq!(
+            parser.next(),
+            key(KeyEvent::new(KeyCode::Char('x'), KeyModifiers::ALT))
+        );
+        assert_eq!(parser.poll_timeout(&PollTimeout::new(None)), None);
+    }
+
+    #[test]
+    fn test_tty_parser_flushes_after_escape_timeout() {
+        let mut parser = TtyParser::default();
+
+        parser.advance_with_timeout(b"a\x1B", false, Duration::from_millis(1));
+        assert_eq!(parser.next(), key(KeyCode::Char('a')));
+        std::thread::sleep(Duration::from_millis(5));
+        assert_eq!(
+            parser.poll_timeout(&PollTimeout::new(Some(Duration::from_secs(1)))),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(parser.next(), key(KeyCode::Esc));
+        assert_eq!(parser.next(), None);
+        assert_eq!(parser.poll_timeout(&PollTimeout::new(None)), None);
+    }
+
+    #[test]
+    fn test_event_source_reports_resumed() {
+        let (input, _output) = UnixStream::pair().unwrap();
+        input.set_nonblocking(true).unwrap();
+        #[cfg(feature = "libc")]
+        let input = FileDesc::new(input.into_raw_fd(), true);
+        #[cfg(not(feature = "libc"))]
+        let input = FileDesc::Owned(input.into());
+        let mut source = UnixInternalEventSource::from_file_descriptor(input).unwrap();
+
+        // Continuing a process which isn't stopped does nothing else.
+        signal_hook::low_level::raise(signal_hook::consts::SIGCONT).unwrap();
+        assert_eq!(
+            source.try_read(Some(Duration::from_secs(1))).unwrap(),
+            Some(InternalEvent::Event(Event::Resumed))
+        );
+    }
+
+    #[test]
+    fn test_event_source_waits_for_escape_timeout() {
+        let (mut terminal, tty) = open_pty().unwrap();
+        RawMode::new()
+            .enable(&tty, RawModeOptions::default())
+            .unwrap();
+        #[cfg(feature = "libc")]
+        let tty = FileDesc::new(tty.into_raw_fd(), true);
+        #[cfg(not(feature = "libc"))]
+        let tty = FileDesc::Owned(tty.into());
+        let mut source = UnixInternalEventSource::from_file_descriptor(tty).unwrap();
+
+        super::set_escape_timeout(Duration::from_millis(50));
+        terminal.write_all(b"\x1B").unwrap();
+        let esc = source.try_read(Some(Duration::from_secs(1))).unwrap();
+        terminal.write_all(b"a").unwrap();
+        let a = source.try_read(Some(Duration::from_secs(1))).unwrap();
+        super::set_escape_timeout(Duration::ZERO);
+
+        assert_eq!(esc, key(KeyCode::Esc));
+        assert_eq!(a, key(KeyCode::Char('a')));
+    }
+}
@@ -1,4 +1,4 @@
-use std::{collections::VecDeque, io, time::Duration};
+use std::{ffi::c_int, io, sync::Arc, time::Duration};
 
 use mio::{Events, Interest, Poll, Token, unix::SourceFd};
 use signal_hook_mio::v1_0::Signals;
@@ -6,10 +6,18 @@ use signal_hook_mio::v1_0::Signals;
 #[cfg(feature = "event-stream")]
 use crate::event::sys::Waker;
 use crate::event::{
-    Event, internal::InternalEvent, source::EventSource, sys::unix::parse::parse_event,
+    Event,
+    internal::InternalEvent,
+    source::{
+        InternalEventSource,
+        unix::{TtyParser, terminal_size},
+    },
     timeout::PollTimeout,
 };
-use crate::terminal::sys::file_descriptor::{FileDesc, tty_fd};
+use crate::terminal::{
+    sys::file_descriptor::{FileDesc, tty_fd},
+    tty::TtyState,
+};
 
 // Tokens to identify file descriptor
 const TTY_TOKEN: Token = Token(0);
@@ -25,7 +33,7 @@ const TTY_BUFFER_SIZE: usize = 1_024;
 pub(crate) struct UnixInternalEventSource {
     poll: Poll,
     events: Events,
-    parser: Parser,
+    parser: TtyParser,
     tty_buffer: [u8; TTY_BUFFER_SIZE],
     tty_fd: FileDesc<'static>,
     signals: Signals,
@@ -39,6 +47,24 @@ impl UnixInternalEventSource {
     }
 
     pub(crate) fn from_file_descriptor(input_fd: FileDesc<'static>) -> io::Result<Self> {
+        UnixInternalEventSource::with_parser(
+            input_fd,
+            TtyParser::default(),
+            &[signal_hook::consts::SIGWINCH, signal_hook::consts::SIGCONT],
+        )
+    }
+
+    /// Creates the event source of a `Tty`. Signals are only sent for the controlling terminal of
+    /// the process, so it doesn't report resizes and resumes.
+    pub(crate) fn for_tty(input_fd: FileDesc<'static>, tty: Arc<TtyState>) -> io::Result<Self> {
+        UnixInternalEventSource::with_parser(input_fd, TtyParser::for_tty(tty), &[])
+    }
+
+    fn with_parser(
+        input_fd: FileDesc<'static>,
+        parser: TtyParser,
+        signals: &[c_int],
+    ) -> io::Result<Self> {
         let poll = Poll::new()?;
         let registry = poll.registry();
 
@@ -46,7 +72,7 @@ impl UnixInternalEventSource {
         let mut tty_ev = SourceFd(&tty_raw_fd);
         registry.register(&mut tty_ev, TTY_TOKEN, Interest::READABLE)?;
 
-        let mut signals = Signals::new([signal_hook::consts::SIGWINCH])?;
+        let mut signals = Signals::new(signals)?;
         registry.register(&mut signals, SIGNAL_TOKEN, Interest::READABLE)?;
 
         #[cfg(feature = "event-stream")]
@@ -55,7 +81,7 @@ impl UnixInternalEventSource {
         Ok(UnixInternalEventSource {
             poll,
             events: Events::with_capacity(3),
-            parser: Parser::default(),
+            parser,
             tty_buffer: [0u8; TTY_BUFFER_SIZE],
             tty_fd: input_fd,
             signals,
@@ -65,7 +91,7 @@ impl UnixInternalEventSource {
     }
 }
 
-impl EventSource for UnixInternalEventSource {
+impl InternalEventSource for UnixInternalEventSource {
     fn try_read(&mut self, timeout: Option<Duration>) -> io::Result<Option<InternalEvent>> {
         if let Some(event) = self.parser.next() {
             return Ok(Some(event));
@@ -74,7 +100,10 @@ impl EventSource for UnixInternalEventSource {
         let timeout = PollTimeout::new(timeout);
 
         loop {
-            if let Err(e) = self.poll.poll(&mut self.events, timeout.leftover()) {
+            if let Err(e) = self
+                .poll
+                .poll(&mut self.events, self.parser.poll_timeout(&timeout))
+            {
                 // Mio will throw an interrupted error in case of cursor position retrieval. We need to retry until it succeeds.
                 // Previous versions of Mio (< 0.7) would automatically retry the poll call if it was interrupted (if EINTR was returned).
                 // https://docs.rs/mio/0.7.0/mio/struct.Poll.html#notes
@@ -86,8 +115,9 @@ impl EventSource for UnixInternalEventSource {
             };
 
             if self.events.is_empty() {
-                // No readiness events = timeout
-                return Ok(None);
+                // No readiness events = timeout, a lone `ESC` is the Esc key if the escape
+                // timeout elapsed.
+                return Ok(self.parser.next());
             }
 
             for token in self.events.iter().map(|x| x.token()) {
@@ -102,6 +132,16 @@ impl EventSource for UnixInternalEventSource {
                                             read_count == TTY_BUFFER_SIZE,
                                         );
                                     }
+
+                                    if let Some(event) = self.parser.next() {
+                                        return Ok(Some(event));
+                                    }
+
+                                    // All input is read, wait in `poll` for more input or for the
+                                    // escape timeout to elapse instead of blocking in `read`.
+                                    if read_count < TTY_BUFFER_SIZE {
+                                        break;
+                                    }
                                 }
                                 Err(e) => {
                                     // No more data to read at the moment. We will receive another event
@@ -114,21 +154,22 @@ impl EventSource for UnixInternalEventSource {
                                     }
                                 }
                             };
-
-                            if let Some(event) = self.parser.next() {
-                                return Ok(Some(event));
-                            }
                         }
                     }
                     SIGNAL_TOKEN => {
-                        if self.signals.pending().next() == Some(signal_hook::consts::SIGWINCH) {
+                        let signals: Vec<_> = self.signals.pending().collect();
+                        // The program is redrawn with the current size once it's resumed.
+                        if signals.contains(&signal_hook::consts::SIGCONT) {
+                            return Ok(Some(InternalEvent::Event(Event::Resumed)));
+                        }
+                        if signals.contains(&signal_hook::consts::SIGWINCH) {
                             // TODO Should we remove tput?
                             //
                             // This can take a really long time, because terminal::size can
                             // launch new process (tput) and then it parses its output. It's
                             // not a really long time from the absolute time point of view, but
                             // it's a really long time from an async executor's point of view.
-                            let new_size = crate::terminal::size()?;
+                            let new_size = terminal_size(&self.tty_fd)?;
                             return Ok(Some(InternalEvent::Event(Event::Resize(
                                 new_size.0, new_size.1,
                             ))));
@@ -147,83 +188,13 @@ impl EventSource for UnixInternalEventSource {
 
             // Processing above can take some time, check if timeout expired
             if timeout.elapsed() {
-                return Ok(None);
+                return Ok(self.parser.next());
             }
         }
     }
 
     #[cfg(feature = "event-stream")]
-    fn waker(&self) -> Waker {
-        self.waker.clone()
-    }
-}
-
-//
-// Following `Parser` structure exists for two reasons:
-//
-//  * mimic anes Parser interface
-//  * move the advancing, parsing, ... stuff out of the `try_read` method
-//
-#[derive(Debug)]
-struct Parser {
-    buffer: Vec<u8>,
-    internal_events: VecDeque<InternalEvent>,
-}
-
-impl Default for Parser {
-    fn default() -> Self {
-        Parser {
-            // This buffer is used for -> 1 <- ANSI escape sequence. Are we
-            // aware of any ANSI escape sequence that is bigger? Can we make
-            // it smaller?
-            //
-            // Probably not worth spending more time on this as "there's a plan"
-            // to use the anes crate parser.
-            buffer: Vec::with_capacity(256),
-            // TTY_BUFFER_SIZE is 1_024 bytes. How many ANSI escape sequences can
-            // fit? What is an average sequence length? Let's guess here
-            // and say that the average ANSI escape sequence length is 8 bytes. Thus
-            // the buffer size should be 1024/8=128 to avoid additional allocations
-            // when processing large amounts of data.
-            //
-            // There's no need to make it bigger, because when you look at the `try_read`
-            // method implementation, all events are consumed before the next TTY_BUFFER
-            // is processed -> events pushed.
-            internal_events: VecDeque::with_capacity(128),
-        }
-    }
-}
-
-impl Parser {
-    fn advance(&mut self, buffer: &[u8], more: bool) {
-        for (idx, byte) in buffer.iter().enumerate() {
-            let more = idx + 1 < buffer.len() || more;
-
-            self.buffer.push(*byte);
-
-            match parse_event(&self.buffer, more) {
-                Ok(Some(ie)) => {
-                    self.internal_events.push_back(ie);
-                    self.buffer.clear();
-                }
-                Ok(None) => {
-                    // Event can't be parsed, because we don't have enough bytes for
-                    // the current sequence. Keep the buffer and process next bytes.
-                }
-                Err(_) => {
-                    // Event can't be parsed (not enough parameters, parameter is not a number, ...).
-                    // Clear the buffer and continue with another sequence.
-                    self.buffer.clear();
-                }
-            }
-        }
-    }
-}
-
-impl Iterator for Parser {
-    type Item = InternalEvent;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        self.internal_events.pop_front()
+    fn waker(&self) -> Option<Waker> {
+        Some(self.waker.clone())
     }
 }
@@ -1,6 +1,6 @@
 #[cfg(feature = "libc")]
 use std::os::unix::prelude::AsRawFd;
-use std::{collections::VecDeque, io, os::unix::net::UnixStream, time::Duration};
+use std::{io, os::unix::net::UnixStream, sync::Arc, time::Duration};
 
 #[cfg(not(feature = "libc"))]
 use rustix::fd::{AsFd, AsRawFd};
@@ -13,8 +13,17 @@ use filedescriptor::{POLLIN, poll, pollfd};
 
 #[cfg(feature = "event-stream")]
 use crate::event::sys::Waker;
-use crate::event::{internal::InternalEvent, source::EventSource, sys::unix::parse::parse_event};
-use crate::terminal::sys::file_descriptor::{FileDesc, tty_fd};
+use crate::event::{
+    internal::InternalEvent,
+    source::{
+        InternalEventSource,
+        unix::{TtyParser, terminal_size},
+    },
+};
+use crate::terminal::{
+    sys::file_descriptor::{FileDesc, tty_fd},
+    tty::TtyState,
+};
 
 /// Holds a prototypical Waker and a receiver we can wait on when doing select().
 #[cfg(feature = "event-stream")]
@@ -40,10 +49,12 @@ impl WakePipe {
 const TTY_BUFFER_SIZE: usize = 1_024;
 
 pub(crate) struct UnixInternalEventSource {
-    parser: Parser,
+    parser: TtyParser,
     tty_buffer: [u8; TTY_BUFFER_SIZE],
     tty: FileDesc<'static>,
-    winch_signal_receiver: UnixStream,
+    // `None` for the sources of a `Tty`, which don't report signals.
+    winch_signal_receiver: Option<UnixStream>,
+    cont_signal_receiver: Option<UnixStream>,
     #[cfg(feature = "event-stream")]
     wake_pipe: WakePipe,
 }
@@ -61,18 +72,44 @@ impl UnixInternalEventSource {
     }
 
     pub(crate) fn from_file_descriptor(input_fd: FileDesc<'static>) -> io::Result<Self> {
+        UnixInternalEventSource::with_parser(input_fd, TtyParser::default(), true)
+    }
+
+    /// Creates the event source of a `Tty`. Signals are only sent for the controlling terminal of
+    /// the process, so it doesn't report resizes and resumes.
+    pub(crate) fn for_tty(input_fd: FileDesc<'static>, tty: Arc<TtyState>) -> io::Result<Self> {
+        UnixInternalEventSource::with_parser(input_fd, TtyParser::for_tty(tty), false)
+    }
+
+    fn with_parser(
+        input_fd: FileDesc<'static>,
+        parser: TtyParser,
+        signals: bool,
+    ) -> io::Result<Self> {
         Ok(UnixInternalEventSource {
-            parser: Parser::default(),
+            parser,
             tty_buffer: [0u8; TTY_BUFFER_SIZE],
             tty: input_fd,
-            winch_signal_receiver: {
+            winch_signal_receiver: if signals {
                 let (receiver, sender) = nonblocking_unix_pair()?;
                 // Unregistering is unnecessary because EventSource is a singleton
                 #[cfg(feature = "libc")]
                 pipe::register(libc::SIGWINCH, sender)?;
                 #[cfg(not(feature = "libc"))]
                 pipe::register(rustix::process::Signal::WINCH.as_raw(), sender)?;
-                receiver
+                Some(receiver)
+            } else {
+                None
+            },
+            cont_signal_receiver: if signals {
+                let (receiver, sender) = nonblocking_unix_pair()?;
+                #[cfg(feature = "libc")]
+                pipe::register(libc::SIGCONT, sender)?;
+                #[cfg(not(feature = "libc"))]
+                pipe::register(rustix::process::Signal::CONT.as_raw(), sender)?;
+                Some(receiver)
+            } else {
+                None
             },
             #[cfg(feature = "event-stream")]
             wake_pipe: WakePipe::new()?,
@@ -98,7 +135,20 @@ fn read_complete(fd: &FileDesc, buf: &mut [u8]) -> io::Result<usize> {
     }
 }
 
-impl EventSource for UnixInternalEventSource {
+/// Reads the bytes written to the pipe by the signal handler.
+fn drain_signal_pipe(receiver: &Option<UnixStream>) -> io::Result<()> {
+    let Some(receiver) = receiver else {
+        return Ok(());
+    };
+    #[cfg(feature = "libc")]
+    let fd = FileDesc::new(receiver.as_raw_fd(), false);
+    #[cfg(not(feature = "libc"))]
+    let fd = FileDesc::Borrowed(receiver.as_fd());
+    while read_complete(&fd, &mut [0; 1024])? != 0 {}
+    Ok(())
+}
+
+impl InternalEventSource for UnixInternalEventSource {
     fn try_read(&mut self, timeout: Option<Duration>) -> io::Result<Option<InternalEvent>> {
         let timeout = PollTimeout::new(timeout);
 
@@ -110,16 +160,30 @@ impl EventSource for UnixInternalEventSource {
             }
         }
 
+        // `poll` ignores negative file descriptors.
+        fn make_signal_pollfd(receiver: &Option<UnixStream>) -> pollfd {
+            receiver.as_ref().map_or(
+                pollfd {
+                    fd: -1,
+                    events: POLLIN,
+                    revents: 0,
+                },
+                make_pollfd,
+            )
+        }
+
         #[cfg(not(feature = "event-stream"))]
         let mut fds = [
             make_pollfd(&self.tty),
-            make_pollfd(&self.winch_signal_receiver),
+            make_signal_pollfd(&self.winch_signal_receiver),
+            make_signal_pollfd(&self.cont_signal_receiver),
         ];
 
         #[cfg(feature = "event-stream")]
         let mut fds = [
             make_pollfd(&self.tty),
-            make_pollfd(&self.winch_signal_receiver),
+            make_signal_pollfd(&self.winch_signal_receiver),
+            make_signal_pollfd(&self.cont_signal_receiver),
             make_pollfd(&self.wake_pipe.receiver),
         ];
 
@@ -128,7 +192,7 @@ impl EventSource for UnixInternalEventSource {
             if let Some(event) = self.parser.next() {
                 return Ok(Some(event));
             }
-            match poll(&mut fds, timeout.leftover()) {
+            match poll(&mut fds, self.parser.poll_timeout(&timeout)) {
                 Err(filedescriptor::Error::Poll(e)) | Err(filedescriptor::Error::Io(e)) => {
                     match e.kind() {
                         // retry on EINTR
@@ -157,32 +221,33 @@ impl EventSource for UnixInternalEventSource {
                         return Ok(Some(event));
                     }
 
-                    if read_count == 0 {
+                    // All input is read, wait in `poll` for more input or for the escape
+                    // timeout to elapse instead of blocking in `read`.
+                    if read_count < TTY_BUFFER_SIZE {
                         break;
                     }
                 }
             }
+            if fds[2].revents & POLLIN != 0 {
+                drain_signal_pipe(&self.cont_signal_receiver)?;
+                return Ok(Some(InternalEvent::Event(Event::Resumed)));
+            }
             if fds[1].revents & POLLIN != 0 {
-                #[cfg(feature = "libc")]
-                let fd = FileDesc::new(self.winch_signal_receiver.as_raw_fd(), false);
-                #[cfg(not(feature = "libc"))]
-                let fd = FileDesc::Borrowed(self.winch_signal_receiver.as_fd());
-                // drain the pipe
-                while read_complete(&fd, &mut [0; 1024])? != 0 {}
+                drain_signal_pipe(&self.winch_signal_receiver)?;
                 // TODO Should we remove tput?
                 //
                 // This can take a really long time, because terminal::size can
                 // launch new process (tput) and then it parses its output. It's
                 // not a really long time from the absolute time point of view, but
                 // it's a really long time from an async executor's point of view.
-                let new_size = crate::terminal::size()?;
+                let new_size = terminal_size(&self.tty)?;
                 return Ok(Some(InternalEvent::Event(Event::Resize(
                     new_size.0, new_size.1,
                 ))));
             }
 
             #[cfg(feature = "event-stream")]
-            if fds[2].revents & POLLIN != 0 {
+            if fds[3].revents & POLLIN != 0 {
                 #[cfg(feature = "libc")]
                 let fd = FileDesc::new(self.wake_pipe.receiver.as_raw_fd(), false);
                 #[cfg(not(feature = "libc"))]
@@ -196,81 +261,12 @@ impl EventSource for UnixInternalEventSource {
                 ));
             }
         }
-        Ok(None)
+        // A lone `ESC` is the Esc key if the escape timeout elapsed.
+        Ok(self.parser.next())
     }
 
     #[cfg(feature = "event-stream")]
-    fn waker(&self) -> Waker {
-        self.wake_pipe.waker.clone()
-    }
-}
-
-//
-// Following `Parser` structure exists for two reasons:
-//
-//  * mimic anes Parser interface
-//  * move the advancing, parsing, ... stuff out of the `try_read` method
-//
-#[derive(Debug)]
-struct Parser {
-    buffer: Vec<u8>,
-    internal_events: VecDeque<InternalEvent>,
-}
-
-impl Default for Parser {
-    fn default() -> Self {
-        Parser {
-            // This buffer is used for -> 1 <- ANSI escape sequence. Are we
-            // aware of any ANSI escape sequence that is bigger? Can we make
-            // it smaller?
-            //
-            // Probably not worth spending more time on this as "there's a plan"
-            // to use the anes crate parser.
-            buffer: Vec::with_capacity(256),
-            // TTY_BUFFER_SIZE is 1_024 bytes. How many ANSI escape sequences can
-            // fit? What is an average sequence length? Let's guess here
-            // and say that the average ANSI escape sequence length is 8 bytes. Thus
-            // the buffer size should be 1024/8=128 to avoid additional allocations
-            // when processing large amounts of data.
-            //
-            // There's no need to make it bigger, because when you look at the `try_read`
-            // method implementation, all events are consumed before the next TTY_BUFFER
-            // is processed -> events pushed.
-            internal_events: VecDeque::with_capacity(128),
-        }
-    }
-}
-
-impl Parser {
-    fn advance(&mut self, buffer: &[u8], more: bool) {
-        for (idx, byte) in buffer.iter().enumerate() {
-            let more = idx + 1 < buffer.len() || more;
-
-            self.buffer.push(*byte);
-
-            match parse_event(&self.buffer, more) {
-                Ok(Some(ie)) => {
-                    self.internal_events.push_back(ie);
-                    self.buffer.clear();
-                }
-                Ok(None) => {
-                    // Event can't be parsed, because we don't have enough bytes for
-                    // the current sequence. Keep the buffer and process next bytes.
-                }
-                Err(_) => {
-                    // Event can't be parsed (not enough parameters, parameter is not a number, ...).
-                    // Clear the buffer and continue with another sequence.
-                    self.buffer.clear();
-                }
-            }
-        }
-    }
-}
-
-impl Iterator for Parser {
-    type Item = InternalEvent;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        self.internal_events.pop_front()
+    fn waker(&self) -> Option<Waker> {
+        Some(self.wake_pipe.waker.clone())
     }
 }
@@ -11,7 +11,7 @@ use crate::event::{
 use crate::event::sys::Waker;
 use crate::event::{
     internal::InternalEvent,
-    source::EventSource,
+    source::InternalEventSource,
     sys::windows::parse::{handle_key_event, handle_mouse_event},
     timeout::PollTimeout,
 };
@@ -40,7 +40,7 @@ impl WindowsEventSource {
     }
 }
 
-impl EventSource for WindowsEventSource {
+impl InternalEventSource for WindowsEventSource {
     fn try_read(&mut self, timeout: Option<Duration>) -> std::io::Result<Option<InternalEvent>> {
         let poll_timeout = PollTimeout::new(timeout);
 
@@ -94,7 +94,7 @@ impl EventSource for WindowsEventSource {
     }
 
     #[cfg(feature = "event-stream")]
-    fn waker(&self) -> Waker {
-        self.poll.waker()
+    fn waker(&self) -> Option<Waker> {
+        Some(self.poll.waker())
     }
 }
@@ -13,12 +13,7 @@ use std::{
 
 use futures_core::stream::Stream;
 
-use crate::event::{
-    Event,
-    filter::EventFilter,
-    internal::{self, InternalEvent},
-    sys::Waker,
-};
+use crate::event::{Event, filter::EventFilter, internal, sys::Waker};
 
 /// A stream of `Result<Event>`.
 ///
@@ -33,7 +28,7 @@ use crate::event::{
 /// it (`event-stream-*`).
 #[derive(Debug)]
 pub struct EventStream {
-    poll_internal_waker: Waker,
+    poll_internal_waker: Option<Waker>,
     stream_wake_task_executed: Arc<AtomicBool>,
     stream_wake_task_should_shutdown: Arc<AtomicBool>,
     task_sender: SyncSender<Task>,
@@ -42,11 +37,19 @@ pub struct EventStream {
 impl Default for EventStream {
     fn default() -> Self {
         let (task_sender, receiver) = mpsc::sync_channel::<Task>(1);
+        let poll_internal_waker = internal::lock_event_reader().waker();
+
+        // Sources without a waker can't be interrupted, so poll them with a timeout to notice
+        // the shutdown request.
+        let poll_timeout = match poll_internal_waker {
+            Some(_) => None,
+            None => Some(Duration::from_millis(50)),
+        };
 
         thread::spawn(move || {
             while let Ok(task) = receiver.recv() {
                 loop {
-                    if let Ok(true) = internal::poll(None, &EventFilter) {
+                    if let Ok(true) = internal::poll(poll_timeout, &EventFilter) {
                         break;
                     }
 
@@ -61,7 +64,7 @@ impl Default for EventStream {
         });
 
         EventStream {
-            poll_internal_waker: internal::lock_event_reader().waker(),
+            poll_internal_waker,
             stream_wake_task_executed: Arc::new(AtomicBool::new(false)),
             stream_wake_task_should_shutdown: Arc::new(AtomicBool::new(false)),
             task_sender,
@@ -106,10 +109,11 @@ impl Stream for EventStream {
     fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
         match internal::poll(Some(Duration::from_secs(0)), &EventFilter) {
             Ok(true) => match internal::read(&EventFilter) {
-                Ok(InternalEvent::Event(event)) => Poll::Ready(Some(Ok(event))),
+                Ok(event) => match event.into_event() {
+                    Some(event) => Poll::Ready(Some(Ok(event))),
+                    None => unreachable!(),
+                },
                 Err(e) => Poll::Ready(Some(Err(e))),
-                #[cfg(unix)]
-                _ => unreachable!(),
             },
             Ok(false) => {
                 if !self
@@ -142,6 +146,8 @@ impl Drop for EventStream {
     fn drop(&mut self) {
         self.stream_wake_task_should_shutdown
             .store(true, Ordering::SeqCst);
-        let _ = self.poll_internal_waker.wake();
+        if let Some(waker) = &self.poll_internal_waker {
+            let _ = waker.wake();
+        }
     }
 }
@@ -366,5 +366,6 @@ fn parse_mouse_event_record(
         column: xpos,
         row: ypos,
         modifiers,
+        pixel: None,
     }))
 }
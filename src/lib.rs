@@ -47,9 +47,15 @@
 //! - Module [`event`](event/index.html)
 //!   - Keyboard events -
 //!     [`PushKeyboardEnhancementFlags`](event/struct.PushKeyboardEnhancementFlags.html),
-//!     [`PopKeyboardEnhancementFlags`](event/struct.PopKeyboardEnhancementFlags.html)
+//!     [`PopKeyboardEnhancementFlags`](event/struct.PopKeyboardEnhancementFlags.html),
+//!     [`EnableModifyOtherKeys`](event/struct.EnableModifyOtherKeys.html),
+//!     [`DisableModifyOtherKeys`](event/struct.DisableModifyOtherKeys.html)
 //!   - Mouse events - [`EnableMouseCapture`](event/struct.EnableMouseCapture.html),
-//!     [`DisableMouseCapture`](event/struct.DisableMouseCapture.html)
+//!     [`DisableMouseCapture`](event/struct.DisableMouseCapture.html),
+//!     [`EnableMouseCaptureWith`](event/struct.EnableMouseCaptureWith.html),
+//!     [`DisableMouseCaptureWith`](event/struct.DisableMouseCaptureWith.html),
+//!     [`EnableMousePixelReporting`](event/struct.EnableMousePixelReporting.html),
+//!     [`DisableMousePixelReporting`](event/struct.DisableMousePixelReporting.html)
 //! - Module [`style`](style/index.html)
 //!   - Colors - [`SetForegroundColor`](style/struct.SetForegroundColor.html),
 //!     [`SetBackgroundColor`](style/struct.SetBackgroundColor.html),
@@ -58,6 +64,9 @@
 //!     [`PrintStyledContent`](style/struct.PrintStyledContent.html)
 //!   - Hyperlinks - [`StartHyperlink`](style/struct.StartHyperlink.html),
 //!     [`EndHyperlink`](style/struct.EndHyperlink.html)
+//!   - Palette - [`SetPaletteColor`](style/struct.SetPaletteColor.html),
+//!     [`ResetPaletteColor`](style/struct.ResetPaletteColor.html),
+//!     [`ResetPalette`](style/struct.ResetPalette.html)
 //! - Module [`terminal`](terminal/index.html)
 //!   - Scrolling - [`ScrollUp`](terminal/struct.ScrollUp.html),
 //!     [`ScrollDown`](terminal/struct.ScrollDown.html)
@@ -242,7 +251,6 @@ pub use crate::command::{Command, ExecutableCommand, QueueableCommand, Synchroni
 /// A module to work with the terminal cursor
 pub mod cursor;
 /// A module to read events.
-#[cfg(feature = "events")]
 pub mod event;
 /// A module to apply attributes and colors on your text.
 pub mod style;
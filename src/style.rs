@@ -119,10 +119,16 @@ use std::{
 use crate::command::execute_fmt;
 use crate::{Command, csi, impl_display};
 
+#[cfg(feature = "events")]
+pub use self::query::{
+    query_background_color, query_color_scheme, query_foreground_color, query_palette_color,
+};
 pub use self::{
     attributes::Attributes,
     content_style::ContentStyle,
     hyperlink::{EndHyperlink, StartHyperlink},
+    palette::{ResetPalette, ResetPaletteColor, SetPaletteColor},
+    query::ColorScheme,
     styled_content::StyledContent,
     stylize::Stylize,
     types::{Attribute, Color, Colored, Colors},
@@ -131,6 +137,8 @@ pub use self::{
 mod attributes;
 mod content_style;
 mod hyperlink;
+mod palette;
+mod query;
 mod styled_content;
 mod stylize;
 mod sys;
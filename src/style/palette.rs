@@ -0,0 +1,119 @@
+use std::fmt;
+
+use crate::{Command, impl_display, osc};
+
+/// A command that changes an entry of the terminal's 256-color palette.
+///
+/// Indexes 0 to 15 are the named colors, see
+/// [`Color::palette_index`](crate::style::Color::palette_index). Text printed with the color before
+/// and after the command changes, as the palette is looked up when the screen is drawn.
+///
+/// Use [`ResetPaletteColor`] or [`ResetPalette`] to restore the user's palette on exit.
+///
+/// # Notes
+///
+/// Commands must be executed/queued for execution otherwise they do nothing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SetPaletteColor {
+    /// The palette index.
+    pub index: u8,
+    /// The red component.
+    pub r: u8,
+    /// The green component.
+    pub g: u8,
+    /// The blue component.
+    pub b: u8,
+}
+
+impl Command for SetPaletteColor {
+    fn write_ansi(&self, f: &mut impl fmt::Write) -> fmt::Result {
+        // OSC 4 ; index ; rgb:RR/GG/BB ST
+        write!(
+            f,
+            osc!("4;{};rgb:{:02x}/{:02x}/{:02x}"),
+            self.index, self.r, self.g, self.b
+        )
+    }
+
+    #[cfg(windows)]
+    fn execute_winapi(&self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// A command that restores an entry of the terminal's 256-color palette to its default, see
+/// [`SetPaletteColor`].
+///
+/// # Notes
+///
+/// Commands must be executed/queued for execution otherwise they do nothing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResetPaletteColor(pub u8);
+
+impl Command for ResetPaletteColor {
+    fn write_ansi(&self, f: &mut impl fmt::Write) -> fmt::Result {
+        write!(f, osc!("104;{}"), self.0)
+    }
+
+    #[cfg(windows)]
+    fn execute_winapi(&self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// A command that restores all entries of the terminal's 256-color palette to their defaults, see
+/// [`SetPaletteColor`].
+///
+/// # Notes
+///
+/// Commands must be executed/queued for execution otherwise they do nothing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResetPalette;
+
+impl Command for ResetPalette {
+    fn write_ansi(&self, f: &mut impl fmt::Write) -> fmt::Result {
+        f.write_str(osc!("104"))
+    }
+
+    #[cfg(windows)]
+    fn execute_winapi(&self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl_display!(for SetPaletteColor);
+impl_display!(for ResetPaletteColor);
+impl_display!(for ResetPalette);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_palette_color() {
+        let mut buf = String::new();
+        SetPaletteColor {
+            index: 9,
+            r: 255,
+            g: 10,
+            b: 0,
+        }
+        .write_ansi(&mut buf)
+        .unwrap();
+        assert_eq!(buf, "\x1B]4;9;rgb:ff/0a/00\x1B\\");
+    }
+
+    #[test]
+    fn reset_palette_color() {
+        let mut buf = String::new();
+        ResetPaletteColor(200).write_ansi(&mut buf).unwrap();
+        assert_eq!(buf, "\x1B]104;200\x1B\\");
+    }
+
+    #[test]
+    fn reset_palette() {
+        let mut buf = String::new();
+        ResetPalette.write_ansi(&mut buf).unwrap();
+        assert_eq!(buf, "\x1B]104\x1B\\");
+    }
+}
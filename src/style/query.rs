@@ -0,0 +1,191 @@
+#[cfg(feature = "events")]
+use std::{io, time::Duration};
+
+#[cfg(feature = "events")]
+use crate::{
+    event::{
+        filter::{BackgroundColorFilter, ForegroundColorFilter, PaletteColorFilter},
+        internal::InternalEvent,
+    },
+    terminal::query,
+};
+
+use crate::style::Color;
+
+/// Whether a terminal uses light or dark colors, see [`query_color_scheme`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ColorScheme {
+    /// Dark text on a light background.
+    Light,
+    /// Light text on a dark background.
+    Dark,
+}
+
+impl ColorScheme {
+    /// Classifies a background color by its perceived brightness.
+    ///
+    /// Returns `None` for colors which aren't [`Color::Rgb`], as their actual value depends on the
+    /// terminal.
+    pub fn from_background(color: Color) -> Option<ColorScheme> {
+        let Color::Rgb { r, g, b } = color else {
+            return None;
+        };
+
+        // The luma of the color, using the Rec. 709 coefficients.
+        let luma = 2126 * u32::from(r) + 7152 * u32::from(g) + 722 * u32::from(b);
+        Some(if luma > 10_000 * 255 / 2 {
+            ColorScheme::Light
+        } else {
+            ColorScheme::Dark
+        })
+    }
+}
+
+/// Queries the default foreground color of the terminal.
+///
+/// Returns `Ok(None)` if the terminal doesn't support the query.
+///
+/// # Notes
+///
+/// * Raw mode is enabled while the terminal is queried, if it isn't enabled already.
+/// * Querying the terminal is only supported on UNIX systems, on Windows an error is returned.
+/// * On UNIX systems, this function will block and possibly time out while
+///   [`crossterm::event::read`](crate::event::read) or
+///   [`crossterm::event::poll`](crate::event::poll) are being called.
+#[cfg(feature = "events")]
+pub fn query_foreground_color() -> io::Result<Option<Color>> {
+    // ESC ] 10 ; ? ESC \       Query the default foreground color.
+    match query::execute(
+        b"\x1B]10;?\x1B\\",
+        &ForegroundColorFilter,
+        Duration::from_millis(2000),
+        true,
+    )? {
+        Some(InternalEvent::ForegroundColor(color)) => Ok(Some(color)),
+        Some(_) => Ok(None),
+        None => Err(io::Error::other(
+            "The foreground color could not be read within a normal duration",
+        )),
+    }
+}
+
+/// Queries the default background color of the terminal.
+///
+/// Returns `Ok(None)` if the terminal doesn't support the query.
+///
+/// See [`query_foreground_color`] for more information.
+#[cfg(feature = "events")]
+pub fn query_background_color() -> io::Result<Option<Color>> {
+    // ESC ] 11 ; ? ESC \       Query the default background color.
+    match query::execute(
+        b"\x1B]11;?\x1B\\",
+        &BackgroundColorFilter,
+        Duration::from_millis(2000),
+        true,
+    )? {
+        Some(InternalEvent::BackgroundColor(color)) => Ok(Some(color)),
+        Some(_) => Ok(None),
+        None => Err(io::Error::other(
+            "The background color could not be read within a normal duration",
+        )),
+    }
+}
+
+/// Queries whether the terminal uses a light or dark color scheme, judging by its background
+/// color.
+///
+/// Returns `Ok(None)` if the terminal doesn't report its background color.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::io;
+/// use crossterm::style::{ColorScheme, query_color_scheme};
+///
+/// fn main() -> io::Result<()> {
+///     let scheme = query_color_scheme()?.unwrap_or(ColorScheme::Dark);
+///     println!("Using the {scheme:?} palette");
+///     Ok(())
+/// }
+/// ```
+///
+/// See [`query_foreground_color`] for more information.
+#[cfg(feature = "events")]
+pub fn query_color_scheme() -> io::Result<Option<ColorScheme>> {
+    Ok(query_background_color()?.and_then(ColorScheme::from_background))
+}
+
+/// Queries a color of the terminal's 256-color palette.
+///
+/// [`Color::AnsiValue`] uses the palette entry of its value, and the named colors use the first 16
+/// entries, see [`Color::palette_index`]:
+///
+/// | Index | Color                  | Index | Color               |
+/// | :---- | :--------------------- | :---- | :------------------ |
+/// | 0     | [`Color::Black`]       | 8     | [`Color::DarkGrey`] |
+/// | 1     | [`Color::DarkRed`]     | 9     | [`Color::Red`]      |
+/// | 2     | [`Color::DarkGreen`]   | 10    | [`Color::Green`]    |
+/// | 3     | [`Color::DarkYellow`]  | 11    | [`Color::Yellow`]   |
+/// | 4     | [`Color::DarkBlue`]    | 12    | [`Color::Blue`]     |
+/// | 5     | [`Color::DarkMagenta`] | 13    | [`Color::Magenta`]  |
+/// | 6     | [`Color::DarkCyan`]    | 14    | [`Color::Cyan`]     |
+/// | 7     | [`Color::Grey`]        | 15    | [`Color::White`]    |
+///
+/// Returns `Ok(None)` if the terminal doesn't support the query. The palette can be changed with
+/// [`SetPaletteColor`](crate::style::SetPaletteColor).
+///
+/// See [`query_foreground_color`] for more information.
+#[cfg(feature = "events")]
+pub fn query_palette_color(index: u8) -> io::Result<Option<Color>> {
+    // ESC ] 4 ; index ; ? ESC \       Query a palette color.
+    match query::execute(
+        format!("\x1B]4;{index};?\x1B\\").as_bytes(),
+        &PaletteColorFilter(index),
+        Duration::from_millis(2000),
+        true,
+    )? {
+        Some(InternalEvent::PaletteColor(_, color)) => Ok(Some(color)),
+        Some(_) => Ok(None),
+        None => Err(io::Error::other(
+            "The palette color could not be read within a normal duration",
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ColorScheme;
+    use crate::style::Color;
+
+    #[test]
+    fn test_color_scheme_from_background() {
+        let rgb = |r, g, b| Color::Rgb { r, g, b };
+
+        assert_eq!(
+            ColorScheme::from_background(rgb(255, 255, 255)),
+            Some(ColorScheme::Light)
+        );
+        assert_eq!(
+            ColorScheme::from_background(rgb(253, 246, 227)),
+            Some(ColorScheme::Light)
+        );
+        assert_eq!(
+            ColorScheme::from_background(rgb(0, 0, 0)),
+            Some(ColorScheme::Dark)
+        );
+        assert_eq!(
+            ColorScheme::from_background(rgb(0, 43, 54)),
+            Some(ColorScheme::Dark)
+        );
+        // Pure blue is dark, pure green is light.
+        assert_eq!(
+            ColorScheme::from_background(rgb(0, 0, 255)),
+            Some(ColorScheme::Dark)
+        );
+        assert_eq!(
+            ColorScheme::from_background(rgb(0, 255, 0)),
+            Some(ColorScheme::Light)
+        );
+        assert_eq!(ColorScheme::from_background(Color::White), None);
+    }
+}
@@ -94,6 +94,26 @@ pub enum Color {
     AnsiValue(u8),
 }
 
+/// The named colors, in the order of their palette indexes.
+const NAMED_COLORS: [Color; 16] = [
+    Color::Black,       // 0
+    Color::DarkRed,     // 1
+    Color::DarkGreen,   // 2
+    Color::DarkYellow,  // 3
+    Color::DarkBlue,    // 4
+    Color::DarkMagenta, // 5
+    Color::DarkCyan,    // 6
+    Color::Grey,        // 7
+    Color::DarkGrey,    // 8
+    Color::Red,         // 9
+    Color::Green,       // 10
+    Color::Yellow,      // 11
+    Color::Blue,        // 12
+    Color::Magenta,     // 13
+    Color::Cyan,        // 14
+    Color::White,       // 15
+];
+
 impl Color {
     /// Parses an ANSI color sequence.
     ///
@@ -115,6 +135,54 @@ impl Color {
         Self::parse_ansi_iter(&mut ansi.split(';'))
     }
 
+    /// Returns the index of the color in the terminal's 256-color palette.
+    ///
+    /// The named colors are the first 16 entries and [`Color::AnsiValue`] is its value. Returns
+    /// `None` for [`Color::Rgb`] and [`Color::Reset`], which aren't palette entries.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crossterm::style::Color;
+    ///
+    /// assert_eq!(Color::DarkRed.palette_index(), Some(1));
+    /// assert_eq!(Color::White.palette_index(), Some(15));
+    /// assert_eq!(Color::AnsiValue(26).palette_index(), Some(26));
+    /// assert_eq!(Color::Rgb { r: 0, g: 0, b: 0 }.palette_index(), None);
+    /// ```
+    pub fn palette_index(&self) -> Option<u8> {
+        match *self {
+            Color::AnsiValue(index) => Some(index),
+            color => NAMED_COLORS
+                .iter()
+                .position(|named| *named == color)
+                .map(|index| index as u8),
+        }
+    }
+
+    /// Parses an X11 color specification `rgb:R/G/B` with 1 to 4 hex digits per component, as
+    /// reported by terminals in response to color queries.
+    pub(crate) fn parse_x11_rgb(spec: &str) -> Option<Self> {
+        let mut components = spec.strip_prefix("rgb:")?.split('/').map(|component| {
+            if !(1..=4).contains(&component.len())
+                || !component.bytes().all(|byte| byte.is_ascii_hexdigit())
+            {
+                return None;
+            }
+            // Scale the component to 8 bits, e.g. `ffff` to 255 and `80` to 128.
+            let value = u32::from_str_radix(component, 16).ok()?;
+            let max = (1 << (4 * component.len())) - 1;
+            Some(((value * 255 + max / 2) / max) as u8)
+        });
+
+        let color = Color::Rgb {
+            r: components.next()??,
+            g: components.next()??,
+            b: components.next()??,
+        };
+        components.next().is_none().then_some(color)
+    }
+
     /// The logic for parse_ansi, takes an iterator of the sequences terms (the numbers between the
     /// ';'). It's a separate function so it can be used by both Color::parse_ansi and
     /// colored::parse_ansi.
@@ -125,28 +193,10 @@ impl Color {
             5 => {
                 let n = parse_next_u8(values)?;
 
-                use Color::*;
-                [
-                    Black,       // 0
-                    DarkRed,     // 1
-                    DarkGreen,   // 2
-                    DarkYellow,  // 3
-                    DarkBlue,    // 4
-                    DarkMagenta, // 5
-                    DarkCyan,    // 6
-                    Grey,        // 7
-                    DarkGrey,    // 8
-                    Red,         // 9
-                    Green,       // 10
-                    Yellow,      // 11
-                    Blue,        // 12
-                    Magenta,     // 13
-                    Cyan,        // 14
-                    White,       // 15
-                ]
-                .get(n as usize)
-                .copied()
-                .unwrap_or(Color::AnsiValue(n))
+                NAMED_COLORS
+                    .get(n as usize)
+                    .copied()
+                    .unwrap_or(Color::AnsiValue(n))
             }
 
             // 24 bit colors: `2;<r>;<g>;<b>`
@@ -330,6 +380,40 @@ impl<'de> serde::de::Deserialize<'de> for Color {
 mod tests {
     use super::Color;
 
+    #[test]
+    fn test_palette_index_round_trip() {
+        for index in 0..=255 {
+            let color = Color::parse_ansi(&format!("5;{index}")).unwrap();
+            assert_eq!(color.palette_index(), Some(index));
+        }
+        assert_eq!(Color::Reset.palette_index(), None);
+    }
+
+    #[test]
+    fn test_parse_x11_rgb() {
+        assert_eq!(
+            Color::parse_x11_rgb("rgb:ffff/8080/0000"),
+            Some(Color::Rgb {
+                r: 255,
+                g: 128,
+                b: 0
+            })
+        );
+        assert_eq!(
+            Color::parse_x11_rgb("rgb:f/80/abc"),
+            Some(Color::Rgb {
+                r: 255,
+                g: 128,
+                b: 171
+            })
+        );
+        assert_eq!(Color::parse_x11_rgb("rgb:ffff/ffff"), None);
+        assert_eq!(Color::parse_x11_rgb("rgb:ffff/ffff/ffff/ffff"), None);
+        assert_eq!(Color::parse_x11_rgb("rgb:fffff/ffff/ffff"), None);
+        assert_eq!(Color::parse_x11_rgb("rgb:+fff/ffff/ffff"), None);
+        assert_eq!(Color::parse_x11_rgb("#ffffff"), None);
+    }
+
     #[test]
     fn test_known_color_conversion() {
         assert_eq!("reset".parse(), Ok(Color::Reset));
@@ -59,6 +59,11 @@
 //! - New line character will not be processed therefore `println!` can't be used, use `write!` instead
 //!
 //! Raw mode can be enabled/disabled with the [enable_raw_mode](terminal::enable_raw_mode) and [disable_raw_mode](terminal::disable_raw_mode) functions.
+//! [enable_raw_mode_with](terminal::enable_raw_mode_with) enables a variant of it, e.g. one where
+//! CTRL+C still interrupts the program, see [RawModeOptions](terminal::RawModeOptions).
+//!
+//! A [TerminalSession](terminal::TerminalSession) enables the raw mode together with the alternate screen and other modes,
+//! and restores all of them when it's dropped or the program panics.
 //!
 //! ## Examples
 //!
@@ -96,10 +101,32 @@ use winapi::um::wincon::ENABLE_WRAP_AT_EOL_OUTPUT;
 use crate::Command;
 use crate::{csi, impl_display};
 
+mod capabilities;
+mod mode;
+#[cfg(feature = "events")]
+mod password;
+pub(crate) mod query;
+mod session;
 pub(crate) mod sys;
+#[cfg(unix)]
+pub(crate) mod tty;
 
+pub use capabilities::Capabilities;
+#[cfg(feature = "events")]
+pub use capabilities::capabilities;
+pub use mode::ModeState;
+#[cfg(feature = "events")]
+pub use mode::query_mode;
+#[cfg(feature = "events")]
+pub use password::read_password;
+pub use query::Response;
+#[cfg(feature = "events")]
+pub use query::{Query, ResponseMatcher};
+pub use session::{TerminalSession, TerminalSessionBuilder};
 #[cfg(feature = "events")]
 pub use sys::supports_keyboard_enhancement;
+#[cfg(unix)]
+pub use tty::Tty;
 
 /// Tells whether the raw mode is enabled.
 ///
@@ -123,6 +150,30 @@ pub fn enable_raw_mode() -> io::Result<()> {
     sys::enable_raw_mode()
 }
 
+/// Enables a variant of the raw mode chosen by the options.
+///
+/// On UNIX, nothing changes if the raw mode is enabled already, [disable](disable_raw_mode) it
+/// first to switch to other options.
+///
+/// Please have a look at the [raw mode](./index.html#raw-mode) section.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::io;
+/// use crossterm::terminal::{RawModeOptions, disable_raw_mode, enable_raw_mode_with};
+///
+/// fn main() -> io::Result<()> {
+///     // Read key presses without echo, CTRL+C still interrupts the program.
+///     enable_raw_mode_with(RawModeOptions::new().cbreak())?;
+///     // ...
+///     disable_raw_mode()
+/// }
+/// ```
+pub fn enable_raw_mode_with(options: RawModeOptions) -> io::Result<()> {
+    sys::enable_raw_mode_with(options)
+}
+
 /// Disables raw mode.
 ///
 /// Please have a look at the [raw mode](./index.html#raw-mode) section.
@@ -130,6 +181,86 @@ pub fn disable_raw_mode() -> io::Result<()> {
     sys::disable_raw_mode()
 }
 
+/// Enables the echo of the input, which is how the terminal starts.
+///
+/// The raw mode disables the echo as well, and [disable_raw_mode] restores it as it was before.
+/// On Windows, the echo requires the line buffered input of the normal mode.
+pub fn enable_echo() -> io::Result<()> {
+    sys::set_echo(true)
+}
+
+/// Disables the echo of the input, e.g. to read a password without the raw mode.
+///
+/// The input is still line buffered and edited by the terminal, see [read_password] to read a
+/// password with the input edited by crossterm.
+pub fn disable_echo() -> io::Result<()> {
+    sys::set_echo(false)
+}
+
+/// Suspends the program like Ctrl+Z does in a shell, returning once it's continued (e.g. with
+/// `fg`).
+///
+/// In the raw mode, Ctrl+Z is read as a key event instead of stopping the program. The raw mode
+/// is disabled while the program is stopped and enabled again afterwards. Other modes like the
+/// alternate screen stay enabled, use [`TerminalSession::suspend`] to disable them as well.
+///
+/// If events were read before, an [`Event::Resumed`](crate::event::Event::Resumed) is read after
+/// the program was continued.
+#[cfg(unix)]
+pub fn suspend() -> io::Result<()> {
+    sys::suspend()
+}
+
+/// Options for [enable_raw_mode_with] to keep some of the terminal's processing.
+///
+/// The default options are the raw mode enabled by [enable_raw_mode].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[must_use]
+pub struct RawModeOptions {
+    cbreak: bool,
+    keep_signals: bool,
+    keep_output_processing: bool,
+    keep_echo: bool,
+}
+
+impl RawModeOptions {
+    /// Creates the options of the raw mode enabled by [enable_raw_mode].
+    pub fn new() -> RawModeOptions {
+        RawModeOptions::default()
+    }
+
+    /// Only disables the line buffering and the echo of the input, like the cbreak mode of
+    /// curses.
+    ///
+    /// Signal keys like CTRL+C and the output processing keep working. Like in the raw mode, the
+    /// carriage return sent by Enter isn't translated to a newline, which would be read as CTRL+J.
+    pub fn cbreak(mut self) -> RawModeOptions {
+        self.cbreak = true;
+        self
+    }
+
+    /// Keeps the signal keys, e.g. CTRL+C interrupts and CTRL+Z suspends the program instead of
+    /// being read as key events (`ISIG` on UNIX, `ENABLE_PROCESSED_INPUT` on Windows).
+    pub fn keep_signals(mut self) -> RawModeOptions {
+        self.keep_signals = true;
+        self
+    }
+
+    /// Keeps the output processing, e.g. `\n` moves the cursor to the start of the next line so
+    /// `println!` can be used (`OPOST` on UNIX). The output isn't changed on Windows.
+    pub fn keep_output_processing(mut self) -> RawModeOptions {
+        self.keep_output_processing = true;
+        self
+    }
+
+    /// Keeps the echo of the input (`ECHO` on UNIX). Windows only echoes line buffered input, so
+    /// this has no effect there.
+    pub fn keep_echo(mut self) -> RawModeOptions {
+        self.keep_echo = true;
+        self
+    }
+}
+
 /// Returns the terminal size `(columns, rows)`.
 ///
 /// The top left cell is represented `(1, 1)`.
@@ -145,6 +276,16 @@ pub struct WindowSize {
     pub height: u16,
 }
 
+impl WindowSize {
+    /// Returns the cell size `(width, height)` in pixels, or `(0, 0)` if it's unknown.
+    pub(crate) fn cell_size(&self) -> (u16, u16) {
+        self.width
+            .checked_div(self.columns)
+            .zip(self.height.checked_div(self.rows))
+            .unwrap_or((0, 0))
+    }
+}
+
 /// Returns the terminal size `[WindowSize]`.
 ///
 /// The width and height in pixels may not be reliably implemented or default to 0.
@@ -0,0 +1,309 @@
+#[cfg(feature = "events")]
+use std::{env, io, time::Duration};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::event::KeyboardEnhancementFlags;
+#[cfg(feature = "events")]
+use crate::{
+    event::{filter::CapabilitiesFilter, internal::InternalEvent},
+    style::available_color_count,
+    terminal::{Response, query},
+};
+
+/// The capabilities of a terminal, see [`capabilities`].
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct Capabilities {
+    /// The name and version of the terminal reported by XTVERSION, e.g. `kitty(0.40.0)`.
+    pub version: Option<String>,
+    /// The primary device attributes (DA1): the conformance level followed by the supported
+    /// extensions.
+    ///
+    /// See <https://vt100.net/docs/vt510-rm/DA1.html>
+    pub device_attributes: Vec<u16>,
+    /// The secondary device attributes (DA2): the terminal type, the firmware version and the ROM
+    /// cartridge registration number.
+    ///
+    /// See <https://vt100.net/docs/vt510-rm/DA2.html>
+    pub secondary_device_attributes: Vec<u16>,
+    /// The number of supported colors, `u16::MAX` if 24-bit colors are supported.
+    pub colors: u16,
+    /// Whether 24-bit colors ([`Color::Rgb`](crate::style::Color::Rgb)) are supported.
+    pub truecolor: bool,
+    /// Whether sixel graphics are supported.
+    pub sixel: bool,
+    /// Whether the clipboard can be accessed with OSC 52.
+    pub clipboard: bool,
+    /// Whether hyperlinks ([`StartHyperlink`](crate::style::StartHyperlink)) are supported.
+    ///
+    /// There is no query for this, so it's derived from the terminal's name and environment
+    /// variables, and may be wrong.
+    pub hyperlinks: bool,
+    /// Whether synchronized output (mode 2026, [`BeginSynchronizedUpdate`](super::BeginSynchronizedUpdate))
+    /// is supported.
+    pub synchronized_output: bool,
+    /// Whether grapheme cluster segmentation (mode 2027) is supported.
+    pub grapheme_clusters: bool,
+    /// Whether bracketed paste (mode 2004) is supported.
+    pub bracketed_paste: bool,
+    /// Whether focus events (mode 1004) are supported.
+    pub focus_events: bool,
+    /// The currently enabled keyboard enhancement flags, or `None` if the kitty keyboard protocol
+    /// isn't supported.
+    pub keyboard_enhancement: Option<KeyboardEnhancementFlags>,
+}
+
+#[cfg(feature = "events")]
+impl Capabilities {
+    /// Collects the capabilities from the responses to [`REQUESTS`].
+    fn from_responses(events: &[InternalEvent]) -> Capabilities {
+        let mut capabilities = Capabilities::default();
+
+        for event in events {
+            match event {
+                InternalEvent::KeyboardEnhancementFlags(flags) => {
+                    capabilities.keyboard_enhancement = Some(*flags);
+                }
+                InternalEvent::Response(Response::Csi {
+                    parameters,
+                    final_byte: b'c',
+                }) => match parameters.split_first() {
+                    Some((b'?', parameters)) => {
+                        capabilities.device_attributes = parse_numbers(parameters);
+                    }
+                    Some((b'>', parameters)) => {
+                        capabilities.secondary_device_attributes = parse_numbers(parameters);
+                    }
+                    _ => {}
+                },
+                InternalEvent::ModeReport(mode, state) => {
+                    let supported = state.is_supported();
+                    match mode {
+                        2026 => capabilities.synchronized_output = supported,
+                        2027 => capabilities.grapheme_clusters = supported,
+                        2004 => capabilities.bracketed_paste = supported,
+                        1004 => capabilities.focus_events = supported,
+                        _ => {}
+                    }
+                }
+                InternalEvent::Response(Response::Dcs(payload)) => {
+                    if let Some(version) = payload.strip_prefix(b">|") {
+                        capabilities.version = Some(String::from_utf8_lossy(version).into_owned());
+                    } else if let Some(sgr) = payload.strip_prefix(b"1$r") {
+                        // The terminal reports the background color set before the request in
+                        // either notation, e.g. `48:2::1:2:3m` or `48;2;1;2;3m`.
+                        let sgr = String::from_utf8_lossy(sgr).replace(':', ";");
+                        capabilities.truecolor =
+                            sgr.contains("48;2;1;2;3") || sgr.contains("48;2;;1;2;3");
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        // The first attribute is the conformance level.
+        let extensions = capabilities.device_attributes.get(1..).unwrap_or_default();
+        capabilities.sixel = extensions.contains(&4);
+        capabilities.clipboard = extensions.contains(&52);
+        capabilities.hyperlinks = capabilities.version.as_deref().is_some_and(|version| {
+            HYPERLINK_TERMINALS
+                .iter()
+                .any(|name| version.starts_with(name))
+        });
+
+        capabilities
+    }
+}
+
+/// The requests of the capability probe, the primary device attributes are requested last by
+/// [`query::execute_batch`].
+#[cfg(feature = "events")]
+const REQUESTS: &[u8] = concat!(
+    // ESC [ > 0 q          XTVERSION, terminal name and version.
+    "\x1B[>0q",
+    // ESC [ > c            Secondary device attributes.
+    "\x1B[>c",
+    // ESC [ ? mode $ p     DECRQM, request the state of a DEC private mode.
+    "\x1B[?2026$p",
+    "\x1B[?2027$p",
+    "\x1B[?2004$p",
+    "\x1B[?1004$p",
+    // ESC [ ? u            Query progressive keyboard enhancement flags (kitty protocol).
+    "\x1B[?u",
+    // ESC [ # {            XTPUSHSGR, save the text attributes.
+    "\x1B[#{",
+    // Set a 24-bit background color and read it back with DECRQSS (ESC P $ q m ESC \), terminals
+    // without 24-bit colors report a different or no color.
+    "\x1B[48;2;1;2;3m",
+    "\x1BP$qm\x1B\\",
+    // Reset the background for terminals without XTPUSHSGR, then restore the saved attributes
+    // with XTPOPSGR (ESC [ # }).
+    "\x1B[49m",
+    "\x1B[#}",
+)
+.as_bytes();
+
+/// Prefixes of the XTVERSION reports of terminals supporting hyperlinks.
+#[cfg(feature = "events")]
+const HYPERLINK_TERMINALS: &[&str] = &[
+    "kitty", "WezTerm", "foot", "iTerm2", "ghostty", "contour", "xterm.js",
+];
+
+#[cfg(feature = "events")]
+fn parse_numbers(parameters: &[u8]) -> Vec<u16> {
+    String::from_utf8_lossy(parameters)
+        .split(';')
+        .filter_map(|number| number.parse().ok())
+        .collect()
+}
+
+/// Returns whether the environment variables name a terminal supporting hyperlinks, for terminals
+/// not reporting their name.
+#[cfg(feature = "events")]
+fn hyperlinks_from_env() -> bool {
+    env::var("VTE_VERSION")
+        .ok()
+        .and_then(|version| version.parse::<u32>().ok())
+        .is_some_and(|version| version >= 5000)
+        || env::var_os("WT_SESSION").is_some()
+}
+
+/// Probes the capabilities of the terminal.
+///
+/// All queries are sent at once, followed by the primary device attributes request, which all
+/// terminals answer. The terminal answers in order, so the probe is done after one round trip and
+/// queries the terminal doesn't support are simply left unanswered.
+///
+/// The probe consists of the primary and secondary device attributes (DA1, DA2), the terminal
+/// version (XTVERSION), the states of the synchronized output, grapheme cluster, bracketed paste
+/// and focus event modes (DECRQM), the keyboard enhancement flags and a 24-bit color test
+/// (DECRQSS). The color count and hyperlink support are completed from environment variables.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::io;
+/// use crossterm::terminal::capabilities;
+///
+/// fn main() -> io::Result<()> {
+///     let capabilities = capabilities()?;
+///     println!("{capabilities:?}");
+///
+///     if capabilities.synchronized_output {
+///         // Use BeginSynchronizedUpdate to avoid tearing.
+///     }
+///     Ok(())
+/// }
+/// ```
+///
+/// # Notes
+///
+/// * Raw mode is enabled while the terminal is probed, if it isn't enabled already.
+/// * The text attributes are saved and restored around the 24-bit color test (XTPUSHSGR and
+///   XTPOPSGR). Terminals without these sequences are left with the default background color.
+/// * An error of the kind [`io::ErrorKind::TimedOut`] is returned if the terminal doesn't answer
+///   within 2 seconds.
+/// * Querying the terminal is only supported on UNIX systems, on Windows an error is returned.
+/// * On UNIX systems, this function will block and possibly time out while
+///   [`crossterm::event::read`](crate::event::read) or
+///   [`crossterm::event::poll`](crate::event::poll) are being called.
+#[cfg(feature = "events")]
+pub fn capabilities() -> io::Result<Capabilities> {
+    let events = query::execute_batch(REQUESTS, &CapabilitiesFilter, Duration::from_millis(2000))?
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::TimedOut,
+                "The terminal capabilities could not be read within a normal duration",
+            )
+        })?;
+
+    let mut capabilities = Capabilities::from_responses(&events);
+    capabilities.colors = available_color_count();
+    if capabilities.truecolor {
+        capabilities.colors = u16::MAX;
+    } else {
+        capabilities.truecolor = capabilities.colors == u16::MAX;
+    }
+    capabilities.hyperlinks |= hyperlinks_from_env();
+    Ok(capabilities)
+}
+
+#[cfg(test)]
+#[cfg(feature = "events")]
+mod tests {
+    use super::Capabilities;
+    use crate::event::{
+        KeyboardEnhancementFlags,
+        internal::InternalEvent,
+        parse::{ParseOptions, parse_event},
+    };
+
+    fn parse(input: &[u8]) -> Vec<InternalEvent> {
+        let options = ParseOptions {
+            awaiting_response: true,
+            ..ParseOptions::default()
+        };
+        let mut events = Vec::new();
+        let mut buffer = Vec::new();
+        for byte in input {
+            buffer.push(*byte);
+            if let Some(event) = parse_event(&buffer, true, &options).unwrap() {
+                events.push(event);
+                buffer.clear();
+            }
+        }
+        events
+    }
+
+    #[test]
+    fn test_capabilities_from_responses() {
+        let events = parse(
+            concat!(
+                "\x1BP>|kitty(0.40.0)\x1B\\",
+                "\x1B[>1;4000;29c",
+                "\x1B[?2026;2$y",
+                "\x1B[?2027;0$y",
+                "\x1B[?2004;2$y",
+                "\x1B[?1004;1$y",
+                "\x1B[?1u",
+                "\x1BP1$r0;48:2::1:2:3m\x1B\\",
+                "\x1B[?62;4;22;52c",
+            )
+            .as_bytes(),
+        );
+
+        assert_eq!(
+            Capabilities::from_responses(&events),
+            Capabilities {
+                version: Some("kitty(0.40.0)".to_string()),
+                device_attributes: vec![62, 4, 22, 52],
+                secondary_device_attributes: vec![1, 4000, 29],
+                colors: 0,
+                truecolor: true,
+                sixel: true,
+                clipboard: true,
+                hyperlinks: true,
+                synchronized_output: true,
+                grapheme_clusters: false,
+                bracketed_paste: true,
+                focus_events: true,
+                keyboard_enhancement: Some(KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES),
+            }
+        );
+    }
+
+    #[test]
+    fn test_capabilities_from_primary_device_attributes_only() {
+        let events = parse(b"\x1BP0$r\x1B\\\x1B[?1;2c");
+
+        assert_eq!(
+            Capabilities::from_responses(&events),
+            Capabilities {
+                device_attributes: vec![1, 2],
+                ..Capabilities::default()
+            }
+        );
+    }
+}
@@ -0,0 +1,82 @@
+#[cfg(feature = "events")]
+use std::{io, time::Duration};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "events")]
+use crate::{
+    event::{filter::ModeReportFilter, internal::InternalEvent},
+    terminal::query,
+};
+
+/// The state of a DEC private mode reported by the terminal, see [`query_mode`].
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, PartialOrd, Ord, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum ModeState {
+    /// The terminal doesn't know the mode.
+    NotRecognized,
+    /// The mode is enabled.
+    Set,
+    /// The mode is disabled.
+    Reset,
+    /// The mode is always enabled and can't be disabled.
+    PermanentlySet,
+    /// The mode is always disabled and can't be enabled.
+    PermanentlyReset,
+}
+
+impl ModeState {
+    /// Returns whether the terminal supports the mode, that is, it's either changeable or always
+    /// enabled.
+    pub fn is_supported(self) -> bool {
+        matches!(
+            self,
+            ModeState::Set | ModeState::Reset | ModeState::PermanentlySet
+        )
+    }
+}
+
+/// Queries the state of a DEC private mode, e.g. `2026` for synchronized output
+/// ([`BeginSynchronizedUpdate`](super::BeginSynchronizedUpdate)) or `1049` for the alternate
+/// screen ([`EnterAlternateScreen`](super::EnterAlternateScreen)).
+///
+/// Returns [`ModeState::NotRecognized`] if the terminal doesn't support the query either.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::io;
+/// use crossterm::terminal::query_mode;
+///
+/// fn main() -> io::Result<()> {
+///     if query_mode(2026)?.is_supported() {
+///         println!("Synchronized output is supported");
+///     }
+///     Ok(())
+/// }
+/// ```
+///
+/// # Notes
+///
+/// * Raw mode is enabled while the terminal is queried, if it isn't enabled already.
+/// * Querying the terminal is only supported on UNIX systems, on Windows an error is returned.
+/// * On UNIX systems, this function will block and possibly time out while
+///   [`crossterm::event::read`](crate::event::read) or
+///   [`crossterm::event::poll`](crate::event::poll) are being called.
+#[cfg(feature = "events")]
+pub fn query_mode(mode: u16) -> io::Result<ModeState> {
+    // ESC [ ? mode $ p     DECRQM, request the state of a DEC private mode.
+    match query::execute(
+        format!("\x1B[?{mode}$p").as_bytes(),
+        &ModeReportFilter(mode),
+        Duration::from_millis(2000),
+        true,
+    )? {
+        Some(InternalEvent::ModeReport(_, state)) => Ok(state),
+        Some(_) => Ok(ModeState::NotRecognized),
+        None => Err(io::Error::other(
+            "The mode could not be read within a normal duration",
+        )),
+    }
+}
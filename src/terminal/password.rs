@@ -0,0 +1,241 @@
+use std::io::{self, Write};
+
+use crate::{
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    terminal::{disable_raw_mode, enable_raw_mode, is_raw_mode_enabled},
+};
+
+/// Reads a line without echoing it, e.g. a password.
+///
+/// The line is read from the [events](crate::event::read), with the raw mode enabled while reading
+/// unless it's enabled already, e.g. in the cbreak mode. If a `mask` is given, it's printed for
+/// every character typed. Backspace or CTRL+H deletes the last character, CTRL+U the whole line
+/// and Enter ends it. CTRL+C aborts with an [`Interrupted`](io::ErrorKind::Interrupted) error.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::io::{self, Write};
+/// use crossterm::terminal::read_password;
+///
+/// fn main() -> io::Result<()> {
+///     print!("Password: ");
+///     io::stdout().flush()?;
+///     let password = read_password(Some('*'))?;
+///     println!("The password has {} characters", password.chars().count());
+///     Ok(())
+/// }
+/// ```
+pub fn read_password(mask: Option<char>) -> io::Result<String> {
+    if is_raw_mode_enabled()? {
+        return read_line(mask);
+    }
+
+    let _raw_mode = RawModeGuard::enable()?;
+    read_line(mask)
+}
+
+/// Disables the raw mode when it's dropped, even if reading panics.
+struct RawModeGuard;
+
+impl RawModeGuard {
+    fn enable() -> io::Result<RawModeGuard> {
+        enable_raw_mode()?;
+        Ok(RawModeGuard)
+    }
+}
+
+impl Drop for RawModeGuard {
+    fn drop(&mut self) {
+        let _ = disable_raw_mode();
+    }
+}
+
+fn read_line(mask: Option<char>) -> io::Result<String> {
+    let mut line = PasswordLine::new(mask);
+    let mut stdout = io::stdout();
+    while !line.handle(&event::read()?, &mut stdout)? {}
+    Ok(line.text)
+}
+
+/// The line edited by [`read_password`].
+struct PasswordLine {
+    text: String,
+    mask: Option<char>,
+}
+
+impl PasswordLine {
+    fn new(mask: Option<char>) -> PasswordLine {
+        PasswordLine {
+            text: String::new(),
+            mask,
+        }
+    }
+
+    /// Edits the line and returns `true` once it's ended.
+    fn handle(&mut self, event: &Event, w: &mut impl Write) -> io::Result<bool> {
+        match event {
+            Event::Key(KeyEvent {
+                code,
+                modifiers,
+                kind: KeyEventKind::Press | KeyEventKind::Repeat,
+                ..
+            }) => match code {
+                KeyCode::Enter => {
+                    w.write_all(b"\r\n")?;
+                    w.flush()?;
+                    return Ok(true);
+                }
+                // Some terminals send CTRL+H for Backspace.
+                KeyCode::Backspace => self.pop(w)?,
+                KeyCode::Char('h') if modifiers.contains(KeyModifiers::CONTROL) => self.pop(w)?,
+                KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => {
+                    w.write_all(b"\r\n")?;
+                    w.flush()?;
+                    return Err(io::Error::new(
+                        io::ErrorKind::Interrupted,
+                        "reading the password was interrupted",
+                    ));
+                }
+                KeyCode::Char('u') if modifiers.contains(KeyModifiers::CONTROL) => {
+                    let len = self.text.chars().count();
+                    self.text.clear();
+                    self.erase(len, w)?;
+                }
+                KeyCode::Char(c)
+                    if !modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) =>
+                {
+                    self.insert(&c.to_string(), w)?;
+                }
+                _ => {}
+            },
+            #[cfg(feature = "bracketed-paste")]
+            Event::Paste(text) => self.insert(text, w)?,
+            _ => {}
+        }
+        Ok(false)
+    }
+
+    fn insert(&mut self, text: &str, w: &mut impl Write) -> io::Result<()> {
+        let text = text.replace(|c: char| c.is_control(), "");
+        self.text.push_str(&text);
+        if let Some(mask) = self.mask {
+            let masked: String = text.chars().map(|_| mask).collect();
+            w.write_all(masked.as_bytes())?;
+            w.flush()?;
+        }
+        Ok(())
+    }
+
+    fn pop(&mut self, w: &mut impl Write) -> io::Result<()> {
+        let len = usize::from(self.text.pop().is_some());
+        self.erase(len, w)
+    }
+
+    fn erase(&self, len: usize, w: &mut impl Write) -> io::Result<()> {
+        if self.mask.is_some() && len > 0 {
+            w.write_all(b"\x08 \x08".repeat(len).as_slice())?;
+            w.flush()?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use super::PasswordLine;
+    use crate::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+
+    fn key(code: KeyCode) -> Event {
+        Event::Key(code.into())
+    }
+
+    fn ctrl(c: char) -> Event {
+        Event::Key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL))
+    }
+
+    fn handle(line: &mut PasswordLine, events: &[Event]) -> (Vec<bool>, Vec<u8>) {
+        let mut output = Vec::new();
+        let ended = events
+            .iter()
+            .map(|event| line.handle(event, &mut output).unwrap())
+            .collect();
+        (ended, output)
+    }
+
+    #[test]
+    fn test_password_line_editing() {
+        let mut line = PasswordLine::new(None);
+        let (ended, output) = handle(
+            &mut line,
+            &[
+                key(KeyCode::Char('a')),
+                key(KeyCode::Char('b')),
+                key(KeyCode::Backspace),
+                key(KeyCode::Char('ä')),
+                Event::Key(KeyEvent::new_with_kind(
+                    KeyCode::Char('x'),
+                    KeyModifiers::NONE,
+                    KeyEventKind::Release,
+                )),
+                Event::Key(KeyEvent::new(KeyCode::Char('y'), KeyModifiers::ALT)),
+                key(KeyCode::Enter),
+            ],
+        );
+        assert_eq!(ended, [false, false, false, false, false, false, true]);
+        assert_eq!(line.text, "aä");
+        assert_eq!(output, b"\r\n");
+
+        let mut line = PasswordLine::new(None);
+        handle(
+            &mut line,
+            &[key(KeyCode::Char('a')), ctrl('u'), key(KeyCode::Char('b'))],
+        );
+        assert_eq!(line.text, "b");
+
+        let mut line = PasswordLine::new(None);
+        handle(
+            &mut line,
+            &[key(KeyCode::Char('a')), key(KeyCode::Char('b')), ctrl('h')],
+        );
+        assert_eq!(line.text, "a");
+    }
+
+    #[test]
+    fn test_password_line_mask() {
+        let mut line = PasswordLine::new(Some('*'));
+        let (_, output) = handle(
+            &mut line,
+            &[
+                key(KeyCode::Char('a')),
+                key(KeyCode::Char('b')),
+                key(KeyCode::Backspace),
+                ctrl('u'),
+                key(KeyCode::Backspace),
+                key(KeyCode::Char('c')),
+            ],
+        );
+        assert_eq!(line.text, "c");
+        assert_eq!(output, b"**\x08 \x08\x08 \x08*");
+    }
+
+    #[test]
+    fn test_password_line_interrupted() {
+        let mut line = PasswordLine::new(None);
+        let mut output = Vec::new();
+        let error = line.handle(&ctrl('c'), &mut output).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::Interrupted);
+        assert_eq!(output, b"\r\n");
+    }
+
+    #[cfg(feature = "bracketed-paste")]
+    #[test]
+    fn test_password_line_paste() {
+        let mut line = PasswordLine::new(Some('*'));
+        let (_, output) = handle(&mut line, &[Event::Paste("pä\nss".to_string())]);
+        assert_eq!(line.text, "päss");
+        assert_eq!(output, b"****");
+    }
+}
@@ -0,0 +1,509 @@
+//! Queries reporting information about the terminal as escape sequences.
+
+#[cfg(feature = "events")]
+use std::{io, time::Duration};
+
+#[cfg(all(unix, feature = "events"))]
+use crate::event::{
+    filter::PrimaryDeviceAttributesFilter, internal, parse::PendingQuery, timeout::PollTimeout,
+};
+#[cfg(feature = "events")]
+use crate::event::{
+    filter::{Filter, ResponseFilter},
+    internal::InternalEvent,
+};
+
+/// A response of the terminal to a [`Query`].
+#[derive(Debug, PartialOrd, Ord, PartialEq, Eq, Clone, Hash)]
+pub enum Response {
+    /// A control sequence, `ESC [ parameters final_byte`.
+    ///
+    /// The parameters contain the raw parameter and intermediate bytes, including private markers
+    /// like `?` or `>`.
+    Csi {
+        /// The bytes between `ESC [` and the final byte.
+        parameters: Vec<u8>,
+        /// The final byte of the sequence, in the range `0x40..=0x7E`.
+        final_byte: u8,
+    },
+    /// The payload of an operating system command, `ESC ] payload ST`.
+    Osc(Vec<u8>),
+    /// The payload of a device control string, `ESC P payload ST`.
+    Dcs(Vec<u8>),
+}
+
+impl Response {
+    /// Returns the bytes of the sequence, control strings are terminated with `ESC \\`.
+    pub(crate) fn to_bytes(&self) -> Vec<u8> {
+        let (introducer, payload, terminator): (&[u8], &[u8], &[u8]) = match self {
+            Response::Csi {
+                parameters,
+                final_byte,
+            } => (b"\x1B[", parameters, std::slice::from_ref(final_byte)),
+            Response::Osc(payload) => (b"\x1B]", payload, b"\x1B\\"),
+            Response::Dcs(payload) => (b"\x1BP", payload, b"\x1B\\"),
+        };
+        [introducer, payload, terminator].concat()
+    }
+}
+
+/// Describes which [`Response`] answers a [`Query`].
+#[cfg(feature = "events")]
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub enum ResponseMatcher {
+    /// Matches a control sequence with the given final byte whose parameters start with `prefix`,
+    /// e.g. `prefix: b"?".to_vec(), final_byte: b'y'` for a DEC private mode report.
+    Csi {
+        /// The bytes the parameters have to start with.
+        prefix: Vec<u8>,
+        /// The final byte of the sequence.
+        final_byte: u8,
+    },
+    /// Matches an operating system command whose payload starts with `prefix`, e.g. `b"11;"` for
+    /// the background color.
+    Osc {
+        /// The bytes the payload has to start with.
+        prefix: Vec<u8>,
+    },
+    /// Matches a device control string whose payload starts with `prefix`, e.g. `b">|"` for the
+    /// terminal version.
+    Dcs {
+        /// The bytes the payload has to start with.
+        prefix: Vec<u8>,
+    },
+}
+
+#[cfg(feature = "events")]
+impl ResponseMatcher {
+    /// Returns whether the response is matched.
+    pub fn matches(&self, response: &Response) -> bool {
+        match (self, response) {
+            (
+                ResponseMatcher::Csi { prefix, final_byte },
+                Response::Csi {
+                    parameters,
+                    final_byte: response_final_byte,
+                },
+            ) => final_byte == response_final_byte && parameters.starts_with(prefix),
+            (ResponseMatcher::Osc { prefix }, Response::Osc(payload))
+            | (ResponseMatcher::Dcs { prefix }, Response::Dcs(payload)) => {
+                payload.starts_with(prefix)
+            }
+            _ => false,
+        }
+    }
+}
+
+/// A query sent to the terminal, which answers with an escape sequence.
+///
+/// The response is read from the terminal input like events are, so queries can be used while
+/// events are read. Any query can be issued by describing the expected response with a
+/// [`ResponseMatcher`]. Responses crossterm decodes itself, like the mode states reported to
+/// [`query_mode`](crate::terminal::query_mode) or the colors reported to
+/// [`query_background_color`](crate::style::query_background_color), are re-encoded for the
+/// matcher, with colors as `rgb:RRRR/GGGG/BBBB`. Clipboard contents are read with
+/// `read_clipboard` instead.
+///
+/// Terminals ignore queries they don't support, so the query times out after 2 seconds by
+/// default. Enable the [sentinel](Query::sentinel) to detect unsupported queries quickly.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::{io, time::Duration};
+/// use crossterm::terminal::{Query, Response, ResponseMatcher};
+///
+/// fn main() -> io::Result<()> {
+///     // XTVERSION, answered with `ESC P > | name(version) ESC \`.
+///     let version = Query::new(b"\x1B[>0q", ResponseMatcher::Dcs { prefix: b">|".to_vec() })
+///         .timeout(Duration::from_millis(500))
+///         .sentinel(true)
+///         .execute()?;
+///
+///     match version {
+///         Some(Response::Dcs(payload)) => println!("{}", String::from_utf8_lossy(&payload[2..])),
+///         _ => println!("The terminal doesn't report its version"),
+///     }
+///     Ok(())
+/// }
+/// ```
+///
+/// # Notes
+///
+/// * Raw mode is enabled while the query is executed, if it isn't enabled already.
+/// * Queries are only supported on UNIX systems, on Windows [`execute`](Query::execute)
+///   returns an error.
+/// * On UNIX systems, executing a query will block and possibly time out while
+///   [`crossterm::event::read`](crate::event::read) or
+///   [`crossterm::event::poll`](crate::event::poll) are being called.
+#[cfg(feature = "events")]
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct Query {
+    request: Vec<u8>,
+    matcher: ResponseMatcher,
+    timeout: Duration,
+    sentinel: bool,
+}
+
+#[cfg(feature = "events")]
+impl Query {
+    /// Creates a query sending the request bytes and waiting for a response matched by the given
+    /// matcher.
+    pub fn new(request: impl Into<Vec<u8>>, matcher: ResponseMatcher) -> Query {
+        Query {
+            request: request.into(),
+            matcher,
+            timeout: Duration::from_millis(2000),
+            sentinel: false,
+        }
+    }
+
+    /// Sets how long to wait for the response.
+    pub fn timeout(mut self, timeout: Duration) -> Query {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Sets whether the primary device attributes (DA1) are requested after the query.
+    ///
+    /// All terminals answer this request and they answer in order, so receiving the device
+    /// attributes before the response means the query isn't supported. [`execute`](Query::execute)
+    /// then returns `Ok(None)` right away instead of waiting for the timeout.
+    pub fn sentinel(mut self, sentinel: bool) -> Query {
+        self.sentinel = sentinel;
+        self
+    }
+
+    /// Sends the query to the terminal and waits for the response.
+    ///
+    /// Returns `Ok(None)` if the [sentinel](Query::sentinel) shows the query isn't supported,
+    /// and an error of the kind [`io::ErrorKind::TimedOut`] if no response arrived in time.
+    pub fn execute(&self) -> io::Result<Option<Response>> {
+        let filter = ResponseFilter {
+            matcher: self.matcher.clone(),
+            sentinel: self.sentinel,
+        };
+        match execute(&self.request, &filter, self.timeout, self.sentinel)? {
+            Some(event) => Ok(event
+                .to_response()
+                .filter(|response| self.matcher.matches(response))),
+            None => Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                "The terminal did not respond to the query within the timeout",
+            )),
+        }
+    }
+}
+
+/// Sends the request to the terminal and reads the first event accepted by the filter, or `None`
+/// if the timeout elapsed.
+///
+/// If `sentinel` is set, the primary device attributes are requested after the request and the
+/// filter has to accept them as well. They are returned if the terminal didn't answer the request,
+/// otherwise they're discarded.
+#[cfg(all(unix, feature = "events"))]
+pub(crate) fn execute(
+    request: &[u8],
+    filter: &impl Filter,
+    timeout: Duration,
+    sentinel: bool,
+) -> io::Result<Option<InternalEvent>> {
+    execute_on(&DefaultTerminal, request, filter, timeout, sentinel)
+}
+
+/// Sends the request to the given terminal, see [`execute`].
+#[cfg(all(unix, feature = "events"))]
+pub(crate) fn execute_on(
+    terminal: &impl Terminal,
+    request: &[u8],
+    filter: &impl Filter,
+    timeout: Duration,
+    sentinel: bool,
+) -> io::Result<Option<InternalEvent>> {
+    with_raw_mode(terminal, || {
+        execute_raw(terminal, request, filter, timeout, sentinel)
+    })
+}
+
+/// Sends the requests to the terminal followed by a primary device attributes request, and reads
+/// all events accepted by the filter up to the primary device attributes, or `None` if the timeout
+/// elapsed first.
+///
+/// The filter has to accept the primary device attributes, they're the last returned event.
+#[cfg(all(unix, feature = "events"))]
+pub(crate) fn execute_batch(
+    requests: &[u8],
+    filter: &impl Filter,
+    timeout: Duration,
+) -> io::Result<Option<Vec<InternalEvent>>> {
+    with_raw_mode(&DefaultTerminal, || {
+        execute_batch_raw(&DefaultTerminal, requests, filter, timeout)
+    })
+}
+
+#[cfg(all(windows, feature = "events"))]
+pub(crate) fn execute(
+    _request: &[u8],
+    _filter: &impl Filter,
+    _timeout: Duration,
+    _sentinel: bool,
+) -> io::Result<Option<InternalEvent>> {
+    Err(unsupported())
+}
+
+#[cfg(all(windows, feature = "events"))]
+pub(crate) fn execute_batch(
+    _requests: &[u8],
+    _filter: &impl Filter,
+    _timeout: Duration,
+) -> io::Result<Option<Vec<InternalEvent>>> {
+    Err(unsupported())
+}
+
+#[cfg(all(windows, feature = "events"))]
+fn unsupported() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Unsupported,
+        "Terminal queries are not supported on Windows.",
+    )
+}
+
+/// A terminal queries are sent to, the one of the process or a [`Tty`](crate::terminal::Tty).
+#[cfg(all(unix, feature = "events"))]
+pub(crate) trait Terminal {
+    fn is_raw_mode_enabled(&self) -> bool;
+
+    fn enable_raw_mode(&self) -> io::Result<()>;
+
+    fn disable_raw_mode(&self) -> io::Result<()>;
+
+    /// Writes the request and flushes it.
+    fn write_request(&self, request: &[u8]) -> io::Result<()>;
+
+    /// Checks if an event accepted by the filter is available within the timeout.
+    fn poll(&self, timeout: Option<Duration>, filter: &impl Filter) -> io::Result<bool>;
+
+    /// Reads the next event accepted by the filter.
+    fn read(&self, filter: &impl Filter) -> io::Result<InternalEvent>;
+}
+
+/// The terminal of the process, which is read by the global event reader.
+#[cfg(all(unix, feature = "events"))]
+pub(crate) struct DefaultTerminal;
+
+#[cfg(all(unix, feature = "events"))]
+impl Terminal for DefaultTerminal {
+    fn is_raw_mode_enabled(&self) -> bool {
+        crate::terminal::sys::is_raw_mode_enabled()
+    }
+
+    fn enable_raw_mode(&self) -> io::Result<()> {
+        crate::terminal::sys::enable_raw_mode()
+    }
+
+    fn disable_raw_mode(&self) -> io::Result<()> {
+        crate::terminal::sys::disable_raw_mode()
+    }
+
+    fn write_request(&self, request: &[u8]) -> io::Result<()> {
+        write_to_terminal(request)
+    }
+
+    fn poll(&self, timeout: Option<Duration>, filter: &impl Filter) -> io::Result<bool> {
+        internal::poll(timeout, filter)
+    }
+
+    fn read(&self, filter: &impl Filter) -> io::Result<InternalEvent> {
+        internal::read(filter)
+    }
+}
+
+#[cfg(all(unix, feature = "events"))]
+fn with_raw_mode<T>(terminal: &impl Terminal, f: impl FnOnce() -> io::Result<T>) -> io::Result<T> {
+    if terminal.is_raw_mode_enabled() {
+        f()
+    } else {
+        terminal.enable_raw_mode()?;
+        let result = f();
+        terminal.disable_raw_mode()?;
+        result
+    }
+}
+
+#[cfg(all(unix, feature = "events"))]
+fn execute_raw(
+    terminal: &impl Terminal,
+    request: &[u8],
+    filter: &impl Filter,
+    timeout: Duration,
+    sentinel: bool,
+) -> io::Result<Option<InternalEvent>> {
+    discard_pending(terminal, filter);
+
+    // ESC [ c          Query primary device attributes.
+    let mut request = request.to_vec();
+    if sentinel {
+        request.extend_from_slice(b"\x1B[c");
+    }
+    let _pending = PendingQuery::new();
+    terminal.write_request(&request)?;
+
+    let timeout = PollTimeout::new(Some(timeout));
+    let Some(event) = read_event(terminal, filter, &timeout) else {
+        return Ok(None);
+    };
+
+    // Flush the primary device attributes which follow the response out of the event queue.
+    if sentinel && !event.is_primary_device_attributes() {
+        if let Ok(true) = terminal.poll(timeout.leftover(), &PrimaryDeviceAttributesFilter) {
+            let _ = terminal.read(&PrimaryDeviceAttributesFilter);
+        }
+    }
+
+    Ok(Some(event))
+}
+
+#[cfg(all(unix, feature = "events"))]
+fn execute_batch_raw(
+    terminal: &impl Terminal,
+    requests: &[u8],
+    filter: &impl Filter,
+    timeout: Duration,
+) -> io::Result<Option<Vec<InternalEvent>>> {
+    discard_pending(terminal, filter);
+
+    // ESC [ c          Query primary device attributes.
+    let mut requests = requests.to_vec();
+    requests.extend_from_slice(b"\x1B[c");
+    let _pending = PendingQuery::new();
+    terminal.write_request(&requests)?;
+
+    // The terminal answers in order, so the primary device attributes are the last response.
+    let timeout = PollTimeout::new(Some(timeout));
+    let mut events = Vec::new();
+    while let Some(event) = read_event(terminal, filter, &timeout) {
+        let last = event.is_primary_device_attributes();
+        events.push(event);
+        if last {
+            return Ok(Some(events));
+        }
+    }
+    Ok(None)
+}
+
+/// Discards any buffered responses to earlier requests, so the responses read afterwards
+/// correspond to the fresh request.
+#[cfg(all(unix, feature = "events"))]
+fn discard_pending(terminal: &impl Terminal, filter: &impl Filter) {
+    // Poll with a zero timeout to drain only already-available events without blocking.
+    while let Ok(true) = terminal.poll(Some(Duration::ZERO), filter) {
+        let _ = terminal.read(filter);
+    }
+}
+
+/// Reads the next event accepted by the filter, or `None` if the timeout elapsed.
+#[cfg(all(unix, feature = "events"))]
+fn read_event(
+    terminal: &impl Terminal,
+    filter: &impl Filter,
+    timeout: &PollTimeout,
+) -> Option<InternalEvent> {
+    loop {
+        match terminal.poll(timeout.leftover(), filter) {
+            Ok(true) => {
+                if let Ok(event) = terminal.read(filter) {
+                    return Some(event);
+                }
+            }
+            Ok(false) => return None,
+            Err(_) if timeout.elapsed() => return None,
+            Err(_) => {}
+        }
+    }
+}
+
+#[cfg(all(unix, feature = "events"))]
+fn write_to_terminal(bytes: &[u8]) -> io::Result<()> {
+    use std::{fs::File, io::Write};
+
+    let result = File::options()
+        .write(true)
+        .open("/dev/tty")
+        .and_then(|mut file| {
+            file.write_all(bytes)?;
+            file.flush()
+        });
+    if result.is_err() {
+        let mut stdout = io::stdout();
+        stdout.write_all(bytes)?;
+        stdout.flush()?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+#[cfg(feature = "events")]
+mod tests {
+    use super::{Response, ResponseMatcher};
+
+    #[test]
+    fn test_response_to_bytes() {
+        let csi = Response::Csi {
+            parameters: b"?997;1".to_vec(),
+            final_byte: b'n',
+        };
+        assert_eq!(csi.to_bytes(), b"\x1B[?997;1n");
+        assert_eq!(
+            Response::Osc(b"12;?".to_vec()).to_bytes(),
+            b"\x1B]12;?\x1B\\"
+        );
+        assert_eq!(
+            Response::Dcs(b">|foot".to_vec()).to_bytes(),
+            b"\x1BP>|foot\x1B\\"
+        );
+    }
+
+    #[test]
+    fn test_response_matcher() {
+        let mode_report = Response::Csi {
+            parameters: b"?2004;1$".to_vec(),
+            final_byte: b'y',
+        };
+        assert!(
+            ResponseMatcher::Csi {
+                prefix: b"?".to_vec(),
+                final_byte: b'y',
+            }
+            .matches(&mode_report)
+        );
+        assert!(
+            !ResponseMatcher::Csi {
+                prefix: b"?".to_vec(),
+                final_byte: b'c',
+            }
+            .matches(&mode_report)
+        );
+        assert!(
+            !ResponseMatcher::Csi {
+                prefix: b">".to_vec(),
+                final_byte: b'y',
+            }
+            .matches(&mode_report)
+        );
+
+        let version = Response::Dcs(b">|kitty(0.40.0)".to_vec());
+        assert!(
+            ResponseMatcher::Dcs {
+                prefix: b">|".to_vec()
+            }
+            .matches(&version)
+        );
+        assert!(
+            !ResponseMatcher::Osc {
+                prefix: b">|".to_vec()
+            }
+            .matches(&version)
+        );
+        assert!(ResponseMatcher::Osc { prefix: Vec::new() }.matches(&Response::Osc(Vec::new())));
+    }
+}
@@ -0,0 +1,457 @@
+use std::{
+    io, panic,
+    sync::{Arc, Once, Weak},
+};
+
+use parking_lot::Mutex;
+
+#[cfg(feature = "bracketed-paste")]
+use crate::event::{DisableBracketedPaste, EnableBracketedPaste};
+#[cfg(feature = "events")]
+use crate::event::{DisableMouseCapture, EnableMouseCapture};
+use crate::{
+    Command, ExecutableCommand,
+    cursor::{Hide, Show},
+    event::{
+        DisableFocusChange, EnableFocusChange, KeyboardEnhancementFlags,
+        PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags,
+    },
+    terminal::{
+        EnterAlternateScreen, LeaveAlternateScreen, RawModeOptions, disable_raw_mode,
+        enable_raw_mode_with, is_raw_mode_enabled,
+    },
+};
+
+fn execute(w: &mut impl io::Write, command: impl Command) -> io::Result<()> {
+    w.execute(command).map(|_| ())
+}
+
+/// The modes of the sessions restored by the panic hook, in the order the sessions were entered.
+static PANIC_HOOK_SESSIONS: Mutex<Vec<Weak<Mutex<Vec<Mode>>>>> =
+    parking_lot::const_mutex(Vec::new());
+static PANIC_HOOK: Once = Once::new();
+
+/// Installs the panic hook once per process and registers the modes of a session with it.
+fn register_panic_hook(modes: &Arc<Mutex<Vec<Mode>>>) {
+    PANIC_HOOK.call_once(|| {
+        let hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            restore_panic_hook_sessions();
+            hook(info);
+        }));
+    });
+
+    let mut sessions = PANIC_HOOK_SESSIONS.lock();
+    sessions.retain(|session| session.strong_count() > 0);
+    sessions.push(Arc::downgrade(modes));
+}
+
+/// Restores the live sessions registered with the panic hook, the latest one first.
+fn restore_panic_hook_sessions() {
+    // The locks are held if the panic happened while registering or restoring.
+    let Some(sessions) = PANIC_HOOK_SESSIONS.try_lock() else {
+        return;
+    };
+    for modes in sessions.iter().rev().filter_map(Weak::upgrade) {
+        if let Some(mut modes) = modes.try_lock() {
+            disable_all(std::mem::take(&mut *modes), &mut io::stdout()).ok();
+        }
+    }
+}
+
+/// A terminal mode enabled by a [`TerminalSession`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    Raw(RawModeOptions),
+    AlternateScreen,
+    HiddenCursor,
+    #[cfg(feature = "events")]
+    MouseCapture,
+    #[cfg(feature = "bracketed-paste")]
+    BracketedPaste,
+    FocusChange,
+    KeyboardEnhancement(KeyboardEnhancementFlags),
+}
+
+impl Mode {
+    fn enable(self, w: &mut impl io::Write) -> io::Result<()> {
+        match self {
+            Mode::Raw(options) => enable_raw_mode_with(options),
+            Mode::AlternateScreen => execute(w, EnterAlternateScreen),
+            Mode::HiddenCursor => execute(w, Hide),
+            #[cfg(feature = "events")]
+            Mode::MouseCapture => execute(w, EnableMouseCapture),
+            #[cfg(feature = "bracketed-paste")]
+            Mode::BracketedPaste => execute(w, EnableBracketedPaste),
+            Mode::FocusChange => execute(w, EnableFocusChange),
+            Mode::KeyboardEnhancement(flags) => execute(w, PushKeyboardEnhancementFlags(flags)),
+        }
+    }
+
+    fn disable(self, w: &mut impl io::Write) -> io::Result<()> {
+        match self {
+            Mode::Raw(_) => disable_raw_mode(),
+            Mode::AlternateScreen => execute(w, LeaveAlternateScreen),
+            Mode::HiddenCursor => execute(w, Show),
+            #[cfg(feature = "events")]
+            Mode::MouseCapture => execute(w, DisableMouseCapture),
+            #[cfg(feature = "bracketed-paste")]
+            Mode::BracketedPaste => execute(w, DisableBracketedPaste),
+            Mode::FocusChange => execute(w, DisableFocusChange),
+            Mode::KeyboardEnhancement(_) => execute(w, PopKeyboardEnhancementFlags),
+        }
+    }
+}
+
+/// Disables the modes in reverse order. All modes are disabled, even if one fails, and the first
+/// error is returned.
+fn disable_all(modes: Vec<Mode>, w: &mut impl io::Write) -> io::Result<()> {
+    modes
+        .into_iter()
+        .rev()
+        .map(|mode| mode.disable(w))
+        .fold(Ok(()), Result::and)
+}
+
+/// Configures the modes of a [`TerminalSession`], see [`TerminalSession::builder`].
+#[derive(Debug, Default, Clone)]
+#[must_use]
+pub struct TerminalSessionBuilder {
+    raw_mode: Option<RawModeOptions>,
+    alternate_screen: bool,
+    hide_cursor: bool,
+    #[cfg(feature = "events")]
+    mouse_capture: bool,
+    #[cfg(feature = "bracketed-paste")]
+    bracketed_paste: bool,
+    focus_change: bool,
+    keyboard_enhancement: Option<KeyboardEnhancementFlags>,
+    panic_hook: bool,
+}
+
+impl TerminalSessionBuilder {
+    /// Enables the [raw mode](super::enable_raw_mode), unless it's enabled already.
+    pub fn raw_mode(self) -> Self {
+        self.raw_mode_with(RawModeOptions::default())
+    }
+
+    /// Enables a variant of the raw mode, see [`enable_raw_mode_with`](super::enable_raw_mode_with).
+    pub fn raw_mode_with(mut self, options: RawModeOptions) -> Self {
+        self.raw_mode = Some(options);
+        self
+    }
+
+    /// Switches to the alternate screen, see [`EnterAlternateScreen`].
+    pub fn alternate_screen(mut self) -> Self {
+        self.alternate_screen = true;
+        self
+    }
+
+    /// Hides the cursor, see [`Hide`].
+    pub fn hide_cursor(mut self) -> Self {
+        self.hide_cursor = true;
+        self
+    }
+
+    /// Enables mouse events, see [`EnableMouseCapture`].
+    #[cfg(feature = "events")]
+    pub fn mouse_capture(mut self) -> Self {
+        self.mouse_capture = true;
+        self
+    }
+
+    /// Enables bracketed paste, see [`EnableBracketedPaste`].
+    #[cfg(feature = "bracketed-paste")]
+    pub fn bracketed_paste(mut self) -> Self {
+        self.bracketed_paste = true;
+        self
+    }
+
+    /// Enables focus events, see [`EnableFocusChange`].
+    pub fn focus_change(mut self) -> Self {
+        self.focus_change = true;
+        self
+    }
+
+    /// Pushes the keyboard enhancement flags, see [`PushKeyboardEnhancementFlags`].
+    pub fn keyboard_enhancement(mut self, flags: KeyboardEnhancementFlags) -> Self {
+        self.keyboard_enhancement = Some(flags);
+        self
+    }
+
+    /// Installs a panic hook which restores the terminal before the panic message is printed.
+    ///
+    /// The previous panic hook is called after the terminal was restored. A single hook is
+    /// installed per process, which restores the sessions that haven't ended yet.
+    pub fn panic_hook(mut self) -> Self {
+        self.panic_hook = true;
+        self
+    }
+
+    /// Returns the modes to enable, in order.
+    fn modes(&self) -> Vec<Mode> {
+        let mut modes = Vec::new();
+        if let Some(options) = self.raw_mode {
+            modes.push(Mode::Raw(options));
+        }
+        if self.alternate_screen {
+            modes.push(Mode::AlternateScreen);
+        }
+        if self.hide_cursor {
+            modes.push(Mode::HiddenCursor);
+        }
+        #[cfg(feature = "events")]
+        if self.mouse_capture {
+            modes.push(Mode::MouseCapture);
+        }
+        #[cfg(feature = "bracketed-paste")]
+        if self.bracketed_paste {
+            modes.push(Mode::BracketedPaste);
+        }
+        if self.focus_change {
+            modes.push(Mode::FocusChange);
+        }
+        if let Some(flags) = self.keyboard_enhancement {
+            modes.push(Mode::KeyboardEnhancement(flags));
+        }
+        modes
+    }
+
+    /// Enables the modes and returns the session, which restores them when it's dropped.
+    ///
+    /// If a mode can't be enabled, the modes enabled before are restored and the error is
+    /// returned.
+    pub fn enter(self) -> io::Result<TerminalSession> {
+        let session = TerminalSession {
+            modes: Arc::default(),
+        };
+        session.enable(&self.modes(), &mut io::stdout())?;
+
+        if self.panic_hook {
+            register_panic_hook(&session.modes);
+        }
+        Ok(session)
+    }
+}
+
+/// A guard which restores the terminal modes it enabled when it's dropped.
+///
+/// A session enables the raw mode, the alternate screen and the other modes chosen with its
+/// [builder](TerminalSession::builder), writing to the standard output. It records which modes it
+/// enabled and disables them in reverse order when it's dropped or [restored](Self::restore),
+/// optionally also when the program panics.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::io;
+/// # #[cfg(feature = "events")]
+/// use crossterm::{event, terminal::TerminalSession};
+///
+/// # #[cfg(feature = "events")]
+/// fn main() -> io::Result<()> {
+///     let session = TerminalSession::builder()
+///         .raw_mode()
+///         .alternate_screen()
+///         .mouse_capture()
+///         .panic_hook()
+///         .enter()?;
+///
+///     let event = event::read()?;
+///
+///     // The terminal is restored when the session is dropped, `restore` reports errors.
+///     session.restore()?;
+///     println!("{event:?}");
+///     Ok(())
+/// }
+/// # #[cfg(not(feature = "events"))]
+/// # fn main() {}
+/// ```
+#[derive(Debug)]
+pub struct TerminalSession {
+    // The enabled modes, which are taken when they are restored. They are shared with the panic
+    // hook.
+    modes: Arc<Mutex<Vec<Mode>>>,
+}
+
+impl TerminalSession {
+    /// Returns a builder to choose the modes of the session.
+    pub fn builder() -> TerminalSessionBuilder {
+        TerminalSessionBuilder::default()
+    }
+
+    fn enable(&self, modes: &[Mode], w: &mut impl io::Write) -> io::Result<()> {
+        for &mode in modes {
+            if let Err(error) = self.enable_mode(mode, w) {
+                self.restore_to(w).ok();
+                return Err(error);
+            }
+        }
+        Ok(())
+    }
+
+    fn enable_mode(&self, mode: Mode, w: &mut impl io::Write) -> io::Result<()> {
+        // A raw mode enabled before is left enabled.
+        if matches!(mode, Mode::Raw(_)) && is_raw_mode_enabled()? {
+            return Ok(());
+        }
+        mode.enable(w)?;
+        self.modes.lock().push(mode);
+        Ok(())
+    }
+
+    fn restore_to(&self, w: &mut impl io::Write) -> io::Result<()> {
+        let modes = std::mem::take(&mut *self.modes.lock());
+        disable_all(modes, w)
+    }
+
+    /// Suspends the program like [`suspend`](super::suspend), with the modes of the session
+    /// disabled while it's stopped.
+    ///
+    /// The modes are enabled again once the program is continued, and an
+    /// [`Event::Resumed`](crate::event::Event::Resumed) is read, which should trigger a redraw.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::io;
+    /// # #[cfg(feature = "events")]
+    /// use crossterm::{
+    ///     event::{self, Event, KeyCode, KeyModifiers},
+    ///     terminal::TerminalSession,
+    /// };
+    ///
+    /// # #[cfg(feature = "events")]
+    /// fn main() -> io::Result<()> {
+    ///     let session = TerminalSession::builder()
+    ///         .raw_mode()
+    ///         .alternate_screen()
+    ///         .enter()?;
+    ///
+    ///     loop {
+    ///         match event::read()? {
+    ///             Event::Key(event)
+    ///                 if event.code == KeyCode::Char('z')
+    ///                     && event.modifiers == KeyModifiers::CONTROL =>
+    ///             {
+    ///                 session.suspend()?
+    ///             }
+    ///             Event::Resumed => println!("Redraw"),
+    ///             Event::Key(event) if event.code == KeyCode::Esc => break,
+    ///             _ => {}
+    ///         }
+    ///     }
+    ///     Ok(())
+    /// }
+    /// # #[cfg(not(feature = "events"))]
+    /// # fn main() {}
+    /// ```
+    #[cfg(unix)]
+    pub fn suspend(&self) -> io::Result<()> {
+        let mut w = io::stdout();
+        let modes = std::mem::take(&mut *self.modes.lock());
+        let result = disable_all(modes.clone(), &mut w).and_then(|()| super::suspend());
+        self.enable(&modes, &mut w)?;
+        result
+    }
+
+    /// Disables the modes enabled by the session in reverse order.
+    ///
+    /// All modes are disabled even if disabling one fails, the first error is returned. Calling it
+    /// again, or dropping the session afterwards, does nothing.
+    pub fn restore(&self) -> io::Result<()> {
+        self.restore_to(&mut io::stdout())
+    }
+}
+
+impl Drop for TerminalSession {
+    fn drop(&mut self) {
+        let _ = self.restore();
+    }
+}
+
+#[cfg(test)]
+#[cfg(unix)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::{Mode, PANIC_HOOK_SESSIONS, TerminalSession, disable_all, register_panic_hook};
+    use crate::event::KeyboardEnhancementFlags;
+    use crate::terminal::RawModeOptions;
+
+    #[test]
+    fn test_session_modes() {
+        let builder = TerminalSession::builder()
+            .keyboard_enhancement(KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES)
+            .focus_change()
+            .alternate_screen()
+            .raw_mode();
+
+        assert_eq!(
+            builder.modes(),
+            vec![
+                Mode::Raw(RawModeOptions::default()),
+                Mode::AlternateScreen,
+                Mode::FocusChange,
+                Mode::KeyboardEnhancement(KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_session_restores_in_reverse_order_once() {
+        let session = TerminalSession {
+            modes: Default::default(),
+        };
+        let mut output = Vec::new();
+        session
+            .enable(
+                &[Mode::AlternateScreen, Mode::HiddenCursor, Mode::FocusChange],
+                &mut output,
+            )
+            .unwrap();
+        assert_eq!(output, b"\x1B[?1049h\x1B[?25l\x1B[?1004h");
+
+        let mut output = Vec::new();
+        session.restore_to(&mut output).unwrap();
+        assert_eq!(output, b"\x1B[?1004l\x1B[?25h\x1B[?1049l");
+
+        let mut output = Vec::new();
+        session.restore_to(&mut output).unwrap();
+        assert!(output.is_empty());
+    }
+
+    #[test]
+    fn test_panic_hook_forgets_ended_sessions() {
+        let ended = Arc::default();
+        register_panic_hook(&ended);
+        drop(ended);
+
+        let live = Arc::default();
+        register_panic_hook(&live);
+        let sessions = PANIC_HOOK_SESSIONS.lock();
+        assert_eq!(sessions.len(), 1);
+        assert!(Arc::ptr_eq(&sessions[0].upgrade().unwrap(), &live));
+    }
+
+    #[test]
+    fn test_restore_continues_after_errors() {
+        struct CountingWriter(usize);
+
+        impl std::io::Write for CountingWriter {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                self.0 += 1;
+                Err(std::io::Error::other(format!("closed: {buf:?}")))
+            }
+
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let mut writer = CountingWriter(0);
+        let modes = vec![Mode::AlternateScreen, Mode::FocusChange];
+        assert!(disable_all(modes, &mut writer).is_err());
+        assert_eq!(writer.0, 2);
+    }
+}
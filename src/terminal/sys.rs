@@ -1,11 +1,14 @@
 //! This module provides platform related functions.
 
+#[cfg(all(unix, test, feature = "events"))]
+pub(crate) use self::unix::open_pty;
 #[cfg(unix)]
 #[cfg(feature = "events")]
 pub use self::unix::supports_keyboard_enhancement;
 #[cfg(unix)]
 pub(crate) use self::unix::{
-    disable_raw_mode, enable_raw_mode, is_raw_mode_enabled, size, window_size,
+    RawMode, disable_raw_mode, enable_raw_mode, enable_raw_mode_with, is_raw_mode_enabled,
+    set_echo, size, suspend, window_size, window_size_of,
 };
 #[cfg(windows)]
 #[cfg(feature = "events")]
@@ -14,8 +17,8 @@ pub use self::windows::supports_keyboard_enhancement;
 pub(crate) use self::windows::temp_screen_buffer;
 #[cfg(windows)]
 pub(crate) use self::windows::{
-    clear, disable_raw_mode, enable_raw_mode, is_raw_mode_enabled, scroll_down, scroll_up,
-    set_size, set_window_title, size, window_size,
+    clear, disable_raw_mode, enable_raw_mode, enable_raw_mode_with, is_raw_mode_enabled,
+    scroll_down, scroll_up, set_echo, set_size, set_window_title, size, window_size,
 };
 
 #[cfg(windows)]
@@ -8,9 +8,12 @@ use rustix::fd::{AsFd, AsRawFd, BorrowedFd, OwnedFd, RawFd};
 use std::{
     fs,
     marker::PhantomData,
-    os::unix::{
-        io::{IntoRawFd, RawFd},
-        prelude::AsRawFd,
+    os::{
+        fd::{AsFd, BorrowedFd},
+        unix::{
+            io::{IntoRawFd, RawFd},
+            prelude::AsRawFd,
+        },
     },
 };
 
@@ -109,6 +112,14 @@ impl AsRawFd for FileDesc<'_> {
     }
 }
 
+#[cfg(feature = "libc")]
+impl AsFd for FileDesc<'_> {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        // SAFETY: The file descriptor is open as long as the `FileDesc` exists.
+        unsafe { BorrowedFd::borrow_raw(self.fd) }
+    }
+}
+
 #[cfg(not(feature = "libc"))]
 impl AsFd for FileDesc<'_> {
     fn as_fd(&self) -> BorrowedFd<'_> {
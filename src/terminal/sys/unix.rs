@@ -2,35 +2,80 @@
 
 #[cfg(feature = "events")]
 use crate::event::KeyboardEnhancementFlags;
-use crate::terminal::{
-    WindowSize,
-    sys::file_descriptor::{FileDesc, tty_fd},
-};
+use crate::terminal::{RawModeOptions, WindowSize, sys::file_descriptor::tty_fd};
 #[cfg(feature = "libc")]
 use libc::{
-    STDOUT_FILENO, TCSANOW, TIOCGWINSZ, cfmakeraw, ioctl, tcgetattr, tcsetattr, termios as Termios,
-    winsize,
+    TCSANOW, TIOCGWINSZ, cfmakeraw, ioctl, tcgetattr, tcsetattr, termios as Termios, winsize,
 };
 use parking_lot::Mutex;
 #[cfg(not(feature = "libc"))]
-use rustix::{
-    fd::AsFd,
-    termios::{Termios, Winsize},
-};
+use rustix::termios::{InputModes, LocalModes, OutputModes, SpecialCodeIndex, Termios, Winsize};
 
-use std::{fs::File, io, process};
+use std::{fs::File, io, os::fd::AsFd, process};
 #[cfg(feature = "libc")]
-use std::{
-    mem,
-    os::unix::io::{IntoRawFd, RawFd},
-};
+use std::{mem, os::fd::AsRawFd};
 
-// Some(Termios) -> we're in the raw mode and this is the previous mode
-// None -> we're not in the raw mode
-static TERMINAL_MODE_PRIOR_RAW_MODE: Mutex<Option<Termios>> = parking_lot::const_mutex(None);
+/// The raw mode state of a terminal.
+pub(crate) struct RawMode {
+    // Some(Termios) -> we're in the raw mode and this is the previous mode
+    // None -> we're not in the raw mode
+    original_mode: Mutex<Option<Termios>>,
+}
+
+impl RawMode {
+    pub(crate) const fn new() -> RawMode {
+        RawMode {
+            original_mode: parking_lot::const_mutex(None),
+        }
+    }
+
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.original_mode.lock().is_some()
+    }
+
+    pub(crate) fn enable(&self, fd: impl AsFd, options: RawModeOptions) -> io::Result<()> {
+        let mut original_mode = self.original_mode.lock();
+        if original_mode.is_some() {
+            return Ok(());
+        }
+
+        let original_mode_ios = get_terminal_attr(&fd)?;
+        let ios = raw_mode_attr(&original_mode_ios, options);
+        set_terminal_attr(&fd, &ios)?;
+        // Keep it last - set the original mode only if we were able to switch to the raw mode
+        *original_mode = Some(original_mode_ios);
+        Ok(())
+    }
+
+    /// Reset the raw mode.
+    ///
+    /// More precisely, reset the whole termios mode to what it was before the first call
+    /// to [enable](RawMode::enable). If you don't mess with termios outside of crossterm, it's
+    /// effectively disabling the raw mode and doing nothing else.
+    pub(crate) fn disable(&self, fd: impl AsFd) -> io::Result<()> {
+        let mut original_mode = self.original_mode.lock();
+        if let Some(original_mode_ios) = original_mode.as_ref() {
+            set_terminal_attr(&fd, original_mode_ios)?;
+            // Keep it last - remove the original mode only if we were able to switch back
+            *original_mode = None;
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Debug for RawMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RawMode")
+            .field("enabled", &self.is_enabled())
+            .finish()
+    }
+}
+
+// The raw mode state of the terminal used by the free functions, see `tty_fd`.
+static TERMINAL_MODE_PRIOR_RAW_MODE: RawMode = RawMode::new();
 
 pub(crate) fn is_raw_mode_enabled() -> bool {
-    TERMINAL_MODE_PRIOR_RAW_MODE.lock().is_some()
+    TERMINAL_MODE_PRIOR_RAW_MODE.is_enabled()
 }
 
 #[cfg(feature = "libc")]
@@ -56,9 +101,17 @@ impl From<Winsize> for WindowSize {
     }
 }
 
+pub(crate) fn window_size() -> io::Result<WindowSize> {
+    match File::open("/dev/tty") {
+        Ok(file) => window_size_of(file),
+        // Fallback to the standard output if /dev/tty is missing
+        Err(_) => window_size_of(io::stdout()),
+    }
+}
+
 #[allow(clippy::useless_conversion)]
 #[cfg(feature = "libc")]
-pub(crate) fn window_size() -> io::Result<WindowSize> {
+pub(crate) fn window_size_of(fd: impl AsFd) -> io::Result<WindowSize> {
     // http://rosettacode.org/wiki/Terminal_control/Dimensions#Library:_BSD_libc
     let mut size = winsize {
         ws_row: 0,
@@ -67,30 +120,12 @@ pub(crate) fn window_size() -> io::Result<WindowSize> {
         ws_ypixel: 0,
     };
 
-    let file = File::open("/dev/tty").map(|file| FileDesc::new(file.into_raw_fd(), true));
-    let fd = if let Ok(file) = &file {
-        file.raw_fd()
-    } else {
-        // Fallback to libc::STDOUT_FILENO if /dev/tty is missing
-        STDOUT_FILENO
-    };
-
-    if wrap_with_result(unsafe { ioctl(fd, TIOCGWINSZ.into(), &mut size) }).is_ok() {
-        return Ok(size.into());
-    }
-
-    Err(std::io::Error::last_os_error().into())
+    wrap_with_result(unsafe { ioctl(fd.as_fd().as_raw_fd(), TIOCGWINSZ.into(), &mut size) })?;
+    Ok(size.into())
 }
 
 #[cfg(not(feature = "libc"))]
-pub(crate) fn window_size() -> io::Result<WindowSize> {
-    let file = File::open("/dev/tty").map(|file| FileDesc::Owned(file.into()));
-    let fd = if let Ok(file) = &file {
-        file.as_fd()
-    } else {
-        // Fallback to libc::STDOUT_FILENO if /dev/tty is missing
-        rustix::stdio::stdout()
-    };
+pub(crate) fn window_size_of(fd: impl AsFd) -> io::Result<WindowSize> {
     let size = rustix::termios::tcgetwinsize(fd)?;
     Ok(size.into())
 }
@@ -104,68 +139,116 @@ pub(crate) fn size() -> io::Result<(u16, u16)> {
     tput_size().ok_or_else(|| std::io::Error::last_os_error().into())
 }
 
-#[cfg(feature = "libc")]
 pub(crate) fn enable_raw_mode() -> io::Result<()> {
-    let mut original_mode = TERMINAL_MODE_PRIOR_RAW_MODE.lock();
-    if original_mode.is_some() {
-        return Ok(());
-    }
+    enable_raw_mode_with(RawModeOptions::default())
+}
 
-    let tty = tty_fd()?;
-    let fd = tty.raw_fd();
-    let mut ios = get_terminal_attr(fd)?;
-    let original_mode_ios = ios;
-    raw_terminal_attr(&mut ios);
-    set_terminal_attr(fd, &ios)?;
-    // Keep it last - set the original mode only if we were able to switch to the raw mode
-    *original_mode = Some(original_mode_ios);
-    Ok(())
+pub(crate) fn enable_raw_mode_with(options: RawModeOptions) -> io::Result<()> {
+    TERMINAL_MODE_PRIOR_RAW_MODE.enable(tty_fd()?, options)
 }
 
-#[cfg(not(feature = "libc"))]
-pub(crate) fn enable_raw_mode() -> io::Result<()> {
-    let mut original_mode = TERMINAL_MODE_PRIOR_RAW_MODE.lock();
-    if original_mode.is_some() {
-        return Ok(());
+pub(crate) fn disable_raw_mode() -> io::Result<()> {
+    TERMINAL_MODE_PRIOR_RAW_MODE.disable(tty_fd()?)
+}
+
+#[cfg(feature = "libc")]
+pub(crate) fn set_echo(enabled: bool) -> io::Result<()> {
+    let tty = tty_fd()?;
+    let mut ios = get_terminal_attr(&tty)?;
+    if enabled {
+        ios.c_lflag |= libc::ECHO;
+    } else {
+        ios.c_lflag &= !libc::ECHO;
     }
+    set_terminal_attr(&tty, &ios)
+}
 
+#[cfg(not(feature = "libc"))]
+pub(crate) fn set_echo(enabled: bool) -> io::Result<()> {
     let tty = tty_fd()?;
     let mut ios = get_terminal_attr(&tty)?;
-    let original_mode_ios = ios.clone();
-    ios.make_raw();
-    set_terminal_attr(&tty, &ios)?;
-    // Keep it last - set the original mode only if we were able to switch to the raw mode
-    *original_mode = Some(original_mode_ios);
-    Ok(())
+    ios.local_modes.set(LocalModes::ECHO, enabled);
+    set_terminal_attr(&tty, &ios)
 }
 
-/// Reset the raw mode.
+/// Stops the process group like Ctrl+Z does in a shell.
 ///
-/// More precisely, reset the whole termios mode to what it was before the first call
-/// to [enable_raw_mode]. If you don't mess with termios outside of crossterm, it's
-/// effectively disabling the raw mode and doing nothing else.
+/// If the raw mode is enabled, the termios saved by [enable_raw_mode] is applied while the process
+/// is stopped, and the raw mode termios is re-applied once it's continued.
+pub(crate) fn suspend() -> io::Result<()> {
+    let original_mode = TERMINAL_MODE_PRIOR_RAW_MODE.original_mode.lock();
+    let Some(original_mode_ios) = original_mode.as_ref() else {
+        return stop_process_group();
+    };
+
+    let tty = tty_fd()?;
+    let raw_mode_ios = get_terminal_attr(&tty)?;
+    set_terminal_attr(&tty, original_mode_ios)?;
+    let result = stop_process_group();
+    set_terminal_attr(&tty, &raw_mode_ios)?;
+    result
+}
+
 #[cfg(feature = "libc")]
-pub(crate) fn disable_raw_mode() -> io::Result<()> {
-    let mut original_mode = TERMINAL_MODE_PRIOR_RAW_MODE.lock();
-    if let Some(original_mode_ios) = original_mode.as_ref() {
-        let tty = tty_fd()?;
-        set_terminal_attr(tty.raw_fd(), original_mode_ios)?;
-        // Keep it last - remove the original mode only if we were able to switch back
-        *original_mode = None;
-    }
+fn stop_process_group() -> io::Result<()> {
+    // The signal is delivered before `kill` returns, which is after the process was continued.
+    wrap_with_result(unsafe { libc::kill(0, libc::SIGTSTP) })
+}
+
+#[cfg(not(feature = "libc"))]
+fn stop_process_group() -> io::Result<()> {
+    // The signal is delivered before `kill` returns, which is after the process was continued.
+    rustix::process::kill_current_process_group(rustix::process::Signal::TSTP)?;
     Ok(())
 }
 
+// Transform the given mode into the raw mode (non-canonical) variant chosen by the options.
+#[cfg(feature = "libc")]
+fn raw_mode_attr(original: &Termios, options: RawModeOptions) -> Termios {
+    let mut ios = *original;
+    if options.cbreak {
+        ios.c_iflag &= !libc::ICRNL;
+        ios.c_lflag &= !(libc::ICANON | libc::ECHO);
+        ios.c_cc[libc::VMIN] = 1;
+        ios.c_cc[libc::VTIME] = 0;
+    } else {
+        raw_terminal_attr(&mut ios);
+        if options.keep_signals {
+            ios.c_lflag |= original.c_lflag & libc::ISIG;
+        }
+        if options.keep_output_processing {
+            ios.c_oflag |= original.c_oflag & libc::OPOST;
+        }
+    }
+    if options.keep_echo {
+        ios.c_lflag |= original.c_lflag & libc::ECHO;
+    }
+    ios
+}
+
+// Transform the given mode into the raw mode (non-canonical) variant chosen by the options.
 #[cfg(not(feature = "libc"))]
-pub(crate) fn disable_raw_mode() -> io::Result<()> {
-    let mut original_mode = TERMINAL_MODE_PRIOR_RAW_MODE.lock();
-    if let Some(original_mode_ios) = original_mode.as_ref() {
-        let tty = tty_fd()?;
-        set_terminal_attr(&tty, original_mode_ios)?;
-        // Keep it last - remove the original mode only if we were able to switch back
-        *original_mode = None;
+fn raw_mode_attr(original: &Termios, options: RawModeOptions) -> Termios {
+    let mut ios = original.clone();
+    if options.cbreak {
+        ios.input_modes.remove(InputModes::ICRNL);
+        ios.local_modes
+            .remove(LocalModes::ICANON | LocalModes::ECHO);
+        ios.special_codes[SpecialCodeIndex::VMIN] = 1;
+        ios.special_codes[SpecialCodeIndex::VTIME] = 0;
+    } else {
+        ios.make_raw();
+        if options.keep_signals {
+            ios.local_modes |= original.local_modes & LocalModes::ISIG;
+        }
+        if options.keep_output_processing {
+            ios.output_modes |= original.output_modes & OutputModes::OPOST;
+        }
     }
-    Ok(())
+    if options.keep_echo {
+        ios.local_modes |= original.local_modes & LocalModes::ECHO;
+    }
+    ios
 }
 
 #[cfg(not(feature = "libc"))]
@@ -195,28 +278,7 @@ pub fn supports_keyboard_enhancement() -> io::Result<bool> {
 /// [`crossterm::event::read`](crate::event::read) or [`crossterm::event::poll`](crate::event::poll) are being called.
 #[cfg(feature = "events")]
 pub fn query_keyboard_enhancement_flags() -> io::Result<Option<KeyboardEnhancementFlags>> {
-    if is_raw_mode_enabled() {
-        query_keyboard_enhancement_flags_raw()
-    } else {
-        query_keyboard_enhancement_flags_nonraw()
-    }
-}
-
-#[cfg(feature = "events")]
-fn query_keyboard_enhancement_flags_nonraw() -> io::Result<Option<KeyboardEnhancementFlags>> {
-    enable_raw_mode()?;
-    let flags = query_keyboard_enhancement_flags_raw();
-    disable_raw_mode()?;
-    flags
-}
-
-#[cfg(feature = "events")]
-fn query_keyboard_enhancement_flags_raw() -> io::Result<Option<KeyboardEnhancementFlags>> {
-    use crate::event::{
-        filter::{KeyboardEnhancementFlagsFilter, PrimaryDeviceAttributesFilter},
-        internal::{self, InternalEvent},
-    };
-    use std::io::Write;
+    use crate::event::{filter::KeyboardEnhancementFlagsFilter, internal::InternalEvent};
     use std::time::Duration;
 
     // This is the recommended method for testing support for the keyboard enhancement protocol.
@@ -227,44 +289,17 @@ fn query_keyboard_enhancement_flags_raw() -> io::Result<Option<KeyboardEnhanceme
     // See <https://sw.kovidgoyal.net/kitty/keyboard-protocol/#detection-of-support-for-this-protocol>
 
     // ESC [ ? u        Query progressive keyboard enhancement flags (kitty protocol).
-    // ESC [ c          Query primary device attributes.
-    const QUERY: &[u8] = b"\x1B[?u\x1B[c";
-
-    let result = File::options()
-        .write(true)
-        .open("/dev/tty")
-        .and_then(|mut file| {
-            file.write_all(QUERY)?;
-            file.flush()
-        });
-    if result.is_err() {
-        let mut stdout = io::stdout();
-        stdout.write_all(QUERY)?;
-        stdout.flush()?;
-    }
-
-    loop {
-        match internal::poll(
-            Some(Duration::from_millis(2000)),
-            &KeyboardEnhancementFlagsFilter,
-        ) {
-            Ok(true) => {
-                match internal::read(&KeyboardEnhancementFlagsFilter) {
-                    Ok(InternalEvent::KeyboardEnhancementFlags(current_flags)) => {
-                        // Flush the PrimaryDeviceAttributes out of the event queue.
-                        internal::read(&PrimaryDeviceAttributesFilter).ok();
-                        return Ok(Some(current_flags));
-                    }
-                    _ => return Ok(None),
-                }
-            }
-            Ok(false) => {
-                return Err(io::Error::other(
-                    "The keyboard enhancement status could not be read within a normal duration",
-                ));
-            }
-            Err(_) => {}
-        }
+    match crate::terminal::query::execute(
+        b"\x1B[?u",
+        &KeyboardEnhancementFlagsFilter,
+        Duration::from_millis(2000),
+        true,
+    )? {
+        Some(InternalEvent::KeyboardEnhancementFlags(current_flags)) => Ok(Some(current_flags)),
+        Some(_) => Ok(None),
+        None => Err(io::Error::other(
+            "The keyboard enhancement status could not be read within a normal duration",
+        )),
     }
 }
 
@@ -301,17 +336,17 @@ fn raw_terminal_attr(termios: &mut Termios) {
 }
 
 #[cfg(feature = "libc")]
-fn get_terminal_attr(fd: RawFd) -> io::Result<Termios> {
+fn get_terminal_attr(fd: impl AsFd) -> io::Result<Termios> {
     unsafe {
         let mut termios = mem::zeroed();
-        wrap_with_result(tcgetattr(fd, &mut termios))?;
+        wrap_with_result(tcgetattr(fd.as_fd().as_raw_fd(), &mut termios))?;
         Ok(termios)
     }
 }
 
 #[cfg(feature = "libc")]
-fn set_terminal_attr(fd: RawFd, termios: &Termios) -> io::Result<()> {
-    wrap_with_result(unsafe { tcsetattr(fd, TCSANOW, termios) })
+fn set_terminal_attr(fd: impl AsFd, termios: &Termios) -> io::Result<()> {
+    wrap_with_result(unsafe { tcsetattr(fd.as_fd().as_raw_fd(), TCSANOW, termios) })
 }
 
 #[cfg(feature = "libc")]
@@ -322,3 +357,45 @@ fn wrap_with_result(result: i32) -> io::Result<()> {
         Ok(())
     }
 }
+
+/// Opens a pseudoterminal and returns its `(master, slave)`.
+#[cfg(test)]
+pub(crate) fn open_pty() -> io::Result<(File, File)> {
+    use rustix::{
+        fs::{Mode, OFlags},
+        pty::{OpenptFlags, grantpt, openpt, ptsname, unlockpt},
+    };
+
+    let master = openpt(OpenptFlags::RDWR | OpenptFlags::NOCTTY)?;
+    grantpt(&master)?;
+    unlockpt(&master)?;
+    let slave = rustix::fs::open(
+        ptsname(&master, Vec::new())?,
+        OFlags::RDWR | OFlags::NOCTTY,
+        Mode::empty(),
+    )?;
+    Ok((File::from(master), File::from(slave)))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Read, Write};
+
+    use super::{RawMode, open_pty};
+    use crate::terminal::RawModeOptions;
+
+    #[test]
+    fn test_cbreak_reads_carriage_return() {
+        let (mut terminal, mut tty) = open_pty().unwrap();
+        RawMode::new()
+            .enable(&tty, RawModeOptions::new().cbreak())
+            .unwrap();
+
+        // Enter sends a carriage return, which is decoded as Enter instead of CTRL+J only if the
+        // terminal doesn't translate it to a newline.
+        terminal.write_all(b"\r").unwrap();
+        let mut input = [0; 1];
+        tty.read_exact(&mut input).unwrap();
+        assert_eq!(&input, b"\r");
+    }
+}
@@ -11,7 +11,7 @@ use winapi::{
 
 use crate::{
     cursor,
-    terminal::{ClearType, WindowSize},
+    terminal::{ClearType, RawModeOptions, WindowSize},
 };
 
 /// bits which can't be set in raw mode
@@ -23,17 +23,27 @@ pub(crate) fn is_raw_mode_enabled() -> std::io::Result<bool> {
     let dw_mode = console_mode.mode()?;
 
     Ok(
-        // check none of the "not raw" bits is set
-        dw_mode & NOT_RAW_MODE_MASK == 0,
+        // check the input isn't line buffered, Ctrl+C may still be processed with the
+        // `RawModeOptions`
+        dw_mode & ENABLE_LINE_INPUT == 0,
     )
 }
 
 pub(crate) fn enable_raw_mode() -> std::io::Result<()> {
+    enable_raw_mode_with(RawModeOptions::default())
+}
+
+pub(crate) fn enable_raw_mode_with(options: RawModeOptions) -> std::io::Result<()> {
     let console_mode = ConsoleMode::from(Handle::current_in_handle()?);
 
     let dw_mode = console_mode.mode()?;
 
-    let new_mode = dw_mode & !NOT_RAW_MODE_MASK;
+    // The echo requires line input, and there's no output processing to turn off.
+    let mut mask = NOT_RAW_MODE_MASK;
+    if options.cbreak || options.keep_signals {
+        mask &= !ENABLE_PROCESSED_INPUT;
+    }
+    let new_mode = dw_mode & !mask;
 
     console_mode.set_mode(new_mode)?;
 
@@ -52,6 +62,22 @@ pub(crate) fn disable_raw_mode() -> std::io::Result<()> {
     Ok(())
 }
 
+pub(crate) fn set_echo(enabled: bool) -> std::io::Result<()> {
+    let console_mode = ConsoleMode::from(Handle::current_in_handle()?);
+
+    let dw_mode = console_mode.mode()?;
+
+    let new_mode = if enabled {
+        dw_mode | ENABLE_ECHO_INPUT
+    } else {
+        dw_mode & !ENABLE_ECHO_INPUT
+    };
+
+    console_mode.set_mode(new_mode)?;
+
+    Ok(())
+}
+
 pub(crate) fn size() -> io::Result<(u16, u16)> {
     let terminal_size = ScreenBuffer::current()?.info()?.terminal_size();
     // windows starts counting at 0, unix at 1, add one to replicated unix behaviour.
@@ -0,0 +1,377 @@
+#[cfg(all(feature = "events", feature = "libc"))]
+use std::os::fd::IntoRawFd;
+use std::{
+    fmt,
+    fs::{File, OpenOptions},
+    io::{self, Read, Write},
+    os::fd::{AsFd, AsRawFd, BorrowedFd, OwnedFd, RawFd},
+    path::Path,
+    sync::Arc,
+};
+#[cfg(feature = "events")]
+use std::{
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+
+#[cfg(feature = "events")]
+use parking_lot::{MappedMutexGuard, Mutex, MutexGuard};
+
+use crate::terminal::{
+    RawModeOptions, WindowSize,
+    sys::{RawMode, window_size_of},
+};
+#[cfg(feature = "events")]
+use crate::{
+    event::{
+        Event,
+        filter::{EventFilter, Filter},
+        internal::InternalEvent,
+        read::InternalEventReader,
+        source::unix::UnixInternalEventSource,
+    },
+    terminal::{query::Terminal, sys::file_descriptor::FileDesc},
+};
+
+/// A handle to a terminal, e.g. the slave of a pseudoterminal.
+///
+/// The functions of the [terminal](crate::terminal), [cursor](crate::cursor) and
+/// [event](crate::event) modules operate on the terminal of the process, which is its standard
+/// input or `/dev/tty`. A `Tty` offers the same for any terminal, with its own raw mode and event
+/// reader, so one process can drive several terminals. Commands are written to it with the
+/// [`execute!`](crate::execute) and [`queue!`](crate::queue) macros.
+///
+/// The input is decoded with the state of this handle: a newline is the Enter key unless its raw
+/// mode is enabled, and mouse positions are pixels if
+/// [set_mouse_pixel_reporting](Tty::set_mouse_pixel_reporting) says so. Resize and resume events
+/// aren't reported, because the signals are only sent for the controlling terminal of the process,
+/// poll [window_size](Tty::window_size) to follow the size instead.
+///
+/// The raw mode is disabled when the handle is dropped. Only available on UNIX.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::io;
+/// # #[cfg(feature = "events")]
+/// use crossterm::{
+///     cursor::MoveTo,
+///     execute,
+///     style::Print,
+///     terminal::{Clear, ClearType, Tty},
+/// };
+///
+/// # #[cfg(feature = "events")]
+/// fn main() -> io::Result<()> {
+///     let mut tty = Tty::open("/dev/pts/3")?;
+///     tty.enable_raw_mode()?;
+///
+///     let (columns, rows) = tty.size()?;
+///     execute!(
+///         tty,
+///         Clear(ClearType::All),
+///         MoveTo(0, 0),
+///         Print(format!("{columns}x{rows}"))
+///     )?;
+///
+///     let event = tty.read_event()?;
+///     println!("{event:?}");
+///     Ok(())
+/// }
+/// # #[cfg(not(feature = "events"))]
+/// # fn main() {}
+/// ```
+pub struct Tty {
+    state: Arc<TtyState>,
+    // Created by the first event read.
+    #[cfg(feature = "events")]
+    reader: Mutex<Option<InternalEventReader>>,
+}
+
+/// The state of a [`Tty`], shared with its event source to decode the input.
+#[derive(Debug)]
+pub(crate) struct TtyState {
+    file: File,
+    raw_mode: RawMode,
+    #[cfg(feature = "events")]
+    mouse_pixel_reporting: AtomicBool,
+}
+
+#[cfg(feature = "events")]
+impl TtyState {
+    /// Tells whether a newline is the Enter key, which is the case unless the raw mode is enabled.
+    pub(crate) fn newline_is_enter(&self) -> bool {
+        !self.raw_mode.is_enabled()
+    }
+
+    /// Returns the cell size mouse positions in pixels are converted with, or `None` if they are
+    /// reported in cells.
+    pub(crate) fn mouse_cell_size(&self) -> Option<(u16, u16)> {
+        self.mouse_pixel_reporting
+            .load(Ordering::Relaxed)
+            .then(|| window_size_of(&self.file).map_or((0, 0), |size| size.cell_size()))
+    }
+}
+
+impl Tty {
+    /// Opens the terminal at the path for reading and writing, e.g. `/dev/pts/3`.
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Tty> {
+        let file = OpenOptions::new().read(true).write(true).open(path)?;
+        Ok(Tty::from_fd(file))
+    }
+
+    /// Wraps the file descriptor of a terminal, which has to be open for reading and writing.
+    pub fn from_fd(fd: impl Into<OwnedFd>) -> Tty {
+        Tty {
+            state: Arc::new(TtyState {
+                file: File::from(fd.into()),
+                raw_mode: RawMode::new(),
+                #[cfg(feature = "events")]
+                mouse_pixel_reporting: AtomicBool::new(false),
+            }),
+            #[cfg(feature = "events")]
+            reader: Mutex::new(None),
+        }
+    }
+
+    /// Tells whether the raw mode of this terminal is enabled, see
+    /// [is_raw_mode_enabled](super::is_raw_mode_enabled).
+    pub fn is_raw_mode_enabled(&self) -> bool {
+        self.state.raw_mode.is_enabled()
+    }
+
+    /// Enables the raw mode of this terminal, see [enable_raw_mode](super::enable_raw_mode).
+    pub fn enable_raw_mode(&self) -> io::Result<()> {
+        self.enable_raw_mode_with(RawModeOptions::default())
+    }
+
+    /// Enables a variant of the raw mode of this terminal, see
+    /// [enable_raw_mode_with](super::enable_raw_mode_with).
+    pub fn enable_raw_mode_with(&self, options: RawModeOptions) -> io::Result<()> {
+        self.state.raw_mode.enable(&self.state.file, options)
+    }
+
+    /// Disables the raw mode of this terminal, see [disable_raw_mode](super::disable_raw_mode).
+    pub fn disable_raw_mode(&self) -> io::Result<()> {
+        self.state.raw_mode.disable(&self.state.file)
+    }
+
+    /// Returns the size `(columns, rows)` of this terminal, see [size](super::size).
+    pub fn size(&self) -> io::Result<(u16, u16)> {
+        let window_size = self.window_size()?;
+        Ok((window_size.columns, window_size.rows))
+    }
+
+    /// Returns the size of this terminal, see [window_size](super::window_size).
+    pub fn window_size(&self) -> io::Result<WindowSize> {
+        window_size_of(&self.state.file)
+    }
+
+    /// Returns the cursor position `(column, row)` in this terminal, see
+    /// [position](crate::cursor::position).
+    ///
+    /// The raw mode is enabled while waiting for the answer, unless it's enabled already. Events
+    /// read meanwhile are kept for [read_event](Tty::read_event).
+    #[cfg(feature = "events")]
+    pub fn cursor_position(&self) -> io::Result<(u16, u16)> {
+        crate::cursor::sys::unix::position_of(self)
+    }
+
+    /// Tells the event reader of this terminal whether mouse positions are reported in pixels,
+    /// i.e. whether [`EnableMousePixelReporting`](crate::event::EnableMousePixelReporting) was
+    /// written to it. Disabled by default.
+    #[cfg(feature = "events")]
+    pub fn set_mouse_pixel_reporting(&self, enabled: bool) {
+        self.state
+            .mouse_pixel_reporting
+            .store(enabled, Ordering::Relaxed);
+    }
+
+    /// Checks if there is an [`Event`] available from this terminal, see
+    /// [poll](crate::event::poll).
+    #[cfg(feature = "events")]
+    pub fn poll_event(&self, timeout: Duration) -> io::Result<bool> {
+        self.reader()?.poll(Some(timeout), &EventFilter)
+    }
+
+    /// Reads a single [`Event`] from this terminal, see [read](crate::event::read).
+    #[cfg(feature = "events")]
+    pub fn read_event(&self) -> io::Result<Event> {
+        match self.reader()?.read(&EventFilter)?.into_event() {
+            Some(event) => Ok(event),
+            None => unreachable!(),
+        }
+    }
+
+    #[cfg(feature = "events")]
+    fn reader(&self) -> io::Result<MappedMutexGuard<'_, InternalEventReader>> {
+        let mut reader = self.reader.lock();
+        if reader.is_none() {
+            let file = self.state.file.try_clone()?;
+            #[cfg(feature = "libc")]
+            let fd = FileDesc::new(file.into_raw_fd(), true);
+            #[cfg(not(feature = "libc"))]
+            let fd = FileDesc::Owned(file.into());
+            let source = UnixInternalEventSource::for_tty(fd, self.state.clone())?;
+            *reader = Some(InternalEventReader::with_source(Box::new(source)));
+        }
+        Ok(MutexGuard::map(reader, |reader| {
+            reader.as_mut().expect("reader initialized above")
+        }))
+    }
+}
+
+#[cfg(feature = "events")]
+impl Terminal for Tty {
+    fn is_raw_mode_enabled(&self) -> bool {
+        Tty::is_raw_mode_enabled(self)
+    }
+
+    fn enable_raw_mode(&self) -> io::Result<()> {
+        Tty::enable_raw_mode(self)
+    }
+
+    fn disable_raw_mode(&self) -> io::Result<()> {
+        Tty::disable_raw_mode(self)
+    }
+
+    fn write_request(&self, request: &[u8]) -> io::Result<()> {
+        (&self.state.file).write_all(request)?;
+        (&self.state.file).flush()
+    }
+
+    fn poll(&self, timeout: Option<Duration>, filter: &impl Filter) -> io::Result<bool> {
+        self.reader()?.poll(timeout, filter)
+    }
+
+    fn read(&self, filter: &impl Filter) -> io::Result<InternalEvent> {
+        self.reader()?.read(filter)
+    }
+}
+
+impl Drop for Tty {
+    fn drop(&mut self) {
+        let _ = self.disable_raw_mode();
+    }
+}
+
+impl fmt::Debug for Tty {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Tty")
+            .field("fd", &self.state.file.as_raw_fd())
+            .field("raw_mode", &self.is_raw_mode_enabled())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Read for Tty {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        (&self.state.file).read(buf)
+    }
+}
+
+impl Write for Tty {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        (&self.state.file).write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        (&self.state.file).flush()
+    }
+}
+
+impl AsFd for Tty {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.state.file.as_fd()
+    }
+}
+
+impl AsRawFd for Tty {
+    fn as_raw_fd(&self) -> RawFd {
+        self.state.file.as_raw_fd()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{self, Write},
+        os::unix::net::UnixStream,
+    };
+
+    use super::Tty;
+    use crate::{cursor::MoveTo, queue};
+
+    #[test]
+    fn test_tty_writes_commands() {
+        let (tty, mut terminal) = UnixStream::pair().unwrap();
+        let mut tty = Tty::from_fd(tty);
+
+        queue!(tty, MoveTo(1, 2)).unwrap();
+        tty.flush().unwrap();
+        let mut output = [0; 6];
+        io::Read::read_exact(&mut terminal, &mut output).unwrap();
+        assert_eq!(&output, b"\x1B[3;2H");
+    }
+
+    #[test]
+    fn test_tty_raw_mode_requires_terminal() {
+        let (tty, _terminal) = UnixStream::pair().unwrap();
+        let tty = Tty::from_fd(tty);
+
+        assert!(tty.enable_raw_mode().is_err());
+        assert!(!tty.is_raw_mode_enabled());
+        assert!(tty.size().is_err());
+    }
+
+    #[cfg(feature = "events")]
+    #[test]
+    fn test_tty_reads_events() {
+        use std::time::Duration;
+
+        use crate::event::{Event, KeyCode};
+
+        let (tty, mut terminal) = UnixStream::pair().unwrap();
+        tty.set_nonblocking(true).unwrap();
+        let tty = Tty::from_fd(tty);
+
+        assert!(!tty.poll_event(Duration::ZERO).unwrap());
+        terminal.write_all(b"ab").unwrap();
+        assert!(tty.poll_event(Duration::from_secs(1)).unwrap());
+        assert_eq!(
+            tty.read_event().unwrap(),
+            Event::Key(KeyCode::Char('a').into())
+        );
+        assert_eq!(
+            tty.read_event().unwrap(),
+            Event::Key(KeyCode::Char('b').into())
+        );
+    }
+
+    #[cfg(feature = "events")]
+    #[test]
+    fn test_tty_decodes_newline_with_its_raw_mode() {
+        use std::time::Duration;
+
+        use crate::{
+            event::{Event, KeyCode, KeyEvent, KeyModifiers},
+            terminal::sys::open_pty,
+        };
+
+        let (mut terminal, tty) = open_pty().unwrap();
+        let tty = Tty::from_fd(tty);
+
+        tty.enable_raw_mode().unwrap();
+        terminal.write_all(b"\n").unwrap();
+        assert!(tty.poll_event(Duration::from_secs(1)).unwrap());
+        assert_eq!(
+            tty.read_event().unwrap(),
+            Event::Key(KeyEvent::new(KeyCode::Char('j'), KeyModifiers::CONTROL))
+        );
+
+        tty.disable_raw_mode().unwrap();
+        terminal.write_all(b"\n").unwrap();
+        assert!(tty.poll_event(Duration::from_secs(1)).unwrap());
+        assert_eq!(tty.read_event().unwrap(), Event::Key(KeyCode::Enter.into()));
+    }
+}
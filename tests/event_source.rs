@@ -0,0 +1,76 @@
+//! The event source is global, so these tests live in their own test binary and run serially.
+#![cfg(feature = "events")]
+
+use std::{io, time::Duration};
+
+use crossterm::event::{self, Event, EventSource, FakeTerminal, KeyCode, KeyEvent, KeyModifiers};
+use serial_test::serial;
+
+struct Script(Vec<Event>);
+
+impl EventSource for Script {
+    fn try_read(&mut self, _timeout: Option<Duration>) -> io::Result<Option<Event>> {
+        Ok(if self.0.is_empty() {
+            None
+        } else {
+            Some(self.0.remove(0))
+        })
+    }
+}
+
+#[test]
+#[serial]
+fn custom_source_drives_read_and_poll() {
+    event::set_event_source(Script(vec![
+        Event::FocusGained,
+        Event::Key(KeyCode::Char('q').into()),
+    ]));
+
+    assert!(event::poll(Duration::ZERO).unwrap());
+    assert_eq!(event::read().unwrap(), Event::FocusGained);
+    // `try_read` only returns events that were already polled from the source.
+    assert!(event::poll(Duration::ZERO).unwrap());
+    assert_eq!(
+        event::try_read(),
+        Some(Event::Key(KeyCode::Char('q').into()))
+    );
+    assert!(!event::poll(Duration::ZERO).unwrap());
+    assert_eq!(event::try_read(), None);
+
+    event::reset_event_source();
+}
+
+#[test]
+#[serial]
+fn replacing_the_source_discards_pending_events() {
+    event::set_event_source(Script(vec![Event::FocusGained]));
+    assert!(event::poll(Duration::ZERO).unwrap());
+
+    event::set_event_source(Script(vec![Event::FocusLost]));
+    assert_eq!(event::read().unwrap(), Event::FocusLost);
+
+    event::reset_event_source();
+}
+
+#[test]
+#[serial]
+fn fake_terminal_produces_terminal_events() {
+    let terminal = FakeTerminal::new();
+    terminal.install();
+
+    terminal.input(b"\x1B[1;2D");
+    terminal.resize(120, 40);
+    #[cfg(feature = "bracketed-paste")]
+    terminal.input(b"\x1B[200~pasted\x1B[201~");
+
+    assert_eq!(
+        event::read().unwrap(),
+        Event::Key(KeyEvent::new(KeyCode::Left, KeyModifiers::SHIFT))
+    );
+    assert_eq!(event::read().unwrap(), Event::Resize(120, 40));
+    #[cfg(feature = "bracketed-paste")]
+    assert_eq!(event::read().unwrap(), Event::Paste("pasted".to_string()));
+    assert!(!event::poll(Duration::from_millis(10)).unwrap());
+
+    event::reset_event_source();
+}